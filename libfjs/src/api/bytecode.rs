@@ -1,10 +1,13 @@
 //! QuickJS module bytecode utilities.
 
 use crate::api::error::JsError;
+use crate::api::module::module_source_code;
 use crate::api::source::{
-    JsModule, JsModuleBytecode, JsModuleBytecodeBundle, JsModuleBytecodeOptions, JsScriptBytecode,
-    JsScriptBytecodeOptions, get_raw_source_code, get_raw_source_code_sync,
+    JsBytecodeBundleFileOptions, JsModule, JsModuleBytecode, JsModuleBytecodeBundle,
+    JsModuleBytecodeOptions, JsScriptBytecode, JsScriptBytecodeOptions, get_raw_source_code,
+    get_raw_source_code_sync,
 };
+use crate::bytecode_bundle::{bundle_verifying_key, pack_module_bundle, unpack_module_bundle};
 use crate::bytecode_support::{
     compile_module_bundle_impl, compile_module_bytecode_impl, compile_script_bytecode_impl,
     validate_module_bundle_impl, validate_module_bytecode_impl, validate_script_bytecode_impl,
@@ -53,8 +56,9 @@ impl JsBytecode {
     ) -> Result<JsModuleBytecodeBundle, JsError> {
         let mut resolved_modules = Vec::with_capacity(modules.len());
         for module in modules {
-            let JsModule { name, source } = module;
+            let JsModule { name, source, kind } = module;
             let source_code = get_raw_source_code_sync(source)?;
+            let source_code = module_source_code(&name, kind, &source_code)?;
             resolved_modules.push((name, source_code));
         }
         compile_module_bundle_impl(entry, resolved_modules, options.unwrap_or_default())
@@ -90,13 +94,169 @@ impl JsBytecode {
     ) -> Result<JsModuleBytecodeBundle, JsError> {
         let mut resolved_modules = Vec::with_capacity(modules.len());
         for module in modules {
-            let JsModule { name, source } = module;
+            let JsModule { name, source, kind } = module;
             let source_code = get_raw_source_code(source).await?;
+            let source_code = module_source_code(&name, kind, &source_code)?;
             resolved_modules.push((name, source_code));
         }
         compile_module_bundle_impl(entry, resolved_modules, options.unwrap_or_default())
     }
 
+    /// Compiles a set of ES modules into a single-file bytecode bundle synchronously.
+    ///
+    /// This is `compileModuleBundleSync(...)` followed by `packBundleSync(...)`,
+    /// with the file's recorded byte order taken from `options`.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final file = JsBytecode.compileModuleBundleFileSync(
+    ///   modules: [
+    ///     JsModule.code(module: 'feature/index', code: 'export default 42;'),
+    ///   ],
+    ///   entry: 'feature/index',
+    /// );
+    /// File('feature.fjsb').writeAsBytesSync(file);
+    /// ```
+    #[frb(sync)]
+    pub fn compile_module_bundle_file_sync(
+        modules: Vec<JsModule>,
+        entry: Option<String>,
+        options: Option<JsModuleBytecodeOptions>,
+        file_options: Option<JsBytecodeBundleFileOptions>,
+    ) -> Result<Vec<u8>, JsError> {
+        let options = options.unwrap_or_default();
+        let file_options = JsBytecodeBundleFileOptions {
+            endianness: options.endianness,
+            ..file_options.unwrap_or_default()
+        };
+        let bundle = Self::compile_module_bundle_sync(modules, entry, Some(options))?;
+        pack_module_bundle(&bundle, file_options)
+    }
+
+    /// Compiles a set of ES modules into a single-file bytecode bundle.
+    ///
+    /// The file carries the QuickJS version, byte order, entry module, and a
+    /// SHA-256 checksum per module, with optional zstd/brotli compression and
+    /// an optional Ed25519 signature. Load it with
+    /// `engine.evaluateBytecodeBundleFile(...)` or `JsBytecode.unpackBundle(...)`.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final file = await JsBytecode.compileModuleBundleFile(
+    ///   modules: [
+    ///     JsModule.code(module: 'feature/index', code: 'export { answer } from "./shared";'),
+    ///     JsModule.code(module: 'feature/shared', code: 'export const answer = 42;'),
+    ///   ],
+    ///   entry: 'feature/index',
+    ///   fileOptions: JsBytecodeBundleFileOptions.defaults().copyWith(
+    ///     compression: JsBytecodeCompression.zstd,
+    ///     signingKey: signingSeed,
+    ///   ),
+    /// );
+    /// ```
+    pub async fn compile_module_bundle_file(
+        modules: Vec<JsModule>,
+        entry: Option<String>,
+        options: Option<JsModuleBytecodeOptions>,
+        file_options: Option<JsBytecodeBundleFileOptions>,
+    ) -> Result<Vec<u8>, JsError> {
+        let options = options.unwrap_or_default();
+        let file_options = JsBytecodeBundleFileOptions {
+            endianness: options.endianness,
+            ..file_options.unwrap_or_default()
+        };
+        let bundle = Self::compile_module_bundle(modules, entry, Some(options)).await?;
+        pack_module_bundle(&bundle, file_options)
+    }
+
+    /// Packs an existing bytecode bundle into the single-file format synchronously.
+    ///
+    /// `options.endianness` must describe how the bundle was compiled.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final file = JsBytecode.packBundleSync(bundle: compiledBundle);
+    /// ```
+    #[frb(sync)]
+    pub fn pack_bundle_sync(
+        bundle: JsModuleBytecodeBundle,
+        options: Option<JsBytecodeBundleFileOptions>,
+    ) -> Result<Vec<u8>, JsError> {
+        pack_module_bundle(&bundle, options.unwrap_or_default())
+    }
+
+    /// Packs an existing bytecode bundle into the single-file format.
+    ///
+    /// `options.endianness` must describe how the bundle was compiled.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final file = await JsBytecode.packBundle(bundle: compiledBundle);
+    /// ```
+    pub async fn pack_bundle(
+        bundle: JsModuleBytecodeBundle,
+        options: Option<JsBytecodeBundleFileOptions>,
+    ) -> Result<Vec<u8>, JsError> {
+        pack_module_bundle(&bundle, options.unwrap_or_default())
+    }
+
+    /// Reads a single-file bytecode bundle synchronously.
+    ///
+    /// Fails when the file was produced for a different QuickJS version or
+    /// byte order, or when any module checksum does not match. When
+    /// `verifyingKey` is provided, the file must also carry a valid Ed25519
+    /// signature from the matching private key.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final bundle = JsBytecode.unpackBundleSync(
+    ///   bytes: File('feature.fjsb').readAsBytesSync(),
+    ///   verifyingKey: publisherKey,
+    /// );
+    /// ```
+    #[frb(sync)]
+    pub fn unpack_bundle_sync(
+        bytes: Vec<u8>,
+        verifying_key: Option<Vec<u8>>,
+    ) -> Result<JsModuleBytecodeBundle, JsError> {
+        unpack_module_bundle(&bytes, verifying_key.as_deref())
+    }
+
+    /// Reads a single-file bytecode bundle.
+    ///
+    /// See `unpackBundleSync(...)` for the checks performed.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final bundle = await JsBytecode.unpackBundle(bytes: downloadedBytes);
+    /// ```
+    pub async fn unpack_bundle(
+        bytes: Vec<u8>,
+        verifying_key: Option<Vec<u8>>,
+    ) -> Result<JsModuleBytecodeBundle, JsError> {
+        unpack_module_bundle(&bytes, verifying_key.as_deref())
+    }
+
+    /// Returns the Ed25519 public key for a 32-byte bundle signing seed.
+    ///
+    /// Ship the public key with the app and keep the seed on the build machine.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final publicKey = JsBytecode.bundleVerifyingKey(signingKey: signingSeed);
+    /// ```
+    #[frb(sync)]
+    pub fn bundle_verifying_key(signing_key: Vec<u8>) -> Result<Vec<u8>, JsError> {
+        bundle_verifying_key(&signing_key)
+    }
+
     /// Compiles an ES module into QuickJS bytecode synchronously.
     ///
     /// This variant may block the caller while reading module source from disk.
@@ -120,8 +280,10 @@ impl JsBytecode {
         let JsModule {
             name: module_name,
             source,
+            kind,
         } = module;
         let source_code = get_raw_source_code_sync(source)?;
+        let source_code = module_source_code(&module_name, kind, &source_code)?;
         compile_module_bytecode_impl(&module_name, source_code, options.unwrap_or_default())
     }
 
@@ -148,8 +310,10 @@ impl JsBytecode {
         let JsModule {
             name: module_name,
             source,
+            kind,
         } = module;
         let source_code = get_raw_source_code(source).await?;
+        let source_code = module_source_code(&module_name, kind, &source_code)?;
         compile_module_bytecode_impl(&module_name, source_code, options.unwrap_or_default())
     }
 
@@ -0,0 +1,179 @@
+//! # Code Coverage
+//!
+//! Types returned by `JsEngine.coverage()`.
+//!
+//! When coverage is enabled, JavaScript modules declared from source are
+//! instrumented with statement and function counters as they load. A report
+//! can be exported as Istanbul JSON (`coverage-final.json`, readable by `nyc`
+//! and `istanbul-lib-report`) or as an LCOV tracefile for `genhtml` and most
+//! CI coverage services.
+
+use crate::api::module::js_string_literal;
+use flutter_rust_bridge::frb;
+use std::collections::BTreeMap;
+
+/// Execution count of one instrumented statement.
+///
+/// Lines are one-based and columns zero-based, matching Istanbul.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsStatementCoverage {
+    /// Line the statement starts on.
+    pub line: u32,
+    /// Column the statement starts at.
+    pub column: u32,
+    /// Number of times the statement was reached.
+    pub hits: u64,
+}
+
+/// Call count of one function.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsFunctionCoverage {
+    /// Function name, or `(anonymous_N)` when it has none.
+    pub name: String,
+    /// Line of the function body's opening brace.
+    pub line: u32,
+    /// Column of the function body's opening brace.
+    pub column: u32,
+    /// Number of times the function was called.
+    pub hits: u64,
+}
+
+/// Coverage of one module.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsModuleCoverage {
+    /// Module name.
+    pub module: String,
+    /// Instrumented statements in source order.
+    pub statements: Vec<JsStatementCoverage>,
+    /// Functions with block bodies in source order.
+    pub functions: Vec<JsFunctionCoverage>,
+}
+
+impl JsModuleCoverage {
+    /// Returns `(line, hits)` pairs for every line that starts a statement,
+    /// using the highest count of the statements on that line.
+    #[frb(sync)]
+    pub fn line_hits(&self) -> Vec<(u32, u64)> {
+        let mut lines: BTreeMap<u32, u64> = BTreeMap::new();
+        for statement in &self.statements {
+            let hits = lines.entry(statement.line).or_default();
+            *hits = (*hits).max(statement.hits);
+        }
+        lines.into_iter().collect()
+    }
+}
+
+/// Coverage collected by an engine.
+///
+/// ## Example
+///
+/// ```dart
+/// final engine = await JsEngine.create(
+///   runtimeOptions: JsEngineRuntimeOptions(coverage: true),
+/// );
+/// await engine.initWithoutBridge();
+/// await engine.declareNewModule(module: JsModule.code(module: 'cart', code: cartSource));
+/// await engine.call(module: 'cart', method: 'total', params: [JsValue.from(items)]);
+/// final report = await engine.coverage();
+/// await File('coverage/lcov.info').writeAsString(report.toLcov());
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsCoverageReport {
+    /// Per-module coverage, sorted by module name.
+    pub modules: Vec<JsModuleCoverage>,
+}
+
+impl JsCoverageReport {
+    /// Serializes the report in Istanbul's `coverage-final.json` format.
+    ///
+    /// Only statement and function counters are recorded, so `branchMap` is
+    /// always empty.
+    #[frb(sync)]
+    pub fn to_istanbul_json(&self) -> String {
+        let location = |line: u32, column: u32| {
+            format!(
+                "{{\"start\":{{\"line\":{line},\"column\":{column}}},\"end\":{{\"line\":{line},\"column\":{column}}}}}"
+            )
+        };
+        let entries: Vec<String> = self
+            .modules
+            .iter()
+            .map(|module| {
+                let path = js_string_literal(&module.module);
+                let statement_map: Vec<String> = module
+                    .statements
+                    .iter()
+                    .enumerate()
+                    .map(|(id, s)| format!("\"{id}\":{}", location(s.line, s.column)))
+                    .collect();
+                let fn_map: Vec<String> = module
+                    .functions
+                    .iter()
+                    .enumerate()
+                    .map(|(id, f)| {
+                        let loc = location(f.line, f.column);
+                        format!(
+                            "\"{id}\":{{\"name\":{},\"decl\":{loc},\"loc\":{loc},\"line\":{}}}",
+                            js_string_literal(&f.name),
+                            f.line
+                        )
+                    })
+                    .collect();
+                let s: Vec<String> = module
+                    .statements
+                    .iter()
+                    .enumerate()
+                    .map(|(id, s)| format!("\"{id}\":{}", s.hits))
+                    .collect();
+                let f: Vec<String> = module
+                    .functions
+                    .iter()
+                    .enumerate()
+                    .map(|(id, f)| format!("\"{id}\":{}", f.hits))
+                    .collect();
+                format!(
+                    "{path}:{{\"path\":{path},\"statementMap\":{{{}}},\"fnMap\":{{{}}},\"branchMap\":{{}},\"s\":{{{}}},\"f\":{{{}}},\"b\":{{}}}}",
+                    statement_map.join(","),
+                    fn_map.join(","),
+                    s.join(","),
+                    f.join(",")
+                )
+            })
+            .collect();
+        format!("{{{}}}", entries.join(","))
+    }
+
+    /// Serializes the report as an LCOV tracefile.
+    #[frb(sync)]
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        for module in &self.modules {
+            lcov.push_str(&format!("TN:\nSF:{}\n", module.module));
+            for function in &module.functions {
+                lcov.push_str(&format!("FN:{},{}\n", function.line, function.name));
+            }
+            for function in &module.functions {
+                lcov.push_str(&format!("FNDA:{},{}\n", function.hits, function.name));
+            }
+            let functions_hit = module.functions.iter().filter(|f| f.hits > 0).count();
+            lcov.push_str(&format!(
+                "FNF:{}\nFNH:{functions_hit}\n",
+                module.functions.len()
+            ));
+            let lines = module.line_hits();
+            for (line, hits) in &lines {
+                lcov.push_str(&format!("DA:{line},{hits}\n"));
+            }
+            let lines_hit = lines.iter().filter(|(_, hits)| *hits > 0).count();
+            lcov.push_str(&format!(
+                "LF:{}\nLH:{lines_hit}\nend_of_record\n",
+                lines.len()
+            ));
+        }
+        lcov
+    }
+}
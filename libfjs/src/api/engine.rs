@@ -7,45 +7,94 @@
 //!
 //! The engine provides direct async methods:
 //! - `eval()` - Evaluate JavaScript code
+//! - `eval_isolated()` - Evaluate an untrusted snippet in a fresh, frozen context
 //! - `declare_new_module()` - Register a module without executing
 //! - `declare_new_modules()` - Register multiple modules
-//! - `evaluate_module()` - Register and execute a module
+//! - `evaluate_module()` - Register and execute a module, optionally returning its exports
+//! - `module_exports()` - List a module's export names and kinds
+//! - `reload_module()` - Replace a module and re-evaluate it with its dependents
 //! - `declare_new_bytecode_module()` - Register precompiled module bytecode
 //! - `evaluate_bytecode_module()` - Register and execute precompiled module bytecode
+//! - `evaluate_bytecode_bundle_file()` - Verify and execute a single-file bytecode bundle
 //! - `evaluate_script_bytecode()` - Execute precompiled classic script bytecode
 //! - `call()` - Call a function in a module
 //! - `clear_pending_modules()` - Clear dynamic modules that have not been loaded yet
 //! - `get_declared_modules()` - Get all module names
 //! - `get_available_modules()` - Get builtin and dynamic module names
+//! - `module_graph()` - Inspect modules with their imports, exports, and status
 //! - `is_module_declared()` - Check if a module exists
 //! - `is_module_available()` - Check if a builtin or dynamic module exists
-
-use crate::api::error::{JsError, JsResult};
+//! - `unhandled_errors()` - Stream unhandled rejections and timer errors
+//! - `metrics()` - Read latency histograms and driver counters
+//! - `memory_events()` - Stream soft-limit, hard-limit, and memory pressure events
+//! - `notify_memory_pressure()` - Reclaim memory when the OS reports pressure
+//! - `heap_snapshot()` - Summarize reachable objects per constructor
+//! - `export_heap_snapshot()` - Export the reachable heap as a Chrome `.heapsnapshot`
+//! - `start_profiling()` / `stop_profiling()` - Sample the JavaScript call stack
+//! - `coverage()` / `reset_coverage()` - Read statement and function coverage
+//! - `run_tests()` - Run `node:test` tests registered by a module
+//! - `create_realm()` - Create an isolated context sharing the engine's runtime
+//! - `pause()` / `resume()` - Suspend all background work with timers frozen
+//! - `run_pending_jobs()` - Pump the event loop when the background driver is disabled
+//! - `advance_time()` / `set_time()` - Drive the virtual clock in deterministic mode
+//! - `start_recording()` / `stop_recording()` / `replay()` - Record and replay bridge traffic
+//! - `call_batch()` - Call several module functions in one round trip
+//! - `invoke()` - Call a function by path, as a constructor, or with an explicit `this`
+//! - `eval_with_trace_context()` / `call_with_trace_context()` - Carry a trace context through async JavaScript
+//! - `init_with_traced_bridge()` - Receive the trace context with each bridge call
+
+use crate::api::coverage::JsCoverageReport;
+use crate::api::error::{JsError, JsResult, JsUnhandledError, JsUnhandledErrorPolicy};
+use crate::api::heap::JsHeapSnapshot;
 use crate::api::module::{
-    DynamicModuleEntry, DynamicModuleStorage, get_loaded_dynamic_module_names,
-    is_dynamic_module_loaded, mark_dynamic_module_loaded,
+    DynamicModuleEntry, DynamicModuleSnapshot, DynamicModuleStorage, JsModuleExport, JsModuleInfo,
+    collect_module_graph, declare_data_module, describe_exports, dynamic_module_dependents,
+    get_loaded_dynamic_module_names, invalidate_dynamic_module, is_dynamic_module_loaded,
+    is_module_loaded, mark_dynamic_module_loaded, namespace_value, record_module_errored,
+    record_module_evaluated,
 };
+use crate::api::profiler::JsCpuProfile;
+use crate::api::realm::{JsRealm, JsRealmOptions};
+use crate::api::recording::{JsRecordedEvent, JsRecording, JsReplayStep};
 use crate::api::runtime::{
-    JsAsyncContext, JsAsyncRuntime, MemoryUsage, call_module_method, result_from_maybe_promise,
+    JsAsyncContext, JsAsyncRuntime, JsMemoryEvent, JsMemoryEventKind, JsMemoryPressureLevel,
+    MemoryUsage, ModuleMethodCall, call_module_method, call_module_method_traced,
+    call_module_methods, import_module_namespace, invoke_function, result_from_maybe_promise,
     result_from_promise,
 };
 use crate::api::source::{
     JsBuiltinOptions, JsCode, JsEvalOptions, JsModule, JsModuleBytecode, JsModuleBytecodeBundle,
-    JsScriptBytecode, get_raw_source_code,
+    JsModuleEvalOptions, JsModuleKind, JsScriptBytecode, get_raw_source_code,
 };
+use crate::api::testing::JsTestReport;
 use crate::api::value::JsValue;
+use crate::bytecode_bundle::unpack_module_bundle;
 use crate::bytecode_support::{
     eval_script_bytecode, load_module_bytecode_checked, validate_module_bundle_impl,
     validate_module_bytecode_impl, validate_script_bytecode_impl,
 };
+use crate::frb_generated::StreamSink;
+use crate::heap_snapshot::{HeapGraph, HeapSizes};
+use crate::runtime::bindings::{bind_source, unbind};
+use crate::runtime::bridge_limits::BridgeLimits;
+use crate::runtime::coverage::instrument_module_source;
+use crate::runtime::metrics::RuntimeMetrics;
+use crate::runtime::module_cache::{ModuleBytecodeCache, declare_cached_module};
+use crate::runtime::recorder::{outcome_of, outcome_of_batch, recorded_source, replay_bridge};
+use crate::test_runner::{RUN_EXPORT, TEST_MODULE_NAME, parse_results};
 use flutter_rust_bridge::{DartFnFuture, frb};
 use rquickjs::{CatchResultExt, FromJs, Module, Object, Promise};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 /// Type alias for the bridge callback function.
-pub type BridgeCallback = dyn Fn(JsValue) -> DartFnFuture<JsResult> + Sync + Send + 'static;
+///
+/// The second argument is the trace context current when JavaScript made the
+/// call, if any.
+pub type BridgeCallback =
+    dyn Fn(JsValue, Option<JsValue>) -> DartFnFuture<JsResult> + Sync + Send + 'static;
 
 /// Runtime configuration applied when constructing a high-level `JsEngine`.
 #[frb(dart_metadata = ("freezed"))]
@@ -68,6 +117,322 @@ pub struct JsEngineRuntimeOptions {
     ///
     /// The label is metadata for diagnostics and does not affect execution semantics.
     pub info: Option<String>,
+    /// Opt-in cache for bytecode compiled from declared source modules.
+    ///
+    /// `None` disables caching and parses module source on every load.
+    pub module_cache: Option<JsModuleCache>,
+    /// Whether unhandled background errors fail the next engine call.
+    ///
+    /// Defaults to [`JsUnhandledErrorPolicy::Poison`].
+    pub unhandled_error_policy: Option<JsUnhandledErrorPolicy>,
+    /// Soft limit, in bytes, for QuickJS allocator usage.
+    ///
+    /// Crossing it runs a GC pass and emits a
+    /// [`JsMemoryEventKind::SoftLimitExceeded`] event. Set it below
+    /// `memory_limit` to react before allocations start failing.
+    pub soft_memory_limit: Option<usize>,
+    /// Whether to record statement and function coverage.
+    ///
+    /// JavaScript modules declared from source after the engine is created
+    /// are instrumented as they load; read the counters with `coverage()`.
+    /// Instrumentation slows execution down, so enable it for test runs only.
+    pub coverage: Option<bool>,
+    /// Whether the host pumps the event loop with `runPendingJobs()`.
+    ///
+    /// By default a background driver runs timers, Promise callbacks, and
+    /// bridge replies as soon as they are ready. With this set, nothing runs
+    /// between engine calls until the host pumps the loop, which makes
+    /// scheduling deterministic for frame-driven hosts and replayable tests.
+    pub manual_event_loop: Option<bool>,
+    /// Runs the engine on a virtual clock with seeded randomness.
+    ///
+    /// `None` uses real time and unseeded `Math.random`.
+    pub deterministic: Option<JsDeterministicOptions>,
+    /// Timeout and concurrency limits for `fjs.bridge_call()`.
+    ///
+    /// `None` lets calls wait for Dart indefinitely, without a limit on how
+    /// many are in flight.
+    pub bridge: Option<JsBridgeOptions>,
+    /// Whether to carry trace contexts through async JavaScript.
+    ///
+    /// Enables `evalWithTraceContext()` / `callWithTraceContext()` and the
+    /// `traceContext` global, and passes the current context to bridges
+    /// installed with `initWithTracedBridge()`. Tracking every Promise has a
+    /// small cost, so it is off by default.
+    pub trace_context: Option<bool>,
+    /// Whether errors include the chain of `await`s that led to them.
+    ///
+    /// Error stacks gain one `--- await ---` section per suspended caller,
+    /// ending at the `eval`, `call`, or module import that started the async
+    /// work. Every Promise captures a stack trace, so enable it while
+    /// debugging only.
+    pub async_stack_traces: Option<bool>,
+}
+
+/// Limits applied to `fjs.bridge_call()` in the engine and its realms.
+///
+/// A call can override the timeout with a second argument:
+/// `fjs.bridge_call(value, { timeoutMs: 500 })`. Timed-out and rejected calls
+/// reject the JavaScript promise; a Dart reply that arrives later is dropped.
+///
+/// ## Example
+///
+/// ```dart
+/// final engine = await JsEngine.create(
+///   runtimeOptions: JsEngineRuntimeOptions(
+///     bridge: JsBridgeOptions(
+///       timeoutMs: BigInt.from(5000),
+///       maxInFlight: 64,
+///       onOverflow: JsBridgeOverflowPolicy.reject,
+///     ),
+///   ),
+/// );
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JsBridgeOptions {
+    /// Default time, in milliseconds, a call may take, including time spent
+    /// queued. `None` waits indefinitely.
+    pub timeout_ms: Option<u64>,
+    /// Maximum number of calls waiting on Dart at once. `None` is unlimited.
+    pub max_in_flight: Option<u32>,
+    /// What happens to calls made while `max_in_flight` calls are pending.
+    ///
+    /// Defaults to [`JsBridgeOverflowPolicy::Queue`].
+    pub on_overflow: Option<JsBridgeOverflowPolicy>,
+}
+
+/// How `fjs.bridge_call()` handles calls beyond the in-flight limit.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsBridgeOverflowPolicy {
+    /// Wait for an in-flight call to finish.
+    #[default]
+    Queue,
+    /// Reject the call immediately.
+    Reject,
+}
+
+/// Settings for deterministic execution.
+///
+/// `Date`, `performance.now()`, `Math.random()`, and the timers of the
+/// engine context are replaced, both the globals and the exports of the
+/// `timers` module: time only moves when Dart calls `advanceTime()`, and
+/// timers fire in deadline order as virtual time passes them. `setImmediate`
+/// callbacks are due at the current virtual time, so they run on the next
+/// `advanceTime()`, even `advanceTime(ms: 0)`. A run then depends only on the
+/// seed and the sequence of engine calls. Realms and isolated evaluation keep
+/// using real time.
+///
+/// ## Example
+///
+/// ```dart
+/// final engine = await JsEngine.create(
+///   builtins: JsBuiltinOptions.essential(),
+///   runtimeOptions: JsEngineRuntimeOptions(
+///     deterministic: JsDeterministicOptions(seed: BigInt.from(42)),
+///   ),
+/// );
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JsDeterministicOptions {
+    /// Seed for `Math.random()`.
+    pub seed: u64,
+    /// Initial virtual time, in milliseconds since the Unix epoch.
+    ///
+    /// `None` starts the clock at the epoch.
+    pub start_time_ms: Option<i64>,
+}
+
+/// One call in a `callBatch()` request.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsBatchCall {
+    /// The module name to import.
+    pub module: String,
+    /// The exported function to call.
+    pub method: String,
+    /// Optional parameters to pass to the function.
+    pub params: Option<Vec<JsValue>>,
+}
+
+/// A function to run with `invoke()`.
+///
+/// ## Example
+///
+/// ```dart
+/// final user = await engine.invoke(
+///   invocation: JsInvocation(
+///     module: 'api',
+///     path: 'default.users.get',
+///     params: [JsValue.integer(7)],
+///   ),
+/// );
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JsInvocation {
+    /// Module whose exports `path` starts from; `None` starts from
+    /// `globalThis`.
+    pub module: Option<String>,
+    /// Dotted property path to the function, such as `users.get`.
+    ///
+    /// Use `default` to reach a module's default export.
+    pub path: String,
+    /// Optional parameters to pass to the function.
+    pub params: Option<Vec<JsValue>>,
+    /// Whether to call the function as a constructor, with `new`.
+    pub construct: Option<bool>,
+    /// The `this` value for the call. Defaults to [`JsThisBinding::Owner`].
+    pub this_binding: Option<JsThisBinding>,
+}
+
+/// The `this` value an invoked function receives.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum JsThisBinding {
+    /// The object the function was read from, as in `api.users.get()`.
+    #[default]
+    Owner,
+    /// A value converted from Dart.
+    Value(JsValue),
+    /// Another dotted path, resolved from the same module or `globalThis`.
+    Path(String),
+}
+
+/// Outcome of one `runPendingJobs()` turn.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsPendingJobsReport {
+    /// Number of jobs run during this turn.
+    pub jobs_run: u64,
+    /// Whether queued jobs, timers, fetches, or bridge calls remain.
+    pub has_pending_work: bool,
+    /// Milliseconds until the earliest `setTimeout`/`setInterval` timer is
+    /// due, `0` if one is already overdue, or `None` when no timer is armed.
+    /// In deterministic mode this is measured on the virtual clock.
+    pub next_timer_delay_ms: Option<u64>,
+}
+
+/// Storage for the compiled-module cache.
+///
+/// JavaScript modules declared from source are compiled once and stored as
+/// QuickJS bytecode keyed by a SHA-256 hash of the module name, its source,
+/// the embedded QuickJS version, the rquickjs revision, and the bytecode
+/// format version. Each entry also carries a SHA-256 hash of its bytecode,
+/// checked before the bytecode is loaded. Later loads of the same source
+/// reuse the bytecode instead of parsing again. When the source or engine
+/// changes, or an entry is corrupted, it is discarded and replaced on the
+/// next load.
+///
+/// Rust embedders can keep entries elsewhere by implementing
+/// [`JsModuleCacheStore`] and installing it with
+/// [`JsEngine::set_module_cache_store`].
+///
+/// ## Example
+///
+/// ```dart
+/// final cacheDir = await getApplicationCacheDirectory();
+/// final engine = await JsEngine.create(
+///   runtimeOptions: JsEngineRuntimeOptions(
+///     moduleCache: JsModuleCache.directory('${cacheDir.path}/fjs-modules'),
+///   ),
+/// );
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JsModuleCache {
+    /// Keep compiled modules in memory for the lifetime of the engine.
+    Memory,
+    /// Persist compiled modules as files in this directory.
+    Directory(String),
+}
+
+/// Storage backend for the compiled-module cache.
+///
+/// Entries are opaque blobs addressed by a stable id derived from the module
+/// name. The engine verifies each blob's cache key and bytecode hash before
+/// loading it, so a store only has to hand back what it was given; returning
+/// a stale or damaged blob costs a recompile, never a bad load. Methods are
+/// called on the engine thread while a module loads, so keep them fast and
+/// make writes best effort.
+#[frb(ignore)]
+pub trait JsModuleCacheStore: Send + Sync {
+    /// Returns the entry stored under `id`, if any.
+    fn get(&self, id: &str) -> Option<Vec<u8>>;
+    /// Stores `entry` under `id`, replacing any previous entry.
+    fn put(&self, id: &str, entry: Vec<u8>);
+    /// Removes the entry stored under `id`.
+    fn remove(&self, id: &str);
+    /// Releases entries held in memory under memory pressure.
+    ///
+    /// Stores backed by durable storage can keep their entries; the default
+    /// does nothing.
+    fn trim(&self) {}
+}
+
+/// One bucket of a [`JsHistogram`].
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsHistogramBucket {
+    /// Inclusive upper bound of the bucket; the last bucket is unbounded.
+    pub upper_bound: u64,
+    /// Number of samples that fell into this bucket.
+    pub count: u64,
+}
+
+/// A histogram of recorded samples.
+///
+/// Each bucket counts only the samples that fell into it, not the samples
+/// of the buckets below it; sum the counts for a cumulative view.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsHistogram {
+    /// Number of samples recorded.
+    pub count: u64,
+    /// Sum of all samples.
+    pub sum: u64,
+    /// Largest sample recorded.
+    pub max: u64,
+    /// Per-bucket sample counts, ordered by upper bound.
+    pub buckets: Vec<JsHistogramBucket>,
+}
+
+/// Execution metrics collected since the engine was created.
+///
+/// Latencies are in microseconds and measured from the host side, so they
+/// include time spent waiting for the JavaScript context lock.
+///
+/// ## Example
+///
+/// ```dart
+/// final metrics = await engine.metrics();
+/// final evals = metrics.evalLatencyUs;
+/// print('evals: ${evals.count}, mean ${evals.sum ~/ evals.count} us');
+/// print('bridge calls: ${metrics.bridgeCallLatencyUs.count}');
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsEngineMetrics {
+    /// Milliseconds since the engine was created.
+    pub uptime_ms: u64,
+    /// Latency of `eval()` calls.
+    pub eval_latency_us: JsHistogram,
+    /// Latency of `call()` invocations.
+    pub call_latency_us: JsHistogram,
+    /// Latency of `fjs.bridge_call()` round trips to Dart.
+    pub bridge_call_latency_us: JsHistogram,
+    /// Time to resolve and declare imported modules.
+    pub module_load_latency_us: JsHistogram,
+    /// Duration of garbage collection passes run through the engine.
+    pub gc_time_us: JsHistogram,
+    /// Number of times the background driver drained the job queue.
+    pub driver_iterations: u64,
+    /// Number of jobs the background driver executed.
+    pub jobs_executed: u64,
+    /// Jobs run per driver iteration that ran at least one job.
+    pub jobs_per_drain: JsHistogram,
 }
 
 /// Engine state constants
@@ -76,6 +441,11 @@ const STATE_INITIALIZING: u8 = 1;
 const STATE_RUNNING: u8 = 2;
 const STATE_CLOSED: u8 = 3;
 
+/// Default CPU profiler sampling interval, in microseconds.
+const DEFAULT_SAMPLE_INTERVAL_US: u64 = 1_000;
+/// Shortest CPU profiler sampling interval, in microseconds.
+const MIN_SAMPLE_INTERVAL_US: u64 = 50;
+
 /// The JavaScript engine.
 ///
 /// `JsEngine` provides a high-level API for executing JavaScript code,
@@ -105,9 +475,11 @@ pub struct JsEngine {
     state: AtomicU8,
 }
 
-struct JsEngineResources {
-    context: JsAsyncContext,
-    runtime: JsAsyncRuntime,
+pub(crate) struct JsEngineResources {
+    pub(crate) context: JsAsyncContext,
+    pub(crate) runtime: JsAsyncRuntime,
+    pub(crate) bridge_limits: BridgeLimits,
+    pub(crate) trace_context: bool,
 }
 
 impl JsEngine {
@@ -124,7 +496,35 @@ impl JsEngine {
         runtime_options: Option<JsEngineRuntimeOptions>,
     ) -> Result<Self, JsError> {
         let runtime = JsAsyncRuntime::create(builtins, modules).await?;
+        let mut module_cache = None;
+        let mut coverage = false;
+        let mut deterministic = None;
+        let mut bridge_limits = BridgeLimits::default();
+        let mut trace_context = false;
+        let mut async_stack_traces = false;
         if let Some(options) = runtime_options {
+            module_cache = options.module_cache;
+            coverage = options.coverage.unwrap_or(false);
+            deterministic = options.deterministic;
+            trace_context = options.trace_context.unwrap_or(false);
+            async_stack_traces = options.async_stack_traces.unwrap_or(false);
+            if trace_context || async_stack_traces {
+                runtime.install_promise_hook().await;
+            }
+            if let Some(bridge) = &options.bridge {
+                bridge_limits = BridgeLimits::new(bridge);
+            }
+            if options.manual_event_loop.unwrap_or(false) {
+                runtime.driver.set_manual(true);
+                runtime.stop_driver().await;
+            }
+            if let Some(policy) = options.unhandled_error_policy {
+                runtime.driver.set_policy(policy);
+            }
+            runtime
+                .driver
+                .memory()
+                .set_soft_limit(options.soft_memory_limit);
             if let Some(limit) = options.memory_limit {
                 runtime.set_memory_limit(limit).await;
             }
@@ -139,9 +539,43 @@ impl JsEngine {
             }
         }
         let context = JsAsyncContext::from(&runtime).await?;
+        if let Some(cache) = module_cache {
+            context
+                .install_module_cache(ModuleBytecodeCache::new(cache))
+                .await?;
+        }
+        if coverage {
+            context.install_coverage().await?;
+        }
+        if let Some(options) = deterministic {
+            context
+                .with_js(async move |ctx| {
+                    crate::runtime::clock::install(
+                        &ctx,
+                        options.seed,
+                        options.start_time_ms.unwrap_or(0),
+                    )
+                })
+                .await?;
+        }
+        if trace_context {
+            context
+                .with_js(async move |ctx| crate::runtime::trace::install(&ctx))
+                .await?;
+        }
+        if async_stack_traces {
+            context
+                .with_js(async move |ctx| crate::runtime::async_stack::install(&ctx))
+                .await?;
+        }
 
         Ok(Self {
-            resources: RwLock::new(Some(Arc::new(JsEngineResources { runtime, context }))),
+            resources: RwLock::new(Some(Arc::new(JsEngineResources {
+                runtime,
+                context,
+                bridge_limits,
+                trace_context,
+            }))),
             state: AtomicU8::new(STATE_CREATED),
         })
     }
@@ -149,7 +583,12 @@ impl JsEngine {
     #[cfg(test)]
     pub(crate) fn new_for_test(runtime: JsAsyncRuntime, context: JsAsyncContext) -> Self {
         Self {
-            resources: RwLock::new(Some(Arc::new(JsEngineResources { runtime, context }))),
+            resources: RwLock::new(Some(Arc::new(JsEngineResources {
+                runtime,
+                context,
+                bridge_limits: BridgeLimits::default(),
+                trace_context: false,
+            }))),
             state: AtomicU8::new(STATE_CREATED),
         }
     }
@@ -208,6 +647,9 @@ impl JsEngine {
         match self.state.load(Ordering::Acquire) {
             STATE_CREATED => Ok(resources),
             STATE_RUNNING => {
+                if resources.runtime.driver.paused() {
+                    return Err(JsError::engine("Engine is paused"));
+                }
                 self.ensure_no_unhandled_job_errors(&resources.runtime)?;
                 Ok(resources)
             }
@@ -222,6 +664,9 @@ impl JsEngine {
         let resources = self.resources()?;
         match self.state.load(Ordering::Acquire) {
             STATE_RUNNING => {
+                if resources.runtime.driver.paused() {
+                    return Err(JsError::engine("Engine is paused"));
+                }
                 self.ensure_no_unhandled_job_errors(&resources.runtime)?;
                 Ok(resources)
             }
@@ -232,7 +677,17 @@ impl JsEngine {
         }
     }
 
-    fn format_unhandled_job_errors(errors: &[String]) -> String {
+    fn ensure_trace_context(resources: &JsEngineResources) -> Result<(), JsError> {
+        if resources.trace_context {
+            Ok(())
+        } else {
+            Err(JsError::engine(
+                "Trace context is not enabled; set JsEngineRuntimeOptions.traceContext",
+            ))
+        }
+    }
+
+    pub(crate) fn format_unhandled_job_errors(errors: &[String]) -> String {
         format!(
             "Unhandled JavaScript background error: {}",
             errors.join("\n")
@@ -269,7 +724,7 @@ impl JsEngine {
         )
     }
 
-    fn ensure_unique_module_names<'a>(
+    pub(crate) fn ensure_unique_module_names<'a>(
         names: impl IntoIterator<Item = &'a str>,
     ) -> Result<(), JsError> {
         if let Some(duplicate) = first_duplicate_name(names) {
@@ -287,111 +742,18 @@ impl JsEngine {
         &self,
         entries: Vec<(String, DynamicModuleEntry)>,
     ) -> Result<(), JsError> {
-        let single = entries.len() == 1;
-        self.with_foreground_js_result(async move |ctx, _checkpoint| {
-            let conflicts: Vec<_> = entries
-                .iter()
-                .filter(|(name, _)| is_dynamic_module_loaded(&ctx, name))
-                .map(|(name, _)| name.clone())
-                .collect();
-            if let Some(first) = conflicts.first() {
-                return JsResult::Err(if single {
-                    Self::already_loaded_error(first.clone())
-                } else {
-                    JsError::module(
-                        Some(first.clone()),
-                        None,
-                        format!(
-                            "Loaded dynamic modules cannot be redefined in this context: {}",
-                            conflicts.join(", ")
-                        ),
-                    )
-                });
-            }
-            let Some(storage) = ctx.userdata::<DynamicModuleStorage>() else {
-                return JsResult::Err(JsError::storage("Module storage not initialized"));
-            };
-            storage
-                .write()
-                .unwrap_or_else(std::sync::PoisonError::into_inner)
-                .extend(entries);
-            JsResult::Ok(JsValue::None)
-        })
-        .await
-        .map(|_| ())
+        let resources = self.ensure_running()?;
+        declare_dynamic_modules_in(&resources.context, entries).await
     }
 
-    /// Registers a dynamic module and runs its top-level code.
+    /// Registers a dynamic module in the engine context and runs its top-level code.
     async fn evaluate_dynamic_module(
         &self,
         module_name: String,
         entry: DynamicModuleEntry,
     ) -> Result<JsValue, JsError> {
         let resources = self.ensure_running()?;
-        let driver = resources.runtime.driver.clone();
-        let shutdown = resources.runtime.shutdown();
-        resources
-            .context
-            .with_foreground_js_result(async move |ctx, checkpoint| {
-                if is_dynamic_module_loaded(&ctx, &module_name) {
-                    return JsResult::Err(Self::already_loaded_error(module_name));
-                }
-                let Some(storage) = ctx.userdata::<DynamicModuleStorage>() else {
-                    return JsResult::Err(JsError::storage("Module storage not initialized"));
-                };
-
-                let res = match entry {
-                    DynamicModuleEntry::Source(source) => {
-                        storage
-                            .write()
-                            .unwrap_or_else(std::sync::PoisonError::into_inner)
-                            .insert(
-                                module_name.clone(),
-                                DynamicModuleEntry::Source(source.clone()),
-                            );
-                        Module::evaluate(ctx.clone(), module_name.clone(), source)
-                    }
-                    DynamicModuleEntry::Bytecode(bytes) => {
-                        let loaded = load_module_bytecode_checked(
-                            ctx.clone(),
-                            &module_name,
-                            &bytes,
-                        )
-                        .and_then(|module| {
-                            let embedded_name: String = module.name()?;
-                            if embedded_name != module_name {
-                                return Err(rquickjs::Error::new_loading_message(
-                                    module_name.clone(),
-                                    format!(
-                                        "Bytecode module name mismatch: expected '{}', found '{}'",
-                                        module_name, embedded_name
-                                    ),
-                                ));
-                            }
-                            let (_module, promise) = module.eval()?;
-                            Ok(promise)
-                        });
-                        if loaded.is_ok() {
-                            storage
-                                .write()
-                                .unwrap_or_else(std::sync::PoisonError::into_inner)
-                                .insert(module_name.clone(), DynamicModuleEntry::Bytecode(bytes));
-                        }
-                        loaded
-                    }
-                };
-
-                if res.is_ok() {
-                    mark_dynamic_module_loaded(&ctx, &module_name);
-                }
-                let driver = driver.clone();
-                result_from_promise(&ctx, res, shutdown, move |source| {
-                    driver.remove_error_source_since(checkpoint, source);
-                })
-                .await
-            })
-            .await
-            .into_result()
+        evaluate_dynamic_module_in(&resources.context, module_name, entry).await
     }
 
     /// Transitions the engine into the initializing state.
@@ -440,44 +802,796 @@ impl JsEngine {
         Ok(resources.runtime.is_job_pending().await)
     }
 
-    /// Returns memory usage statistics for the engine-owned runtime.
-    pub async fn memory_usage(&self) -> Result<MemoryUsage, JsError> {
-        let resources = self.ensure_runtime_accessible()?;
-        Ok(resources.runtime.memory_usage().await)
+    /// Returns memory usage statistics for the engine-owned runtime.
+    pub async fn memory_usage(&self) -> Result<MemoryUsage, JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        Ok(resources.runtime.memory_usage().await)
+    }
+
+    /// Forces a garbage collection pass on the engine-owned runtime.
+    pub async fn run_gc(&self) -> Result<(), JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        resources.runtime.run_gc().await;
+        Ok(())
+    }
+
+    /// Sets the garbage collection threshold on the engine-owned runtime.
+    pub async fn set_gc_threshold(&self, threshold: usize) -> Result<(), JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        resources.runtime.set_gc_threshold(threshold).await;
+        Ok(())
+    }
+
+    /// Sets runtime metadata on the engine-owned runtime.
+    pub async fn set_info(&self, info: String) -> Result<(), JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        resources.runtime.set_info(info).await
+    }
+
+    /// Sets the max stack size on the engine-owned runtime.
+    pub async fn set_max_stack_size(&self, limit: usize) -> Result<(), JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        resources.runtime.set_max_stack_size(limit).await;
+        Ok(())
+    }
+
+    /// Sets the memory limit on the engine-owned runtime.
+    pub async fn set_memory_limit(&self, limit: usize) -> Result<(), JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        resources.runtime.set_memory_limit(limit).await;
+        Ok(())
+    }
+
+    /// Stores compiled modules in `store` instead of the configured cache.
+    ///
+    /// Replaces any cache set through `JsEngineRuntimeOptions.moduleCache`.
+    /// Only modules loaded afterwards use the new store, so install it before
+    /// declaring or importing modules.
+    #[frb(ignore)]
+    pub async fn set_module_cache_store(
+        &self,
+        store: Arc<dyn JsModuleCacheStore>,
+    ) -> Result<(), JsError> {
+        let resources = self.ensure_runtime_accessible()?;
+        resources
+            .context
+            .install_module_cache(ModuleBytecodeCache::from_store(store))
+            .await
+    }
+
+    /// Returns execution metrics collected since the engine was created.
+    ///
+    /// Metrics remain readable after `close()` until the engine is dropped.
+    pub async fn metrics(&self) -> Result<JsEngineMetrics, JsError> {
+        Ok(self.resources()?.runtime.driver.metrics().snapshot())
+    }
+
+    /// Streams unhandled background errors as they occur.
+    ///
+    /// Each unhandled promise rejection, throwing timer callback, or failed
+    /// background task is delivered once the job queue settles, carrying the
+    /// classified error and its origin. A rejection that gains a handler later
+    /// is not retracted. Multiple listeners may be attached; each receives
+    /// every error raised after it subscribed.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// engine.unhandledErrors().listen((report) {
+    ///   log('background failure: ${report.error}', origin: report.origin);
+    /// });
+    /// ```
+    pub fn unhandled_errors(&self, sink: StreamSink<JsUnhandledError>) -> Result<(), JsError> {
+        let resources = self.resources()?;
+        resources
+            .runtime
+            .driver
+            .add_listener(Arc::new(move |error| sink.add(error).is_ok()));
+        Ok(())
+    }
+
+    /// Returns the current unhandled background error policy.
+    #[frb(sync)]
+    pub fn unhandled_error_policy(&self) -> Result<JsUnhandledErrorPolicy, JsError> {
+        Ok(self.resources()?.runtime.driver.policy())
+    }
+
+    /// Changes whether unhandled background errors fail the next engine call.
+    ///
+    /// Switching to `report` also stops already delivered errors from failing
+    /// the next call.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// engine.setUnhandledErrorPolicy(policy: JsUnhandledErrorPolicy.report);
+    /// ```
+    #[frb(sync)]
+    pub fn set_unhandled_error_policy(
+        &self,
+        policy: JsUnhandledErrorPolicy,
+    ) -> Result<(), JsError> {
+        let resources = self.resources()?;
+        resources.runtime.driver.set_policy(policy);
+        resources.runtime.driver.deliver_errors();
+        Ok(())
+    }
+
+    /// Sets or clears the soft memory limit, in bytes.
+    ///
+    /// The background driver samples allocator usage while it runs; crossing
+    /// the limit triggers a GC pass and a `softLimitExceeded` memory event.
+    #[frb(sync)]
+    pub fn set_soft_memory_limit(&self, limit: Option<usize>) -> Result<(), JsError> {
+        self.resources()?
+            .runtime
+            .driver
+            .memory()
+            .set_soft_limit(limit);
+        Ok(())
+    }
+
+    /// Streams memory events for this engine.
+    ///
+    /// Events are emitted when usage crosses or recovers from the soft limit,
+    /// when a call fails on the hard memory limit, and when
+    /// `notifyMemoryPressure()` runs.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// engine.memoryEvents().listen((event) {
+    ///   if (event.kind == const JsMemoryEventKind.softLimitExceeded()) {
+    ///     pool.shrink();
+    ///   }
+    /// });
+    /// ```
+    pub fn memory_events(&self, sink: StreamSink<JsMemoryEvent>) -> Result<(), JsError> {
+        self.resources()?
+            .runtime
+            .driver
+            .memory()
+            .add_listener(Arc::new(move |event| sink.add(event).is_ok()));
+        Ok(())
+    }
+
+    /// Reclaims memory in response to an operating-system pressure signal.
+    ///
+    /// Runs a GC pass; at [`JsMemoryPressureLevel::Critical`] the in-memory
+    /// compiled-module cache is also cleared. The resulting event is returned
+    /// and delivered to `memoryEvents()` listeners, so pooled engines can be
+    /// shed from the same handler.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// class _Observer extends WidgetsBindingObserver {
+    ///   @override
+    ///   void didHaveMemoryPressure() {
+    ///     engine.notifyMemoryPressure(level: JsMemoryPressureLevel.critical);
+    ///   }
+    /// }
+    /// ```
+    pub async fn notify_memory_pressure(
+        &self,
+        level: JsMemoryPressureLevel,
+    ) -> Result<JsMemoryEvent, JsError> {
+        let resources = self.resources()?;
+        let before = resources.runtime.memory_usage().await.malloc_size();
+        if level == JsMemoryPressureLevel::Critical {
+            resources
+                .context
+                .with_js(async |ctx| {
+                    if let Some(cache) = ctx.userdata::<ModuleBytecodeCache>() {
+                        cache.trim();
+                    }
+                })
+                .await;
+        }
+        resources.runtime.run_gc().await;
+        let after = resources.runtime.memory_usage().await.malloc_size();
+
+        let memory = resources.runtime.driver.memory();
+        let event = memory.event(JsMemoryEventKind::MemoryPressure(level), before, after);
+        memory.emit(event.clone());
+        Ok(event)
+    }
+
+    /// Takes a per-constructor summary of the reachable heap.
+    ///
+    /// A GC pass runs first so that only live objects are counted. The walk
+    /// starts at the global object and the namespaces of loaded modules;
+    /// objects reachable only through closure scopes are not visited. Compare
+    /// two snapshots with `JsHeapSnapshot.diff()` to find what grows.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final snapshot = await engine.heapSnapshot();
+    /// for (final stats in snapshot.classes.take(10)) {
+    ///   print('${stats.name}: ${stats.count} objects, ${stats.retainedSize} bytes retained');
+    /// }
+    /// ```
+    pub async fn heap_snapshot(&self) -> Result<JsHeapSnapshot, JsError> {
+        Ok(self.collect_heap_graph().await?.summary())
+    }
+
+    /// Exports the reachable heap in the Chrome DevTools `.heapsnapshot` format.
+    ///
+    /// The result can be loaded in the Memory panel of Chrome DevTools. It
+    /// covers the same objects as `heapSnapshot()`, with estimated sizes.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final json = await engine.exportHeapSnapshot();
+    /// await File('${dir.path}/engine.heapsnapshot').writeAsString(json);
+    /// ```
+    pub async fn export_heap_snapshot(&self) -> Result<String, JsError> {
+        Ok(self.collect_heap_graph().await?.to_chrome_json())
+    }
+
+    /// Starts sampling the JavaScript call stack.
+    ///
+    /// Samples are taken from the runtime's interrupt handler at most once per
+    /// `sample_interval_us` microseconds (default 1000) while JavaScript is
+    /// running. QuickJS polls that handler after a fixed number of executed
+    /// operations rather than on a timer, so samples land on the first poll
+    /// after each interval. Frames are attributed to the module or script that
+    /// defines the running function. Only one profiling session can run at a
+    /// time.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.startProfiling(sampleIntervalUs: BigInt.from(500));
+    /// await engine.call(module: 'plugin', method: 'render');
+    /// final profile = await engine.stopProfiling();
+    /// print(profile.toFoldedStacks());
+    /// ```
+    pub async fn start_profiling(&self, sample_interval_us: Option<u64>) -> Result<(), JsError> {
+        let resources = self.ensure_running()?;
+        let profiler = resources.runtime.driver.profiler();
+        let interval = Duration::from_micros(
+            sample_interval_us
+                .unwrap_or(DEFAULT_SAMPLE_INTERVAL_US)
+                .max(MIN_SAMPLE_INTERVAL_US),
+        );
+        let started = resources
+            .context
+            .with_js(async move |ctx| profiler.start(ctx.as_raw(), interval))
+            .await;
+        if started {
+            Ok(())
+        } else {
+            Err(JsError::engine("CPU profiling is already running"))
+        }
+    }
+
+    /// Stops the running profiling session and returns its profile.
+    ///
+    /// Function positions refer to public module names, also for modules that
+    /// were reloaded during the session.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final profile = await engine.stopProfiling();
+    /// await File('${dir.path}/plugin.cpuprofile')
+    ///     .writeAsString(profile.toCpuprofileJson());
+    /// ```
+    pub async fn stop_profiling(&self) -> Result<JsCpuProfile, JsError> {
+        self.resources()?
+            .runtime
+            .driver
+            .profiler()
+            .stop()
+            .ok_or_else(|| JsError::engine("CPU profiling is not running"))
+    }
+
+    /// Returns statement and function coverage of the instrumented modules.
+    ///
+    /// Requires `JsEngineRuntimeOptions.coverage`. Counts accumulate from the
+    /// moment each module loads until `resetCoverage()` is called; reloading a
+    /// module starts its counts over.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized, paused, or closed
+    /// - If coverage is not enabled
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.evaluateModule(module: JsModule.code(module: 'suite', code: testSource));
+    /// final report = await engine.coverage();
+    /// await File('coverage/coverage-final.json').writeAsString(report.toIstanbulJson());
+    /// ```
+    pub async fn coverage(&self) -> Result<JsCoverageReport, JsError> {
+        let resources = self.ensure_running()?;
+        resources
+            .context
+            .with_js(async |ctx| {
+                crate::runtime::coverage::collect(&ctx)
+                    .catch(&ctx)
+                    .map_err(|e| JsError::runtime(format!("Failed to read coverage: {e}")))?
+                    .ok_or_else(|| JsError::engine("Coverage is not enabled"))
+            })
+            .await
+    }
+
+    /// Zeroes the coverage counters of every instrumented module.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized, paused, or closed
+    /// - If coverage is not enabled
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.resetCoverage();
+    /// await engine.call(module: 'suite', method: 'runOnly', params: [JsValue.from('cart')]);
+    /// final report = await engine.coverage();
+    /// ```
+    pub async fn reset_coverage(&self) -> Result<(), JsError> {
+        let resources = self.ensure_running()?;
+        let enabled = resources
+            .context
+            .with_js(async |ctx| {
+                crate::runtime::coverage::reset(&ctx)
+                    .catch(&ctx)
+                    .map_err(|e| JsError::runtime(format!("Failed to reset coverage: {e}")))
+            })
+            .await?;
+        if enabled {
+            Ok(())
+        } else {
+            Err(JsError::engine("Coverage is not enabled"))
+        }
+    }
+
+    /// Imports `entry` and runs the tests it registered with the `test`
+    /// builtin module.
+    ///
+    /// Requires `JsBuiltinOptions.test`. Each call runs the tests registered
+    /// since the previous run, so a module that was already imported
+    /// contributes nothing; reload it with `reloadModule()` to run its tests
+    /// again. Failing tests are reported in the result, not thrown.
+    ///
+    /// As in `node:test`, `todo` tests with a body run without counting as
+    /// failures. `only` behaves as under `node --test-only`, which is always
+    /// on here: once a suite contains `only` tests, its other children are
+    /// reported as skipped.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.declareNewModule(module: JsModule.code(module: 'cart.test', code: '''
+    ///   import { describe, it } from 'node:test';
+    ///   import assert from 'node:assert';
+    ///   import { total } from 'cart';
+    ///   describe('cart', () => {
+    ///     it('sums prices', () => assert.strictEqual(total([1, 2]), 3));
+    ///   });
+    /// '''));
+    /// final report = await engine.runTests(entry: 'cart.test');
+    /// print('${report.passed}/${report.results.length} passed');
+    /// ```
+    pub async fn run_tests(&self, entry: String) -> Result<JsTestReport, JsError> {
+        let resources = self.ensure_running()?;
+        let started = Instant::now();
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        let results = resources
+            .context
+            .with_foreground_js_result(async move |ctx, checkpoint| {
+                let driver = driver.clone();
+                let acknowledge =
+                    move |source| driver.remove_error_source_since(checkpoint, source);
+                if import_module_namespace(&ctx, TEST_MODULE_NAME, shutdown.clone(), &acknowledge)
+                    .await
+                    .is_err()
+                {
+                    return JsResult::Err(JsError::engine(
+                        "The `test` builtin module is not enabled",
+                    ));
+                }
+                if let Err(e) =
+                    import_module_namespace(&ctx, &entry, shutdown.clone(), &acknowledge).await
+                {
+                    return JsResult::Err(e);
+                }
+                call_module_method(
+                    &ctx,
+                    TEST_MODULE_NAME.to_string(),
+                    RUN_EXPORT.to_string(),
+                    Vec::new(),
+                    shutdown,
+                    acknowledge,
+                )
+                .await
+            })
+            .await
+            .into_result()?;
+        Ok(JsTestReport::new(
+            parse_results(results)?,
+            started.elapsed().as_secs_f64() * 1000.0,
+        ))
+    }
+
+    /// Creates a realm: a new context with its own globals and dynamic
+    /// modules that shares this engine's runtime.
+    ///
+    /// Realms start without a bridge; call `JsRealm.initBridge()` to give one
+    /// access to `fjs.bridge_call()`. They inherit the engine's builtin
+    /// modules, static modules, global attachments, and module cache.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized or is closed
+    /// - If a shared module cannot be loaded in the engine context
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final realms = {
+    ///   for (final tenant in tenants)
+    ///     tenant: await engine.createRealm(
+    ///       options: JsRealmOptions(sharedModules: ['host-api']),
+    ///     ),
+    /// };
+    /// ```
+    pub async fn create_realm(&self, options: Option<JsRealmOptions>) -> Result<JsRealm, JsError> {
+        let resources = self.ensure_running()?;
+        JsRealm::create(&resources, options).await
+    }
+
+    /// Pauses the engine: stops the background driver and freezes timers.
+    ///
+    /// While paused, no JavaScript runs. `setTimeout` and `setInterval`
+    /// timers keep the time they had left and resume counting from there, so
+    /// intervals do not fire in a burst on resume. Bridge replies that arrive
+    /// while paused are queued and delivered after `resume()`. Other engine
+    /// methods fail with "Engine is paused" until then.
+    ///
+    /// Pausing an already-paused engine is a no-op. Timers created in realms
+    /// or imported from the `timers` module are not frozen.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized or is closed
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// AppLifecycleListener(
+    ///   onHide: () => engine.pause(),
+    ///   onShow: () => engine.resume(),
+    /// );
+    /// ```
+    pub async fn pause(&self) -> Result<(), JsError> {
+        let resources = self.ensure_running_or_paused()?;
+        let runtime = &resources.runtime;
+        if runtime.driver.set_paused(true) {
+            return Ok(());
+        }
+        let paused = resources
+            .context
+            .with_js(async |ctx| crate::api::module::pause_timers(&ctx))
+            .await;
+        if let Err(error) = paused {
+            runtime.driver.set_paused(false);
+            return Err(error);
+        }
+        runtime.stop_driver().await;
+        Ok(())
+    }
+
+    /// Resumes a paused engine.
+    ///
+    /// Restarts the background driver and re-arms frozen timers with the time
+    /// they had left. Resuming an engine that is not paused is a no-op.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized or is closed
+    /// - If the timers cannot be re-armed; the engine then stays paused
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.resume();
+    /// ```
+    pub async fn resume(&self) -> Result<(), JsError> {
+        let resources = self.ensure_running_or_paused()?;
+        let runtime = &resources.runtime;
+        if !runtime.driver.paused() {
+            return Ok(());
+        }
+        resources
+            .context
+            .with_js(async |ctx| crate::api::module::resume_timers(&ctx))
+            .await?;
+        runtime.driver.set_paused(false);
+        if !runtime.driver.manual() {
+            runtime.start_driver().await;
+        }
+        Ok(())
+    }
+
+    /// Runs one turn of a host-pumped event loop.
+    ///
+    /// Requires `JsEngineRuntimeOptions.manualEventLoop`. Runs queued jobs,
+    /// including timer callbacks, Promise reactions, and bridge replies that
+    /// are ready, until the queue is empty, `maxJobs` jobs have run, or
+    /// `timeBudgetMs` milliseconds have elapsed. Errors thrown by jobs are
+    /// handled by the unhandled-error policy, as with the background driver.
+    ///
+    /// Only timers created with the global `setTimeout`/`setInterval` are
+    /// counted in `nextTimerDelayMs`.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized, paused, or closed
+    /// - If the engine runs its own event loop
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// SchedulerBinding.instance.addPersistentFrameCallback((_) async {
+    ///   final turn = await engine.runPendingJobs(
+    ///     maxJobs: 256,
+    ///     timeBudgetMs: 4,
+    ///   );
+    ///   if (turn.hasPendingWork) SchedulerBinding.instance.scheduleFrame();
+    /// });
+    /// ```
+    pub async fn run_pending_jobs(
+        &self,
+        max_jobs: Option<u64>,
+        time_budget_ms: Option<u64>,
+    ) -> Result<JsPendingJobsReport, JsError> {
+        let resources = self.ensure_running()?;
+        let runtime = &resources.runtime;
+        if !runtime.driver.manual() {
+            return Err(JsError::engine(
+                "Engine runs its own event loop; enable manualEventLoop to pump it",
+            ));
+        }
+        let deadline = time_budget_ms.map(|budget| Instant::now() + Duration::from_millis(budget));
+        let jobs_run = runtime
+            .driver
+            .pump(runtime.rt.clone(), max_jobs.unwrap_or(u64::MAX), deadline)
+            .await;
+        let next_timer_delay_ms = resources
+            .context
+            .with_js(async |ctx| crate::api::module::next_timer_delay(&ctx))
+            .await;
+        Ok(JsPendingJobsReport {
+            jobs_run,
+            has_pending_work: runtime.is_job_pending().await,
+            next_timer_delay_ms,
+        })
+    }
+
+    /// Advances the virtual clock by `ms` milliseconds.
+    ///
+    /// Requires `JsEngineRuntimeOptions.deterministic`. Timers due within the
+    /// window fire in deadline order, each seeing the clock at its own
+    /// deadline, and the Promise jobs each one queues run before the next
+    /// timer fires. `setImmediate` callbacks are due when they were
+    /// scheduled. Intervals fire once per elapsed period. Errors thrown by
+    /// timer callbacks are handled by the unhandled-error policy.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized, paused, or closed
+    /// - If deterministic mode is not enabled
+    /// - If a timer callback fails outside the unhandled-error policy; the
+    ///   remaining due timers still fire and the clock still reaches the
+    ///   target first
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.eval(source: JsCode.code('setTimeout(() => expire(), 30_000)'));
+    /// await engine.advanceTime(ms: BigInt.from(30_000));
+    /// ```
+    pub async fn advance_time(&self, ms: u64) -> Result<(), JsError> {
+        let resources = self.ensure_running()?;
+        let runtime = &resources.runtime;
+        let advanced = resources
+            .context
+            .with_js(async move |ctx| crate::runtime::clock::advance(&ctx, ms))
+            .await;
+        // Deliver the errors reported while timers fired and run the work
+        // they handed to the driver.
+        runtime
+            .driver
+            .pump(runtime.rt.clone(), u64::MAX, None)
+            .await;
+        advanced
+    }
+
+    /// Sets the virtual wall-clock time, in milliseconds since the Unix epoch.
+    ///
+    /// Requires `JsEngineRuntimeOptions.deterministic`. Only `Date` changes:
+    /// `performance.now()` and timer deadlines are measured on a separate
+    /// monotonic clock, so no timer fires or moves.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized, paused, or closed
+    /// - If deterministic mode is not enabled
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.setTime(
+    ///   timeMs: DateTime.utc(2030, 1, 1).millisecondsSinceEpoch,
+    /// );
+    /// ```
+    pub async fn set_time(&self, time_ms: i64) -> Result<(), JsError> {
+        let resources = self.ensure_running()?;
+        resources
+            .context
+            .with_js(async move |ctx| crate::runtime::clock::set_time(&ctx, time_ms))
+            .await
     }
 
-    /// Forces a garbage collection pass on the engine-owned runtime.
-    pub async fn run_gc(&self) -> Result<(), JsError> {
-        let resources = self.ensure_runtime_accessible()?;
-        resources.runtime.run_gc().await;
-        Ok(())
+    /// Starts recording engine inputs and bridge traffic.
+    ///
+    /// Every `eval()`, `evaluateModule()`, `call()`, `callBatch()`, and
+    /// `invoke()` is captured with its inputs (including eval options and
+    /// bindings) and result, and every `fjs.bridge_call()` with its
+    /// argument, Dart's response, and timing. Stop with `stopRecording()`,
+    /// save the result with `JsRecording.toJson()`, and feed it to
+    /// `replay()` to reproduce the session.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized, paused, or closed
+    /// - If a recording is already running
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.startRecording();
+    /// ```
+    pub async fn start_recording(&self) -> Result<(), JsError> {
+        let resources = self.ensure_running()?;
+        if resources.runtime.driver.recorder().start() {
+            Ok(())
+        } else {
+            Err(JsError::engine("Recording is already running"))
+        }
     }
 
-    /// Sets the garbage collection threshold on the engine-owned runtime.
-    pub async fn set_gc_threshold(&self, threshold: usize) -> Result<(), JsError> {
-        let resources = self.ensure_runtime_accessible()?;
-        resources.runtime.set_gc_threshold(threshold).await;
-        Ok(())
+    /// Stops recording and returns what was captured, in start order.
+    ///
+    /// Operations still in flight when recording stops are left out.
+    ///
+    /// ## Throws
+    /// - If the engine is closed
+    /// - If no recording is running
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final recording = await engine.stopRecording();
+    /// await File(path).writeAsString(recording.toJson());
+    /// ```
+    pub async fn stop_recording(&self) -> Result<JsRecording, JsError> {
+        self.resources()?
+            .runtime
+            .driver
+            .recorder()
+            .stop()
+            .ok_or_else(|| JsError::engine("Recording is not running"))
     }
 
-    /// Sets runtime metadata on the engine-owned runtime.
-    pub async fn set_info(&self, info: String) -> Result<(), JsError> {
-        let resources = self.ensure_runtime_accessible()?;
-        resources.runtime.set_info(info).await
+    /// Initializes the engine from a recording and replays its inputs.
+    ///
+    /// Replaces `init()`: the engine's bridge answers `fjs.bridge_call()`
+    /// from the recorded responses instead of Dart, matching each call by
+    /// argument. The recorded engine inputs then run in order with their
+    /// original options, and each step reports the recorded and replayed
+    /// outcome. Create the
+    /// engine with the modules the recorded session used. Recorded timing is
+    /// not reproduced.
+    ///
+    /// ## Throws
+    /// - If the engine is closed or already initialized
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final recording = JsRecording.fromJson(json: await File(path).readAsString());
+    /// final steps = await engine.replay(recording: recording);
+    /// final firstDivergence = steps.where((step) => step.diverged).firstOrNull;
+    /// ```
+    pub async fn replay(&self, recording: JsRecording) -> Result<Vec<JsReplayStep>, JsError> {
+        self.init_with_bridge(replay_bridge(&recording)).await?;
+        let mut steps = Vec::new();
+        for (index, event) in recording.events.into_iter().enumerate() {
+            let (recorded, replayed) = match event {
+                JsRecordedEvent::Eval {
+                    source,
+                    options,
+                    outcome,
+                    ..
+                } => (
+                    outcome,
+                    outcome_of(&self.eval(JsCode::Code(source), options).await),
+                ),
+                JsRecordedEvent::EvaluateModule {
+                    module,
+                    options,
+                    outcome,
+                    ..
+                } => (
+                    outcome,
+                    outcome_of(&self.evaluate_module(module, options).await),
+                ),
+                JsRecordedEvent::Call {
+                    module,
+                    method,
+                    params,
+                    outcome,
+                    ..
+                } => (
+                    outcome,
+                    outcome_of(&self.call(module, method, Some(params)).await),
+                ),
+                JsRecordedEvent::CallBatch {
+                    calls,
+                    concurrent,
+                    outcome,
+                    ..
+                } => (
+                    outcome,
+                    outcome_of_batch(&self.call_batch(calls, Some(concurrent)).await),
+                ),
+                JsRecordedEvent::Invoke {
+                    invocation,
+                    outcome,
+                    ..
+                } => (outcome, outcome_of(&self.invoke(invocation).await)),
+                JsRecordedEvent::BridgeCall { .. } => continue,
+            };
+            steps.push(JsReplayStep {
+                event_index: index as u64,
+                recorded,
+                replayed,
+            });
+        }
+        Ok(steps)
     }
 
-    /// Sets the max stack size on the engine-owned runtime.
-    pub async fn set_max_stack_size(&self, limit: usize) -> Result<(), JsError> {
-        let resources = self.ensure_runtime_accessible()?;
-        resources.runtime.set_max_stack_size(limit).await;
-        Ok(())
+    fn ensure_running_or_paused(&self) -> Result<Arc<JsEngineResources>, JsError> {
+        let resources = self.resources()?;
+        if resources.runtime.driver.paused() && self.state.load(Ordering::Acquire) == STATE_RUNNING
+        {
+            Ok(resources)
+        } else {
+            self.ensure_running()
+        }
     }
 
-    /// Sets the memory limit on the engine-owned runtime.
-    pub async fn set_memory_limit(&self, limit: usize) -> Result<(), JsError> {
-        let resources = self.ensure_runtime_accessible()?;
-        resources.runtime.set_memory_limit(limit).await;
-        Ok(())
+    async fn collect_heap_graph(&self) -> Result<HeapGraph, JsError> {
+        let resources = self.ensure_running()?;
+        resources.runtime.run_gc().await;
+        let sizes = HeapSizes::from_usage(&resources.runtime.memory_usage().await);
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        resources
+            .context
+            .with_js(async move |ctx| {
+                let checkpoint = driver.error_checkpoint();
+                let mut roots = vec![("(global)".to_string(), ctx.globals().into_value())];
+                for name in get_loaded_dynamic_module_names(&ctx) {
+                    let acknowledge = |source| driver.remove_error_source_since(checkpoint, source);
+                    if let Ok(namespace) =
+                        import_module_namespace(&ctx, &name, shutdown.clone(), &acknowledge).await
+                    {
+                        roots.push((format!("(module {name})"), namespace.into_value()));
+                    }
+                }
+                Ok(HeapGraph::collect(&ctx, roots, &sizes))
+            })
+            .await
     }
 
     /// Commits initialization after all setup steps succeed.
@@ -557,6 +1671,38 @@ impl JsEngine {
         &self,
         bridge: impl Fn(JsValue) -> DartFnFuture<JsResult> + Sync + Send + 'static,
     ) -> Result<(), JsError> {
+        self.init_with_bridge(Arc::new(move |value: JsValue, _: Option<JsValue>| {
+            bridge(value)
+        }))
+        .await
+    }
+
+    /// Initializes the engine with a bridge callback that also receives the
+    /// trace context.
+    ///
+    /// Works like `init()`, but the callback gets the trace context current
+    /// when JavaScript called `fjs.bridge_call()` (see
+    /// `evalWithTraceContext()`) as a second argument, or `null` outside a
+    /// traced async chain. The value JavaScript passed reaches Dart
+    /// unchanged.
+    ///
+    /// ## Throws
+    /// - Everything `init()` throws
+    ///
+    /// ## Example
+    /// ```dart
+    /// await engine.initWithTracedBridge(bridge: (value, traceContext) async {
+    ///   return JsResult.ok(await tracer.withContext(traceContext, () => handle(value)));
+    /// });
+    /// ```
+    pub async fn init_with_traced_bridge(
+        &self,
+        bridge: impl Fn(JsValue, Option<JsValue>) -> DartFnFuture<JsResult> + Sync + Send + 'static,
+    ) -> Result<(), JsError> {
+        self.init_with_bridge(Arc::new(bridge)).await
+    }
+
+    async fn init_with_bridge(&self, bridge: Arc<BridgeCallback>) -> Result<(), JsError> {
         self.begin_init()?;
 
         let resources = match self.resources() {
@@ -566,9 +1712,11 @@ impl JsEngine {
                 return Err(error);
             }
         };
-        let bridge = Arc::new(bridge);
+        let bridge = resources.runtime.driver.recorder().wrap(bridge);
         let attachment = resources.context.global_attachment.clone();
         let shutdown = resources.runtime.shutdown();
+        let metrics = resources.runtime.driver.metrics();
+        let limits = resources.bridge_limits.clone();
 
         let init_result = resources
             .context
@@ -580,7 +1728,7 @@ impl JsEngine {
                         "Failed to attach global context: {e}"
                     )));
                 }
-                if let Err(e) = register_fjs(ctx.clone(), bridge, shutdown) {
+                if let Err(e) = register_fjs(ctx.clone(), bridge, shutdown, metrics, limits) {
                     return Err(JsError::bridge(format!(
                         "Failed to register fjs bridge: {e}"
                     )));
@@ -850,20 +1998,86 @@ impl JsEngine {
         &self,
         source: JsCode,
         options: Option<JsEvalOptions>,
+    ) -> Result<JsValue, JsError> {
+        self.eval_traced(source, options, None).await
+    }
+
+    /// Evaluates JavaScript code with a trace context attached.
+    ///
+    /// `traceContext` is an opaque map that JavaScript reads with
+    /// `traceContext.getStore()`. It stays current across `await`, Promise
+    /// callbacks, timers, and microtasks started by the evaluated code, and
+    /// every `fjs.bridge_call()` made from that async chain hands it to a
+    /// bridge installed with `initWithTracedBridge()`, alongside the
+    /// unchanged argument.
+    ///
+    /// ## Throws
+    /// - If `JsEngineRuntimeOptions.traceContext` was not enabled
+    /// - Everything `eval()` throws
+    ///
+    /// ## Example
+    /// ```dart
+    /// await engine.evalWithTraceContext(
+    ///   source: JsCode.code('await handleRequest()'),
+    ///   traceContext: {'traceId': JsValue.string('4bf92f35')},
+    /// );
+    /// ```
+    pub async fn eval_with_trace_context(
+        &self,
+        source: JsCode,
+        options: Option<JsEvalOptions>,
+        trace_context: HashMap<String, JsValue>,
+    ) -> Result<JsValue, JsError> {
+        self.eval_traced(source, options, Some(trace_context)).await
+    }
+
+    async fn eval_traced(
+        &self,
+        source: JsCode,
+        options: Option<JsEvalOptions>,
+        trace_context: Option<HashMap<String, JsValue>>,
     ) -> Result<JsValue, JsError> {
         let resources = self.ensure_running()?;
+        if trace_context.is_some() {
+            Self::ensure_trace_context(&resources)?;
+        }
 
+        let recorder = resources.runtime.driver.recorder();
+        let recorded = recorder.reserve().map(|slot| (slot, options.clone()));
         let mut options = options.unwrap_or_default();
         options.promise = Some(true);
+        let bindings = options.bindings.take();
 
         let source_code = get_raw_source_code(source).await?;
+        let recorded = recorded.map(|(slot, options)| {
+            let source = String::from_utf8_lossy(&source_code).into_owned();
+            (slot, source, options)
+        });
 
+        let started = Instant::now();
         let driver = resources.runtime.driver.clone();
         let shutdown = resources.runtime.shutdown();
-        resources
+        let result = resources
             .context
             .with_foreground_js_result(async move |ctx, checkpoint| {
+                let source_code = match bind_source(&ctx, source_code, bindings) {
+                    Ok(source_code) => source_code,
+                    Err(e) => return JsResult::Err(e),
+                };
+                let previous = match trace_context {
+                    Some(context) => match crate::runtime::trace::enter(&ctx, context) {
+                        Ok(previous) => Some(previous),
+                        Err(e) => return JsResult::Err(e),
+                    },
+                    None => None,
+                };
+                let root = crate::runtime::async_stack::enter(&ctx, || "eval".to_string());
                 let res = ctx.eval_with_options(source_code, options.into());
+                crate::runtime::async_stack::exit(&ctx, root);
+                if let Some(previous) = previous {
+                    crate::runtime::trace::exit(&ctx, previous);
+                }
+                unbind(&ctx);
                 let driver = driver.clone();
                 result_from_promise(&ctx, res, shutdown, move |source| {
                     driver.remove_error_source_since(checkpoint, source);
@@ -871,7 +2085,93 @@ impl JsEngine {
                 .await
             })
             .await
-            .into_result()
+            .into_result();
+        resources
+            .runtime
+            .driver
+            .metrics()
+            .record_eval(started.elapsed());
+        if let Some((slot, source, options)) = recorded {
+            let at_ms = slot.at_ms;
+            recorder.fill(
+                slot,
+                JsRecordedEvent::Eval {
+                    at_ms,
+                    source,
+                    options,
+                    outcome: outcome_of(&result),
+                },
+            );
+        }
+        result
+    }
+
+    /// Evaluates an untrusted snippet in a fresh, isolated context.
+    ///
+    /// The snippet runs in a new context on the engine's runtime, so memory
+    /// limits and cancellation still apply, but it cannot see or change the
+    /// engine's globals or modules:
+    /// - only the standard ECMAScript intrinsics and `bindings` are defined;
+    ///   builtins such as `console`, timers, and the bridge are absent
+    /// - every intrinsic is frozen before the snippet runs
+    /// - `import` and `import()` are refused
+    /// - the context is discarded afterwards
+    ///
+    /// Bindings are copied into the context as read-only globals. The result
+    /// must be data: returning a function, or an object containing one, is
+    /// an error.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final total = await engine.evalIsolated(
+    ///   source: 'price * quantity * (1 - discount)',
+    ///   bindings: {
+    ///     'price': JsValue.float(9.5),
+    ///     'quantity': JsValue.integer(3),
+    ///     'discount': JsValue.float(0.1),
+    ///   },
+    /// );
+    /// ```
+    pub async fn eval_isolated(
+        &self,
+        source: String,
+        bindings: Option<HashMap<String, JsValue>>,
+    ) -> Result<JsValue, JsError> {
+        let resources = self.ensure_running()?;
+        let context = JsAsyncContext::from(&resources.runtime).await?;
+
+        let bindings = bindings.unwrap_or_default();
+        let started = Instant::now();
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        let result = context
+            .with_foreground_js_result(async move |ctx, checkpoint| {
+                if let Err(e) = crate::runtime::compartment::prepare(&ctx, bindings) {
+                    return JsResult::Err(e);
+                }
+                let res = ctx.eval_with_options(source, JsEvalOptions::with_promise().into());
+                let driver = driver.clone();
+                result_from_promise(&ctx, res, shutdown, move |source| {
+                    driver.remove_error_source_since(checkpoint, source);
+                })
+                .await
+            })
+            .await
+            .into_result();
+        drop(context);
+        resources
+            .runtime
+            .driver
+            .metrics()
+            .record_eval(started.elapsed());
+        let value = result?;
+        if crate::runtime::compartment::contains_function(&value) {
+            return Err(JsError::runtime(
+                "Isolated evaluation must return data, not functions",
+            ));
+        }
+        Ok(value)
     }
 
     /// Declares a new bytecode-backed module without executing it.
@@ -995,10 +2295,13 @@ impl JsEngine {
     pub async fn declare_new_module(&self, module: JsModule) -> Result<(), JsError> {
         self.ensure_running()?;
 
-        let JsModule { name, source } = module;
+        let JsModule { name, source, kind } = module;
         let source_code = get_raw_source_code(source).await?;
-        self.declare_dynamic_modules(vec![(name, DynamicModuleEntry::Source(source_code))])
-            .await
+        self.declare_dynamic_modules(vec![(
+            name,
+            DynamicModuleEntry::from_source(kind, source_code),
+        )])
+        .await
     }
 
     /// Declares multiple new modules without executing them.
@@ -1028,54 +2331,343 @@ impl JsEngine {
 
         let mut entries = Vec::with_capacity(modules.len());
         for module in modules {
-            let JsModule { name, source } = module;
+            let JsModule { name, source, kind } = module;
             let source_code = get_raw_source_code(source).await?;
-            entries.push((name, DynamicModuleEntry::Source(source_code)));
+            entries.push((name, DynamicModuleEntry::from_source(kind, source_code)));
         }
         self.declare_dynamic_modules(entries).await
     }
 
-    /// Evaluates a module (registers and executes it).
+    /// Evaluates a module (registers and executes it).
+    ///
+    /// Unlike `declareNewModule`, this method also executes the module's
+    /// top-level code and registers it in the current context.
+    ///
+    /// QuickJS module evaluation usually completes with `undefined`. Set
+    /// `returnExports` to get the module's exports instead.
+    ///
+    /// ## Parameters
+    /// - `module`: The module to evaluate (name and source code)
+    /// - `options`: Optional settings; see [`JsModuleEvalOptions`]
+    ///
+    /// ## Returns
+    /// The completion value of module evaluation, which is usually `undefined`,
+    /// or an object of the module's exports with `returnExports`
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized
+    /// - If module storage is not available
+    /// - If module execution fails
+    /// - If the module name has already been loaded in this context
+    ///
+    /// ## Example
+    /// ```dart
+    /// await engine.evaluateModule(module: JsModule.code(
+    ///   module: 'init',
+    ///   code: '''
+    ///     console.log("Module initializing...");
+    ///     export default { version: "1.0" };
+    ///   ''',
+    /// ));
+    ///
+    /// final loaded = await engine.eval(source: JsCode.code('''
+    ///   const { default: info } = await import('init');
+    ///   info.version
+    /// '''));
+    ///
+    /// final exports = await engine.evaluateModule(
+    ///   module: JsModule.code(module: 'math', code: 'export const pi = 3.14;'),
+    ///   options: JsModuleEvalOptions(returnExports: true),
+    /// );
+    /// ```
+    pub async fn evaluate_module(
+        &self,
+        module: JsModule,
+        options: Option<JsModuleEvalOptions>,
+    ) -> Result<JsValue, JsError> {
+        let resources = self.ensure_running()?;
+
+        let JsModule { name, source, kind } = module;
+        let source_code = get_raw_source_code(source).await?;
+        let recorder = resources.runtime.driver.recorder();
+        let recorded = recorder.reserve().map(|slot| {
+            let module = JsModule {
+                name: name.clone(),
+                source: recorded_source(&source_code),
+                kind,
+            };
+            (slot, module, options.clone())
+        });
+        let result = self
+            .evaluate_module_source(&resources, name, kind, source_code, options)
+            .await;
+        if let Some((slot, module, options)) = recorded {
+            let at_ms = slot.at_ms;
+            recorder.fill(
+                slot,
+                JsRecordedEvent::EvaluateModule {
+                    at_ms,
+                    module,
+                    options,
+                    outcome: outcome_of(&result),
+                },
+            );
+        }
+        result
+    }
+
+    async fn evaluate_module_source(
+        &self,
+        resources: &JsEngineResources,
+        name: String,
+        kind: JsModuleKind,
+        source_code: Vec<u8>,
+        options: Option<JsModuleEvalOptions>,
+    ) -> Result<JsValue, JsError> {
+        let completion = self
+            .evaluate_dynamic_module(
+                name.clone(),
+                DynamicModuleEntry::from_source(kind, source_code),
+            )
+            .await?;
+        if !options
+            .and_then(|options| options.return_exports)
+            .unwrap_or(false)
+        {
+            return Ok(completion);
+        }
+
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        resources
+            .context
+            .with_foreground_js_result(async move |ctx, checkpoint| {
+                let acknowledge = |source| driver.remove_error_source_since(checkpoint, source);
+                match import_module_namespace(&ctx, &name, shutdown, &acknowledge).await {
+                    Ok(namespace) => namespace_value(&ctx, &namespace).into(),
+                    Err(e) => JsResult::Err(e),
+                }
+            })
+            .await
+            .into_result()
+    }
+
+    /// Lists the exports of a module with the kind of each one.
+    ///
+    /// Works for declared, static, and builtin modules once they have been
+    /// loaded. A module that has not been imported or evaluated yet is
+    /// rejected rather than imported, so listing exports never runs module
+    /// code for the first time.
+    ///
+    /// ## Parameters
+    /// - `name`: The module name
+    ///
+    /// ## Returns
+    /// The exports sorted by name
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized
+    /// - If the module has not been loaded yet
+    /// - If the module failed to evaluate
+    ///
+    /// ## Example
+    /// ```dart
+    /// final exports = await engine.moduleExports(name: 'api');
+    /// final callable = exports
+    ///     .where((e) => e.kind != JsModuleExportKind.value)
+    ///     .map((e) => e.name);
+    /// ```
+    pub async fn module_exports(&self, name: String) -> Result<Vec<JsModuleExport>, JsError> {
+        let resources = self.ensure_running()?;
+
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        resources
+            .context
+            .with_foreground_js_value(async move |ctx, checkpoint| {
+                if !is_module_loaded(&ctx, &name) {
+                    return Err(JsError::module(
+                        Some(name),
+                        None,
+                        "Module is not loaded; import or evaluate it first",
+                    ));
+                }
+                let acknowledge = |source| driver.remove_error_source_since(checkpoint, source);
+                let namespace =
+                    import_module_namespace(&ctx, &name, shutdown, &acknowledge).await?;
+                describe_exports(&ctx, &namespace)
+            })
+            .await
+    }
+
+    /// Replaces a declared module and re-evaluates it together with its dependents.
     ///
-    /// Unlike `declareNewModule`, this method also executes the module's
-    /// top-level code and registers it in the current context.
+    /// Every loaded dynamic module that imports `module` (directly or
+    /// transitively) is invalidated and evaluated again, so later imports and
+    /// `call()` observe the new code. The reload is staged: if any affected
+    /// module fails to evaluate, the previous declaration is restored and
+    /// imports keep resolving to the instances that were current before.
+    ///
+    /// Once every affected module has evaluated, each old instance gets a
+    /// chance to clean up: if it exports a `dispose` function, that function
+    /// is called (and awaited), dependents first. Use it to clear timers,
+    /// remove listeners, or release other state the old instance created.
     ///
-    /// QuickJS module evaluation usually completes with `undefined`. Import the module
-    /// afterwards if you need its exports.
+    /// Values already imported by scripts that are not dynamic modules keep
+    /// referencing the old instance. A module that has not been loaded yet is
+    /// simply redeclared.
+    ///
+    /// QuickJS cannot unload a module, so every reload leaves the previous
+    /// instance of each affected module in the runtime's module table, along
+    /// with whatever it still references. Memory grows with each reload; for
+    /// long-running sessions with many reloads, recreate the engine
+    /// periodically.
     ///
     /// ## Parameters
-    /// - `module`: The module to evaluate (name and source code)
+    /// - `module`: The new module definition; its name must already be declared
     ///
     /// ## Returns
-    /// The completion value of module evaluation, which is usually `undefined`
+    /// The names of the modules that were re-evaluated, starting with `module`
     ///
     /// ## Throws
     /// - If the engine is not initialized
-    /// - If module storage is not available
-    /// - If module execution fails
-    /// - If the module name has already been loaded in this context
+    /// - If no module with that name has been declared
+    /// - If an affected module was declared from bytecode
+    /// - If the re-evaluation fails; nothing is replaced in that case
+    /// - If a `dispose` hook fails; the new modules are already in place
     ///
     /// ## Example
     /// ```dart
     /// await engine.evaluateModule(module: JsModule.code(
-    ///   module: 'init',
+    ///   module: 'ticker',
     ///   code: '''
-    ///     console.log("Module initializing...");
-    ///     export default { version: "1.0" };
+    ///     const id = setInterval(() => console.log('tick'), 1000);
+    ///     export function dispose() { clearInterval(id); }
     ///   ''',
     /// ));
     ///
-    /// final loaded = await engine.eval(source: JsCode.code('''
-    ///   const { default: info } = await import('init');
-    ///   info.version
-    /// '''));
+    /// // Later, after editing the script:
+    /// final reloaded = await engine.reloadModule(module: JsModule.path(
+    ///   module: 'ticker',
+    ///   path: '/absolute/path/to/ticker.js',
+    /// ));
     /// ```
-    pub async fn evaluate_module(&self, module: JsModule) -> Result<JsValue, JsError> {
-        self.ensure_running()?;
+    pub async fn reload_module(&self, module: JsModule) -> Result<Vec<String>, JsError> {
+        let resources = self.ensure_running()?;
 
-        let JsModule { name, source } = module;
+        let JsModule { name, source, kind } = module;
         let source_code = get_raw_source_code(source).await?;
-        self.evaluate_dynamic_module(name, DynamicModuleEntry::Source(source_code))
+        let entry = DynamicModuleEntry::from_source(kind, source_code);
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        resources
+            .context
+            .with_foreground_js_value(async move |ctx, checkpoint| {
+                let Some(storage) = ctx.userdata::<DynamicModuleStorage>() else {
+                    return Err(JsError::storage("Module storage not initialized"));
+                };
+                let acknowledge = {
+                    let driver = driver.clone();
+                    move |source| driver.remove_error_source_since(checkpoint, source)
+                };
+
+                let affected: Vec<String> = {
+                    let modules = storage
+                        .read()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner);
+                    if !modules.contains_key(&name) {
+                        return Err(JsError::module(
+                            Some(name),
+                            None,
+                            "Module is not declared and cannot be reloaded",
+                        ));
+                    }
+                    let affected: Vec<String> = dynamic_module_dependents(&ctx, &name)
+                        .into_iter()
+                        .filter(|module| {
+                            module == &name
+                                || (modules.contains_key(module)
+                                    && is_dynamic_module_loaded(&ctx, module))
+                        })
+                        .collect();
+                    if let Some(bytecode) = affected.iter().find(|module| {
+                        is_dynamic_module_loaded(&ctx, module)
+                            && matches!(modules.get(*module), Some(DynamicModuleEntry::Bytecode(_)))
+                    }) {
+                        return Err(JsError::module(
+                            Some(bytecode.clone()),
+                            None,
+                            "Bytecode modules cannot be reloaded",
+                        ));
+                    }
+                    affected
+                };
+
+                // Hold the current instances so they can be disposed once the
+                // new generation is in place.
+                let mut previous = Vec::new();
+                for module in &affected {
+                    if !is_dynamic_module_loaded(&ctx, module) {
+                        continue;
+                    }
+                    match import_module_namespace(&ctx, module, shutdown.clone(), &acknowledge)
+                        .await
+                    {
+                        Ok(namespace) => previous.push((module.clone(), namespace)),
+                        Err(e) => return Err(e),
+                    }
+                }
+
+                // Stage the new generation. Nothing is committed until every
+                // affected module evaluates; on failure the previous entry and
+                // generations are restored, so later imports keep resolving to
+                // the instances that were current before the reload.
+                let snapshot = DynamicModuleSnapshot::capture(&ctx, &affected);
+                let previous_entry = storage
+                    .write()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .insert(name.clone(), entry);
+                let staged: Vec<&String> = previous.iter().map(|(module, _)| module).collect();
+                for module in &staged {
+                    invalidate_dynamic_module(&ctx, module);
+                }
+                let mut reloaded = Vec::new();
+                for module in staged {
+                    if let Err(e) =
+                        import_module_namespace(&ctx, module, shutdown.clone(), &acknowledge).await
+                    {
+                        let mut modules = storage
+                            .write()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner);
+                        match previous_entry {
+                            Some(entry) => modules.insert(name, entry),
+                            None => modules.remove(&name),
+                        };
+                        drop(modules);
+                        snapshot.restore(&ctx);
+                        return Err(e);
+                    }
+                    reloaded.push(module.clone());
+                }
+
+                // Dependents are disposed before the modules they import.
+                for (module, namespace) in previous.iter().rev() {
+                    let Ok(dispose) = namespace.get::<_, rquickjs::Function>("dispose") else {
+                        continue;
+                    };
+                    let res = dispose.call::<_, rquickjs::promise::MaybePromise>(());
+                    if let JsResult::Err(e) =
+                        result_from_maybe_promise(&ctx, res, shutdown.clone(), &acknowledge).await
+                    {
+                        return Err(JsError::module(
+                            Some(module.clone()),
+                            Some("dispose".to_string()),
+                            e.to_string(),
+                        ));
+                    }
+                }
+                Ok(reloaded)
+            })
             .await
     }
 
@@ -1173,6 +2765,37 @@ impl JsEngine {
         self.evaluate_bytecode_module(entry_module).await
     }
 
+    /// Verifies, declares, and evaluates a single-file bytecode bundle.
+    ///
+    /// The file is produced by `JsBytecode.compileModuleBundleFile(...)` or
+    /// `JsBytecode.packBundle(...)`. Its QuickJS version, byte order, and
+    /// per-module checksums are checked before anything is declared. When
+    /// `verifyingKey` is provided, the file must carry a valid Ed25519
+    /// signature from the matching private key.
+    ///
+    /// ## Throws
+    /// - If the file is malformed, corrupted, or built for another QuickJS version
+    /// - If signature verification fails
+    /// - For any reason `evaluateBytecodeBundle(...)` would fail
+    ///
+    /// ## Example
+    /// ```dart
+    /// final bytes = await File('feature.fjsb').readAsBytes();
+    /// await engine.evaluateBytecodeBundleFile(
+    ///   bytes: bytes,
+    ///   verifyingKey: publisherKey,
+    /// );
+    /// ```
+    pub async fn evaluate_bytecode_bundle_file(
+        &self,
+        bytes: Vec<u8>,
+        verifying_key: Option<Vec<u8>>,
+    ) -> Result<JsValue, JsError> {
+        self.ensure_running()?;
+        let bundle = unpack_module_bundle(&bytes, verifying_key.as_deref())?;
+        self.evaluate_bytecode_bundle(bundle).await
+    }
+
     /// Evaluates classic script bytecode in the current global context.
     ///
     /// This is the non-module counterpart to `evaluateBytecodeModule()`.
@@ -1311,6 +2934,42 @@ impl JsEngine {
         resources.context.get_available_modules().await
     }
 
+    /// Describes every declared or loaded module and how they depend on each other.
+    ///
+    /// Each entry lists the module's resolved imports, its export names, its
+    /// status, and whether it came from source, bytecode, a builtin, or a
+    /// file. Imports are only known for modules that have been loaded, since
+    /// QuickJS resolves them while linking. The graph is read from what the
+    /// engine recorded while loading; nothing is imported to build it.
+    ///
+    /// Status and exports reflect what the engine observed: a module becomes
+    /// `evaluated` or `errored` once an engine call (`evaluateModule`, `call`,
+    /// `moduleExports`, ...) imported it or a module that imports it, and
+    /// export names are listed for modules the engine imported directly. A
+    /// module only imported from JavaScript stays `linked`.
+    ///
+    /// ## Returns
+    /// The modules sorted by name
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized
+    ///
+    /// ## Example
+    /// ```dart
+    /// final graph = await engine.moduleGraph();
+    /// for (final module in graph) {
+    ///   print('${module.name} (${module.status.name}) -> ${module.imports}');
+    /// }
+    /// final unused = graph.where((m) => m.status == JsModuleStatus.declared);
+    /// ```
+    pub async fn module_graph(&self) -> Result<Vec<JsModuleInfo>, JsError> {
+        let resources = self.ensure_running()?;
+        Ok(resources
+            .context
+            .with_js(async move |ctx| collect_module_graph(&ctx))
+            .await)
+    }
+
     /// Checks if a module is declared.
     ///
     /// ## Parameters
@@ -1416,22 +3075,241 @@ impl JsEngine {
         method: String,
         params: Option<Vec<JsValue>>,
     ) -> Result<JsValue, JsError> {
+        self.call_traced(module, method, params, None).await
+    }
+
+    /// Calls a function in a module with a trace context attached.
+    ///
+    /// The context is visible to the function and the async work it starts,
+    /// as described for `evalWithTraceContext()`.
+    ///
+    /// ## Throws
+    /// - If `JsEngineRuntimeOptions.traceContext` was not enabled
+    /// - Everything `call()` throws
+    ///
+    /// ## Example
+    /// ```dart
+    /// final user = await engine.callWithTraceContext(
+    ///   module: 'api',
+    ///   method: 'loadUser',
+    ///   params: [JsValue.integer(7)],
+    ///   traceContext: {'traceId': JsValue.string('4bf92f35')},
+    /// );
+    /// ```
+    pub async fn call_with_trace_context(
+        &self,
+        module: String,
+        method: String,
+        params: Option<Vec<JsValue>>,
+        trace_context: HashMap<String, JsValue>,
+    ) -> Result<JsValue, JsError> {
+        self.call_traced(module, method, params, Some(trace_context))
+            .await
+    }
+
+    /// Calls several module functions in one round trip.
+    ///
+    /// All calls run in a single foreground task and each module is imported
+    /// once, which avoids per-call overhead when many small values are needed
+    /// at once. By default each call starts after the previous one settles;
+    /// with `concurrent` every call starts first and the batch then waits for
+    /// all of them, so async functions overlap.
+    ///
+    /// ## Returns
+    /// One result per call, in order. A failing call yields an error entry
+    /// and does not affect the others.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized
+    /// - If the engine is closed
+    ///
+    /// ## Example
+    /// ```dart
+    /// final results = await engine.callBatch(
+    ///   calls: [
+    ///     JsBatchCall(module: 'store', method: 'title'),
+    ///     JsBatchCall(module: 'store', method: 'count', params: [JsValue.string('cart')]),
+    ///   ],
+    ///   concurrent: true,
+    /// );
+    /// for (final result in results) {
+    ///   print(result.isOk ? result.ok : result.err);
+    /// }
+    /// ```
+    pub async fn call_batch(
+        &self,
+        calls: Vec<JsBatchCall>,
+        concurrent: Option<bool>,
+    ) -> Result<Vec<JsResult>, JsError> {
         let resources = self.ensure_running()?;
 
-        let params = params.unwrap_or_default();
+        let concurrent = concurrent.unwrap_or(false);
+        let recorder = resources.runtime.driver.recorder();
+        let recorded = recorder.reserve().map(|slot| (slot, calls.clone()));
+        let calls: Vec<ModuleMethodCall> = calls
+            .into_iter()
+            .map(|call| ModuleMethodCall {
+                module: call.module,
+                method: call.method,
+                params: call.params.unwrap_or_default(),
+            })
+            .collect();
+        let started = Instant::now();
         let driver = resources.runtime.driver.clone();
         let shutdown = resources.runtime.shutdown();
+        let results = resources
+            .context
+            .with_foreground_js_results(async move |ctx, checkpoint| {
+                let driver = driver.clone();
+                call_module_methods(&ctx, calls, concurrent, shutdown, move |source| {
+                    driver.remove_error_source_since(checkpoint, source);
+                })
+                .await
+            })
+            .await;
         resources
+            .runtime
+            .driver
+            .metrics()
+            .record_call(started.elapsed());
+        if let Some((slot, calls)) = recorded {
+            let at_ms = slot.at_ms;
+            recorder.fill(
+                slot,
+                JsRecordedEvent::CallBatch {
+                    at_ms,
+                    calls,
+                    concurrent,
+                    outcome: outcome_of_batch(&results),
+                },
+            );
+        }
+        results
+    }
+
+    /// Calls a function by path, with more control than `call()`.
+    ///
+    /// The path is resolved from a module's exports or from `globalThis`, one
+    /// dotted segment at a time, so nested objects, `default` exports, and
+    /// global helpers are all reachable. The function can be called as a
+    /// constructor or with an explicit `this`. Generator results, sync or
+    /// async, are collected into arrays.
+    ///
+    /// ## Throws
+    /// - If the engine is not initialized
+    /// - If the engine is closed
+    /// - If the module cannot be imported or the path does not resolve
+    /// - If the target is not a function (or not a constructor with
+    ///   `construct`), or `construct` is combined with a `this` binding
+    /// - If the function call fails
+    ///
+    /// ## Example
+    /// ```dart
+    /// final point = await engine.invoke(
+    ///   invocation: JsInvocation(
+    ///     module: 'geometry',
+    ///     path: 'Point',
+    ///     params: [JsValue.integer(1), JsValue.integer(2)],
+    ///     construct: true,
+    ///   ),
+    /// );
+    /// ```
+    pub async fn invoke(&self, invocation: JsInvocation) -> Result<JsValue, JsError> {
+        let resources = self.ensure_running()?;
+
+        let recorder = resources.runtime.driver.recorder();
+        let recorded = recorder.reserve().map(|slot| (slot, invocation.clone()));
+        let started = Instant::now();
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        let result = resources
             .context
             .with_foreground_js_result(async move |ctx, checkpoint| {
                 let driver = driver.clone();
-                call_module_method(&ctx, module, method, params, shutdown, move |source| {
+                invoke_function(&ctx, invocation, shutdown, move |source| {
                     driver.remove_error_source_since(checkpoint, source);
                 })
                 .await
             })
             .await
-            .into_result()
+            .into_result();
+        resources
+            .runtime
+            .driver
+            .metrics()
+            .record_call(started.elapsed());
+        if let Some((slot, invocation)) = recorded {
+            let at_ms = slot.at_ms;
+            recorder.fill(
+                slot,
+                JsRecordedEvent::Invoke {
+                    at_ms,
+                    invocation,
+                    outcome: outcome_of(&result),
+                },
+            );
+        }
+        result
+    }
+
+    async fn call_traced(
+        &self,
+        module: String,
+        method: String,
+        params: Option<Vec<JsValue>>,
+        trace_context: Option<HashMap<String, JsValue>>,
+    ) -> Result<JsValue, JsError> {
+        let resources = self.ensure_running()?;
+        if trace_context.is_some() {
+            Self::ensure_trace_context(&resources)?;
+        }
+
+        let params = params.unwrap_or_default();
+        let recorder = resources.runtime.driver.recorder();
+        let recorded = recorder
+            .reserve()
+            .map(|slot| (slot, module.clone(), method.clone(), params.clone()));
+        let started = Instant::now();
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        let result = resources
+            .context
+            .with_foreground_js_result(async move |ctx, checkpoint| {
+                let driver = driver.clone();
+                call_module_method_traced(
+                    &ctx,
+                    module,
+                    method,
+                    params,
+                    trace_context,
+                    shutdown,
+                    move |source| {
+                        driver.remove_error_source_since(checkpoint, source);
+                    },
+                )
+                .await
+            })
+            .await
+            .into_result();
+        resources
+            .runtime
+            .driver
+            .metrics()
+            .record_call(started.elapsed());
+        if let Some((slot, module, method, params)) = recorded {
+            let at_ms = slot.at_ms;
+            recorder.fill(
+                slot,
+                JsRecordedEvent::Call {
+                    at_ms,
+                    module,
+                    method,
+                    params,
+                    outcome: outcome_of(&result),
+                },
+            );
+        }
+        result
     }
 }
 
@@ -1445,16 +3323,152 @@ fn first_duplicate_name<'a>(names: impl IntoIterator<Item = &'a str>) -> Option<
     None
 }
 
+/// Declares pre-resolved dynamic modules in `context`.
+pub(crate) async fn declare_dynamic_modules_in(
+    context: &JsAsyncContext,
+    entries: Vec<(String, DynamicModuleEntry)>,
+) -> Result<(), JsError> {
+    let single = entries.len() == 1;
+    context
+        .with_foreground_js_result(async move |ctx, _checkpoint| {
+            let conflicts: Vec<_> = entries
+                .iter()
+                .filter(|(name, _)| is_dynamic_module_loaded(&ctx, name))
+                .map(|(name, _)| name.clone())
+                .collect();
+            if let Some(first) = conflicts.first() {
+                return JsResult::Err(if single {
+                    JsEngine::already_loaded_error(first.clone())
+                } else {
+                    JsError::module(
+                        Some(first.clone()),
+                        None,
+                        format!(
+                            "Loaded dynamic modules cannot be redefined in this context: {}",
+                            conflicts.join(", ")
+                        ),
+                    )
+                });
+            }
+            let Some(storage) = ctx.userdata::<DynamicModuleStorage>() else {
+                return JsResult::Err(JsError::storage("Module storage not initialized"));
+            };
+            storage
+                .write()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .extend(entries);
+            JsResult::Ok(JsValue::None)
+        })
+        .await
+        .into_result()
+        .map(|_| ())
+}
+
+/// Registers a dynamic module in `context` and runs its top-level code.
+pub(crate) async fn evaluate_dynamic_module_in(
+    context: &JsAsyncContext,
+    module_name: String,
+    entry: DynamicModuleEntry,
+) -> Result<JsValue, JsError> {
+    let driver = context.driver.clone();
+    let shutdown = context.shutdown.clone();
+    context
+        .with_foreground_js_result(async move |ctx, checkpoint| {
+            if is_dynamic_module_loaded(&ctx, &module_name) {
+                return JsResult::Err(JsEngine::already_loaded_error(module_name));
+            }
+            let Some(storage) = ctx.userdata::<DynamicModuleStorage>() else {
+                return JsResult::Err(JsError::storage("Module storage not initialized"));
+            };
+
+            let res = match entry {
+                DynamicModuleEntry::Bytecode(bytes) => {
+                    let loaded = load_module_bytecode_checked(ctx.clone(), &module_name, &bytes)
+                        .and_then(|module| {
+                            let embedded_name: String = module.name()?;
+                            if embedded_name != module_name {
+                                return Err(rquickjs::Error::new_loading_message(
+                                    module_name.clone(),
+                                    format!(
+                                        "Bytecode module name mismatch: expected '{}', found '{}'",
+                                        module_name, embedded_name
+                                    ),
+                                ));
+                            }
+                            let (_module, promise) = module.eval()?;
+                            Ok(promise)
+                        });
+                    if loaded.is_ok() {
+                        storage
+                            .write()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)
+                            .insert(module_name.clone(), DynamicModuleEntry::Bytecode(bytes));
+                    }
+                    loaded
+                }
+                DynamicModuleEntry::Source(source) => {
+                    storage
+                        .write()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .insert(
+                            module_name.clone(),
+                            DynamicModuleEntry::Source(source.clone()),
+                        );
+                    match ctx.userdata::<ModuleBytecodeCache>() {
+                        Some(cache) => {
+                            declare_cached_module(&ctx, &cache, &module_name, source, &[])
+                                .and_then(|module| module.eval().map(|(_, promise)| promise))
+                        }
+                        None => {
+                            let source = instrument_module_source(&ctx, &module_name, source);
+                            Module::evaluate(ctx.clone(), module_name.clone(), source)
+                        }
+                    }
+                }
+                data => {
+                    let (kind, bytes) = data.data().unwrap_or_default();
+                    let declared = declare_data_module(&ctx, &module_name, kind, bytes, "");
+                    if declared.is_ok() {
+                        storage
+                            .write()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)
+                            .insert(module_name.clone(), data);
+                    }
+                    declared.and_then(|module| module.eval().map(|(_, promise)| promise))
+                }
+            };
+
+            if res.is_ok() {
+                mark_dynamic_module_loaded(&ctx, &module_name);
+            }
+            let driver = driver.clone();
+            let result = result_from_promise(&ctx, res, shutdown, move |source| {
+                driver.remove_error_source_since(checkpoint, source);
+            })
+            .await;
+            match &result {
+                JsResult::Ok(_) => record_module_evaluated(&ctx, &module_name, None),
+                JsResult::Err(JsError::Cancelled(_)) => {}
+                JsResult::Err(_) => record_module_errored(&ctx, &module_name),
+            }
+            result
+        })
+        .await
+        .into_result()
+}
+
 /// Registers the fjs bridge object.
-fn register_fjs<'js>(
+pub(crate) fn register_fjs<'js>(
     ctx: rquickjs::Ctx<'js>,
     bridge: Arc<BridgeCallback>,
     shutdown: crate::runtime::shutdown::RuntimeShutdown,
+    metrics: RuntimeMetrics,
+    limits: BridgeLimits,
 ) -> rquickjs::CaughtResult<'js, ()> {
     let fjs = Object::new(ctx.clone()).catch(&ctx)?;
     fjs.set(
         "bridge_call",
-        new_bridge_call(ctx.clone(), bridge, shutdown)?,
+        new_bridge_call(ctx.clone(), bridge, shutdown, metrics, limits)?,
     )
     .catch(&ctx)?;
     ctx.globals().set("fjs", fjs).catch(&ctx)?;
@@ -1462,10 +3476,17 @@ fn register_fjs<'js>(
 }
 
 /// Creates the bridge_call function.
+///
+/// JavaScript calls it as `fjs.bridge_call(value, options?)`, where
+/// `options.timeoutMs` overrides the engine's default bridge timeout.
+/// The current trace context, if any, is passed to `bridge` next to the
+/// argument.
 fn new_bridge_call<'js>(
     ctx: rquickjs::Ctx<'js>,
     bridge: Arc<BridgeCallback>,
     shutdown: crate::runtime::shutdown::RuntimeShutdown,
+    metrics: RuntimeMetrics,
+    limits: BridgeLimits,
 ) -> rquickjs::CaughtResult<'js, rquickjs::Function<'js>> {
     let ctx_for_catch = ctx.clone();
     rquickjs::Function::new(
@@ -1473,9 +3494,9 @@ fn new_bridge_call<'js>(
         move |call_ctx: rquickjs::Ctx<'js>,
               args: rquickjs::function::Rest<rquickjs::Value<'js>>|
               -> rquickjs::Result<Promise<'js>> {
-            if args.0.len() > 1 {
+            if args.0.len() > 2 {
                 return Err(rquickjs::Error::TooManyArgs {
-                    expected: 1,
+                    expected: 2,
                     given: args.len(),
                 });
             }
@@ -1490,29 +3511,63 @@ fn new_bridge_call<'js>(
                 expected: 1,
                 given: 0,
             })?;
+            let timeout = match args.0.get(1).and_then(|options| options.as_object()) {
+                Some(options) => match options
+                    .get::<_, Option<f64>>("timeoutMs")?
+                    .map(|ms| Duration::try_from_secs_f64(ms / 1000.0))
+                {
+                    Some(Ok(timeout)) => Some(timeout),
+                    Some(Err(_)) => {
+                        return Err(rquickjs::Error::new_from_js_message(
+                            "value",
+                            "timeoutMs",
+                            "timeoutMs must be a non-negative number",
+                        ));
+                    }
+                    None => limits.timeout(),
+                },
+                None => limits.timeout(),
+            };
 
             let js_value = JsValue::from_js(&call_ctx, arg.clone())?;
+            let trace = crate::runtime::trace::current(&call_ctx);
             let bridge_ref = bridge.clone();
             let shutdown = shutdown.clone();
+            let metrics = metrics.clone();
+            let limits = limits.clone();
 
             Promise::wrap_future(&call_ctx, async move {
-                tokio::select! {
-                    result = bridge_ref(js_value) => {
-                        match result {
-                            JsResult::Ok(value) => Ok::<JsValue, rquickjs::Error>(value),
-                            JsResult::Err(err) => Err(rquickjs::Error::new_from_js_message(
-                                "bridge",
-                                "JsValue",
-                                err.to_string(),
-                            )),
-                        }
+                let started = Instant::now();
+                let bridge_error = |message: String| {
+                    rquickjs::Error::new_from_js_message("bridge", "JsValue", message)
+                };
+                let call = async {
+                    let _slot = limits.acquire().await.map_err(bridge_error)?;
+                    match bridge_ref(js_value, trace).await {
+                        JsResult::Ok(value) => Ok::<JsValue, rquickjs::Error>(value),
+                        JsResult::Err(err) => Err(bridge_error(err.to_string())),
                     }
-                    _ = shutdown.cancelled() => Err(rquickjs::Error::new_from_js_message(
-                        "bridge",
-                        "JsValue",
-                        shutdown.error().to_string(),
-                    )),
-                }
+                };
+                let call =
+                    async {
+                        match timeout {
+                            Some(timeout) => tokio::time::timeout(timeout, call)
+                                .await
+                                .unwrap_or_else(|_| {
+                                    Err(bridge_error(format!(
+                                        "Bridge call timed out after {} ms",
+                                        timeout.as_millis()
+                                    )))
+                                }),
+                            None => call.await,
+                        }
+                    };
+                let result = tokio::select! {
+                    result = call => result,
+                    _ = shutdown.cancelled() => Err(bridge_error(shutdown.error().to_string())),
+                };
+                metrics.record_bridge_call(started.elapsed());
+                result
             })
         },
     )
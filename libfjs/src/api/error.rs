@@ -12,7 +12,7 @@ use std::fmt;
 /// This enum provides detailed error information for different
 /// categories of errors that can occur during JavaScript execution.
 #[frb(dart_metadata = ("freezed"))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum JsError {
     /// Promise-related errors (async operation failures)
     Promise(String),
@@ -512,6 +512,11 @@ impl JsError {
             .stack()
             .map(|stack| stack.trim_end().to_string())
             .filter(|stack| !stack.is_empty());
+        let async_stack = exception
+            .as_object()
+            .get::<_, Option<String>>(crate::runtime::async_stack::ASYNC_STACK_PROPERTY)
+            .ok()
+            .flatten();
         // Throwing `name`/`message`/`stack` accessors leave their own
         // exception pending on the context; clear it so later operations do
         // not misattribute it.
@@ -528,6 +533,10 @@ impl JsError {
             detail.push('\n');
             detail.push_str(stack);
         }
+        if let Some(async_stack) = &async_stack {
+            detail.push('\n');
+            detail.push_str(async_stack);
+        }
 
         // QuickJS reports stack exhaustion as `RangeError: Maximum call stack
         // size exceeded` or `InternalError: stack overflow (...)`; require the
@@ -878,3 +887,65 @@ impl From<JsResult> for Result<super::value::JsValue, JsError> {
         }
     }
 }
+
+/// How unhandled background errors affect later engine calls.
+///
+/// Background errors are unhandled promise rejections, throwing timer
+/// callbacks, and failing spawned tasks. They are always delivered to
+/// listeners registered with `JsEngine.unhandledErrors()`.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsUnhandledErrorPolicy {
+    /// Fail the next engine call with the queued errors, then clear them.
+    #[default]
+    Poison,
+    /// Only report errors to listeners; later calls are unaffected.
+    Report,
+}
+
+/// Where an unhandled background error came from.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsUnhandledErrorOrigin {
+    /// A promise was rejected and no handler was attached by the end of the turn.
+    PromiseRejection {
+        /// Opaque identifier of the rejected promise, unique while it is alive.
+        promise_id: u64,
+    },
+    /// A `setTimeout`, `setInterval`, or `setImmediate` callback threw.
+    Timer {
+        /// Name of the scheduling function, such as `setTimeout`.
+        function: String,
+        /// Identifier returned by the scheduling function, when numeric.
+        timer_id: Option<u64>,
+        /// Requested delay in milliseconds; `None` for `setImmediate`.
+        delay_ms: Option<f64>,
+    },
+    /// A queued QuickJS job failed outside of any promise.
+    Job,
+    /// A natively spawned task (I/O, networking, ...) failed.
+    Task,
+}
+
+/// An unhandled background error reported by the engine.
+///
+/// ## Example
+///
+/// ```dart
+/// engine.unhandledErrors().listen((report) {
+///   report.origin.whenOrNull(
+///     timer: (function, timerId, delayMs) => print('$function #$timerId failed'),
+///   );
+///   print(report.error.toString());
+/// });
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone)]
+pub struct JsUnhandledError {
+    /// The structured error, classified like a foreground failure.
+    pub error: JsError,
+    /// What produced the error.
+    pub origin: JsUnhandledErrorOrigin,
+    /// Whether the error will also fail the next engine call.
+    pub poisons_engine: bool,
+}
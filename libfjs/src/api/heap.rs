@@ -0,0 +1,114 @@
+//! # Heap Snapshots
+//!
+//! Types returned by `JsEngine.heapSnapshot()` for finding what holds memory
+//! in an engine, and for comparing snapshots to spot leaks.
+//!
+//! Snapshots cover objects reachable from the global object and from loaded
+//! module namespaces. Sizes are estimates derived from QuickJS's aggregate
+//! memory statistics, so compare them relative to each other rather than
+//! against `MemoryUsage` totals.
+
+use flutter_rust_bridge::frb;
+use std::collections::BTreeMap;
+
+/// Object counts and sizes for one constructor.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsHeapClassStats {
+    /// Constructor name, or `(closure)` / `(string)` for functions and strings.
+    pub name: String,
+    /// Number of reachable instances.
+    pub count: u64,
+    /// Estimated bytes held by the instances themselves.
+    pub self_size: u64,
+    /// Estimated bytes that would be freed if every instance became unreachable.
+    pub retained_size: u64,
+}
+
+/// A per-constructor summary of the reachable heap.
+///
+/// ## Example
+///
+/// ```dart
+/// final before = await engine.heapSnapshot();
+/// for (var i = 0; i < 100; i++) {
+///   await engine.call(module: 'app', method: 'handle');
+/// }
+/// final after = await engine.heapSnapshot();
+/// for (final delta in before.diff(later: after).take(5)) {
+///   print('${delta.name}: +${delta.countDelta} (${delta.selfSizeDelta} bytes)');
+/// }
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsHeapSnapshot {
+    /// Number of visited heap nodes.
+    pub node_count: u64,
+    /// Number of references between visited nodes.
+    pub edge_count: u64,
+    /// Estimated bytes reachable from the roots.
+    pub total_size: u64,
+    /// Whether the walk stopped early because the heap was too large.
+    pub truncated: bool,
+    /// Per-constructor statistics, largest retained size first.
+    pub classes: Vec<JsHeapClassStats>,
+}
+
+/// Change in one constructor's statistics between two snapshots.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsHeapClassDelta {
+    /// Constructor name.
+    pub name: String,
+    /// Instances added (positive) or removed (negative).
+    pub count_delta: i64,
+    /// Change in self size, in bytes.
+    pub self_size_delta: i64,
+    /// Change in retained size, in bytes.
+    pub retained_size_delta: i64,
+}
+
+impl JsHeapSnapshot {
+    /// Compares this snapshot with a `later` one.
+    ///
+    /// Returns one entry per constructor whose statistics changed, with the
+    /// largest self-size growth first.
+    #[frb(sync)]
+    pub fn diff(&self, later: &JsHeapSnapshot) -> Vec<JsHeapClassDelta> {
+        let mut deltas: BTreeMap<&str, JsHeapClassDelta> = BTreeMap::new();
+        let mut apply = |stats: &JsHeapClassStats, sign: i64| {
+            let delta = deltas
+                .entry(stats.name.as_str())
+                .or_insert_with(|| JsHeapClassDelta {
+                    name: stats.name.clone(),
+                    count_delta: 0,
+                    self_size_delta: 0,
+                    retained_size_delta: 0,
+                });
+            delta.count_delta += sign * stats.count as i64;
+            delta.self_size_delta += sign * stats.self_size as i64;
+            delta.retained_size_delta += sign * stats.retained_size as i64;
+        };
+        for stats in &self.classes {
+            apply(stats, -1);
+        }
+        for stats in &later.classes {
+            apply(stats, 1);
+        }
+
+        let mut deltas: Vec<_> = deltas
+            .into_values()
+            .filter(|delta| {
+                delta.count_delta != 0
+                    || delta.self_size_delta != 0
+                    || delta.retained_size_delta != 0
+            })
+            .collect();
+        deltas.sort_by(|a, b| {
+            b.self_size_delta
+                .cmp(&a.self_size_delta)
+                .then_with(|| b.count_delta.cmp(&a.count_delta))
+        });
+        deltas
+    }
+}
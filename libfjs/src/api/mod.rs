@@ -9,6 +9,12 @@
 //! - **error**: Comprehensive error types
 //! - **source**: Source code and module definitions
 //! - **module**: Module system and dynamic loading capabilities
+//! - **heap**: Heap snapshots and snapshot diffing
+//! - **profiler**: Sampled CPU profiles and their export formats
+//! - **coverage**: Statement and function coverage reports
+//! - **testing**: Results of `node:test` runs
+//! - **realm**: Additional contexts sharing an engine's runtime
+//! - **recording**: Recorded bridge traffic and engine inputs for replay
 //!
 //! ## Initialization
 //!
@@ -16,23 +22,49 @@
 //! This function should be called once during application initialization.
 
 pub mod bytecode;
+pub mod coverage;
 pub mod engine;
 pub mod error;
+pub mod heap;
 pub mod module;
+pub mod profiler;
+pub mod realm;
+pub mod recording;
 pub mod runtime;
 pub mod source;
+pub mod testing;
 pub mod value;
 
 // Re-export main types for convenience
 pub use bytecode::JsBytecode;
-pub use engine::{JsEngine, JsEngineRuntimeOptions};
-pub use error::{JsError, JsResult};
-pub use module::{DynamicModuleLoader, DynamicModuleResolver, GlobalAttachment, ModuleBuilder};
-pub use runtime::{JsAsyncContext, JsAsyncRuntime, JsContext, JsRuntime, MemoryUsage};
+pub use coverage::{JsCoverageReport, JsFunctionCoverage, JsModuleCoverage, JsStatementCoverage};
+pub use engine::{
+    JsBatchCall, JsBridgeOptions, JsBridgeOverflowPolicy, JsDeterministicOptions, JsEngine,
+    JsEngineMetrics, JsEngineRuntimeOptions, JsHistogram, JsHistogramBucket, JsInvocation,
+    JsModuleCache, JsModuleCacheStore, JsPendingJobsReport, JsThisBinding,
+};
+pub use error::{
+    JsError, JsResult, JsUnhandledError, JsUnhandledErrorOrigin, JsUnhandledErrorPolicy,
+};
+pub use heap::{JsHeapClassDelta, JsHeapClassStats, JsHeapSnapshot};
+pub use module::{
+    DynamicModuleLoader, DynamicModuleResolver, GlobalAttachment, JsModuleExport,
+    JsModuleExportKind, JsModuleInfo, JsModuleOrigin, JsModuleStatus, ModuleBuilder,
+};
+pub use profiler::{JsCpuProfile, JsProfileNode};
+pub use realm::{JsRealm, JsRealmOptions};
+pub use recording::{JsRecordedEvent, JsRecordedOutcome, JsRecording, JsReplayStep};
+pub use runtime::{
+    JsAsyncContext, JsAsyncRuntime, JsContext, JsMemoryEvent, JsMemoryEventKind,
+    JsMemoryPressureLevel, JsRuntime, MemoryUsage,
+};
 pub use source::{
-    JsBuiltinOptions, JsBytecodeEndianness, JsCode, JsEvalOptions, JsModule, JsModuleBytecode,
-    JsModuleBytecodeBundle, JsModuleBytecodeOptions, JsScriptBytecode, JsScriptBytecodeOptions,
+    JsBuiltinOptions, JsBytecodeBundleFileOptions, JsBytecodeCompression, JsBytecodeEndianness,
+    JsCode, JsEvalOptions, JsModule, JsModuleBytecode, JsModuleBytecodeBundle,
+    JsModuleBytecodeOptions, JsModuleEvalOptions, JsModuleKind, JsScriptBytecode,
+    JsScriptBytecodeOptions,
 };
+pub use testing::{JsTestFailure, JsTestReport, JsTestResult, JsTestStatus};
 pub use value::JsValue;
 
 /// Initializes the Flutter Rust bridge with default user utilities.
@@ -21,14 +21,20 @@
 //! - **Storage**: Manage dynamic module state
 //! - **Builders**: Configure runtime module systems
 
-use crate::api::source::JsBuiltinOptions;
+use crate::api::error::JsError;
+use crate::api::source::{JsBuiltinOptions, JsCode, JsModuleKind, get_raw_source_code_sync};
+use crate::api::value::JsValue;
 use crate::bytecode_support::load_module_bytecode_checked;
+use crate::runtime::coverage::instrument_module_source;
+use crate::runtime::metrics::{RuntimeMetrics, SpanKind, in_span};
+use crate::runtime::module_cache::{ModuleBytecodeCache, declare_cached_module};
+use crate::test_runner::{TEST_MODULE_NAME, TestModule};
 use flutter_rust_bridge::frb;
 use llrt_utils::module::ModuleInfo;
 use rquickjs::loader::{ImportAttributes, Loader, ModuleLoader, Resolver};
 use rquickjs::module::ModuleDef;
-use rquickjs::{Ctx, Function, JsLifetime, Module, Value};
-use std::collections::{HashMap, HashSet};
+use rquickjs::{CatchResultExt, Ctx, FromJs, Function, JsLifetime, Module, Object, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::{Arc, RwLock};
 
@@ -40,6 +46,215 @@ pub enum DynamicModuleEntry {
     Source(Vec<u8>),
     /// QuickJS ES-module bytecode whose embedded module name must match the registered name.
     Bytecode(Vec<u8>),
+    /// UTF-8 JSON document exported as the module's default value.
+    Json(Vec<u8>),
+    /// UTF-8 text exported as the module's default string.
+    Text(Vec<u8>),
+    /// Raw bytes exported as the module's default `Uint8Array`.
+    Bytes(Vec<u8>),
+}
+
+impl DynamicModuleEntry {
+    /// Wraps raw module source bytes according to the declared module kind.
+    pub(crate) fn from_source(kind: JsModuleKind, source: Vec<u8>) -> Self {
+        match kind {
+            JsModuleKind::JavaScript => DynamicModuleEntry::Source(source),
+            JsModuleKind::Json => DynamicModuleEntry::Json(source),
+            JsModuleKind::Text => DynamicModuleEntry::Text(source),
+            JsModuleKind::Bytes => DynamicModuleEntry::Bytes(source),
+        }
+    }
+
+    /// Returns the content kind, or `None` for bytecode entries.
+    pub(crate) fn kind(&self) -> Option<JsModuleKind> {
+        match self {
+            DynamicModuleEntry::Source(_) => Some(JsModuleKind::JavaScript),
+            DynamicModuleEntry::Bytecode(_) => None,
+            DynamicModuleEntry::Json(_) => Some(JsModuleKind::Json),
+            DynamicModuleEntry::Text(_) => Some(JsModuleKind::Text),
+            DynamicModuleEntry::Bytes(_) => Some(JsModuleKind::Bytes),
+        }
+    }
+
+    /// Returns the kind and content of JSON, text, and byte entries.
+    pub(crate) fn data(&self) -> Option<(JsModuleKind, &[u8])> {
+        match self {
+            DynamicModuleEntry::Source(_) | DynamicModuleEntry::Bytecode(_) => None,
+            DynamicModuleEntry::Json(bytes) => Some((JsModuleKind::Json, bytes)),
+            DynamicModuleEntry::Text(bytes) => Some((JsModuleKind::Text, bytes)),
+            DynamicModuleEntry::Bytes(bytes) => Some((JsModuleKind::Bytes, bytes)),
+        }
+    }
+}
+
+/// Converts module content into ES-module source text.
+///
+/// JavaScript sources are returned unchanged; JSON, text, and byte modules
+/// are wrapped in a synthesized module with a single default export. Byte
+/// modules embed their content as base64, so this is only for callers that
+/// need source text, such as bytecode compilation; loaders declare byte
+/// modules with [`declare_data_module`] instead.
+pub(crate) fn module_source_code(
+    name: &str,
+    kind: JsModuleKind,
+    bytes: &[u8],
+) -> Result<Vec<u8>, JsError> {
+    let utf8 = |bytes: &[u8]| {
+        std::str::from_utf8(bytes).map_err(|e| {
+            JsError::module(
+                Some(name.to_string()),
+                None,
+                format!("Module source is not valid UTF-8: {}", e),
+            )
+        })
+    };
+    let source = match kind {
+        JsModuleKind::JavaScript => return Ok(bytes.to_vec()),
+        JsModuleKind::Json => format!(
+            "export default JSON.parse({});\n",
+            js_string_literal(utf8(bytes)?)
+        ),
+        JsModuleKind::Text => format!("export default {};\n", js_string_literal(utf8(bytes)?)),
+        JsModuleKind::Bytes => format!(
+            "const data = \"{}\";\n\
+             const bytes = new Uint8Array({});\n\
+             const digit = (i) => {{ const c = data.charCodeAt(i); return c > 96 ? c - 71 : c > 64 ? c - 65 : c > 47 ? c + 4 : c === 43 ? 62 : 63; }};\n\
+             for (let i = 0, j = 0; j < bytes.length; i += 4) {{\n\
+             \x20 const n = digit(i) << 18 | digit(i + 1) << 12 | digit(i + 2) << 6 | digit(i + 3);\n\
+             \x20 bytes[j++] = n >> 16;\n\
+             \x20 if (j < bytes.length) bytes[j++] = n >> 8 & 255;\n\
+             \x20 if (j < bytes.length) bytes[j++] = n & 255;\n\
+             }}\n\
+             export default bytes;\n",
+            base64(bytes),
+            bytes.len()
+        ),
+    };
+    Ok(source.into_bytes())
+}
+
+/// Encodes `bytes` as unpadded base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | (u32::from(*byte) << (16 - 8 * i))
+        });
+        for i in 0..=chunk.len() {
+            encoded.push(char::from(ALPHABET[(group >> (18 - 6 * i)) as usize & 63]));
+        }
+    }
+    encoded
+}
+
+/// Property of `import.meta` holding the buffer of a byte module.
+const BYTES_HOOK: &str = "__fjs_bytes__";
+
+/// Source of a byte module. The buffer is built from Rust and handed over
+/// through `import.meta`, which the module clears before exporting it.
+const BYTES_MODULE_SOURCE: &str = "const buffer = import.meta.__fjs_bytes__;\ndelete import.meta.__fjs_bytes__;\nexport default new Uint8Array(buffer);\n";
+
+/// Declares a JSON, text, or byte module with a single default export,
+/// followed by `trailer`.
+pub(crate) fn declare_data_module<'js>(
+    ctx: &Ctx<'js>,
+    name: &str,
+    kind: JsModuleKind,
+    bytes: &[u8],
+    trailer: &str,
+) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+    if kind == JsModuleKind::Bytes {
+        return declare_bytes_module(ctx, name, bytes, trailer);
+    }
+    let mut source = module_source_code(name, kind, bytes)
+        .map_err(|e| rquickjs::Error::new_loading_message(name, e.to_string()))?;
+    source.extend_from_slice(trailer.as_bytes());
+    Module::declare(ctx.clone(), name, source)
+}
+
+/// Declares a module exporting `bytes` as a `Uint8Array`, followed by `trailer`.
+pub(crate) fn declare_bytes_module<'js>(
+    ctx: &Ctx<'js>,
+    name: &str,
+    bytes: &[u8],
+    trailer: &str,
+) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+    let module = Module::declare(ctx.clone(), name, format!("{BYTES_MODULE_SOURCE}{trailer}"))?;
+    let buffer = rquickjs::ArrayBuffer::new(ctx.clone(), bytes.to_vec())?;
+    let meta: Object = module.meta()?;
+    meta.set(BYTES_HOOK, buffer)?;
+    Ok(module)
+}
+
+/// Quotes `value` as a double-quoted JavaScript string literal.
+pub(crate) fn js_string_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            '\t' => literal.push_str("\\t"),
+            '\u{2028}' => literal.push_str("\\u2028"),
+            '\u{2029}' => literal.push_str("\\u2029"),
+            c if c.is_control() => literal.push_str(&format!("\\u{:04x}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
+
+/// Reads the `type` import attribute.
+///
+/// Returns `Ok(None)` when no `type` is given, and an error message for types
+/// this runtime does not support.
+pub(crate) fn import_attribute_kind(
+    attributes: Option<&ImportAttributes<'_>>,
+) -> Result<Option<JsModuleKind>, String> {
+    let Some(attributes) = attributes else {
+        return Ok(None);
+    };
+    match attributes.get_type() {
+        Ok(Some(value)) => JsModuleKind::from_import_type(&value)
+            .map(Some)
+            .ok_or_else(|| format!("Unsupported import attribute type '{}'", value)),
+        Ok(None) => Ok(None),
+        Err(e) => Err(format!("Invalid import attributes: {}", e)),
+    }
+}
+
+/// Rejects an import of `name` whose `type` attribute does not match `kind`.
+///
+/// JavaScript modules take no `type`, and every other kind requires its own.
+/// The engine's own imports carry no attributes and may load any kind.
+fn check_import_kind(
+    ctx: &Ctx<'_>,
+    base: &str,
+    name: &str,
+    kind: JsModuleKind,
+    attributes: Option<&ImportAttributes<'_>>,
+) -> rquickjs::Result<()> {
+    let requested = import_attribute_kind(attributes)
+        .map_err(|message| rquickjs::Error::new_resolving_message(base, name, message))?;
+    if requested.is_none() && is_host_import(ctx, name) {
+        return Ok(());
+    }
+    let requested = requested.unwrap_or_default();
+    if requested == kind {
+        return Ok(());
+    }
+    let message = match kind.import_type() {
+        Some(expected) => format!("Module must be imported with type '{expected}'"),
+        None => format!(
+            "JavaScript module cannot be imported with type '{}'",
+            requested.import_type().unwrap_or_default()
+        ),
+    };
+    Err(rquickjs::Error::new_resolving_message(base, name, message))
 }
 
 // SAFETY: This type owns only Rust byte buffers and contains no context-bound
@@ -63,20 +278,40 @@ pub struct DynamicModuleResolver {}
 
 /// Tracks dynamic modules that have already been loaded into the QuickJS module cache.
 ///
-/// Once a module name enters this set, it cannot be replaced or unloaded without
-/// recreating the context.
+/// QuickJS never evicts a loaded module, so a reloaded module is loaded again
+/// under a new internal name (`name?fjs-reload=N`). This tracker records the
+/// current generation of each module, every module any loader produced, and
+/// the imports resolved between modules so reloads can find dependents and
+/// `module_graph()` can report them.
 #[frb(ignore)]
 #[derive(Debug, Default)]
 pub struct LoadedDynamicModules {
     names: RwLock<HashSet<String>>,
+    linked: RwLock<HashSet<String>>,
+    generations: RwLock<HashMap<String, u32>>,
+    imports: RwLock<HashMap<String, BTreeSet<String>>>,
+    outcomes: RwLock<HashMap<String, ModuleOutcome>>,
+    host_import: RwLock<Option<String>>,
 }
 
-// SAFETY: This type owns only a lock-protected set of Rust strings and contains
-// no context-bound JavaScript handles, so it is unchanged across JS lifetimes.
+/// How evaluating a module turned out, as observed by the engine.
+#[derive(Debug, Clone)]
+enum ModuleOutcome {
+    /// Evaluated successfully, with the export names when they are known.
+    Evaluated(Vec<String>),
+    /// Loading or evaluation failed.
+    Errored,
+}
+
+// SAFETY: This type owns only lock-protected Rust strings and counters and
+// contains no context-bound JavaScript handles, so it is unchanged across JS lifetimes.
 unsafe impl<'js> JsLifetime<'js> for LoadedDynamicModules {
     type Changed<'to> = LoadedDynamicModules;
 }
 
+/// Separator between a module name and its reload generation in internal names.
+const RELOAD_GENERATION_MARKER: &str = "?fjs-reload=";
+
 impl LoadedDynamicModules {
     fn insert(&self, name: impl Into<String>) {
         self.names
@@ -85,6 +320,13 @@ impl LoadedDynamicModules {
             .insert(name.into());
     }
 
+    fn remove(&self, name: &str) {
+        self.names
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(name);
+    }
+
     fn contains(&self, name: &str) -> bool {
         self.names
             .read()
@@ -103,6 +345,190 @@ impl LoadedDynamicModules {
         names.sort();
         names
     }
+
+    fn generation(&self, name: &str) -> u32 {
+        self.generations
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn bump_generation(&self, name: &str) {
+        *self
+            .generations
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(name.to_string())
+            .or_default() += 1;
+    }
+
+    fn record_import(&self, importer: &str, imported: &str) {
+        self.imports
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(importer.to_string())
+            .or_default()
+            .insert(imported.to_string());
+    }
+
+    fn clear_imports(&self, importer: &str) {
+        self.imports
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(importer);
+    }
+
+    fn mark_linked(&self, name: &str) {
+        self.linked
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(name.to_string());
+    }
+
+    fn is_linked(&self, name: &str) -> bool {
+        self.linked
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .contains(name)
+    }
+
+    fn linked_snapshot(&self) -> Vec<String> {
+        self.linked
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    fn imports_of(&self, name: &str) -> Vec<String> {
+        self.imports
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(name)
+            .map_or_else(Vec::new, |imports| imports.iter().cloned().collect())
+    }
+
+    fn outcome(&self, name: &str) -> Option<ModuleOutcome> {
+        self.outcomes
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(name)
+            .cloned()
+    }
+
+    fn record_errored(&self, name: &str) {
+        self.outcomes
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(name.to_string(), ModuleOutcome::Errored);
+    }
+
+    /// Records that `name` evaluated, along with everything it imports.
+    ///
+    /// A module only finishes evaluating after its static imports did, so
+    /// they are marked too; their export names stay unknown unless recorded
+    /// on their own.
+    fn record_evaluated(&self, name: &str, exports: Option<Vec<String>>) {
+        let imports = self
+            .imports
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut outcomes = self
+            .outcomes
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match exports {
+            Some(exports) => {
+                outcomes.insert(name.to_string(), ModuleOutcome::Evaluated(exports));
+            }
+            None => {
+                outcomes
+                    .entry(name.to_string())
+                    .and_modify(|outcome| {
+                        if matches!(outcome, ModuleOutcome::Errored) {
+                            *outcome = ModuleOutcome::Evaluated(Vec::new());
+                        }
+                    })
+                    .or_insert_with(|| ModuleOutcome::Evaluated(Vec::new()));
+            }
+        }
+        let mut pending: Vec<&String> = imports.get(name).into_iter().flatten().collect();
+        let mut seen: HashSet<&String> = pending.iter().copied().collect();
+        while let Some(imported) = pending.pop() {
+            outcomes
+                .entry(imported.clone())
+                .or_insert_with(|| ModuleOutcome::Evaluated(Vec::new()));
+            for next in imports.get(imported).into_iter().flatten() {
+                if seen.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+    }
+
+    fn dependents(&self, name: &str) -> Vec<String> {
+        let imports = self
+            .imports
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut ordered = vec![name.to_string()];
+        let mut seen: HashSet<String> = ordered.iter().cloned().collect();
+        let mut index = 0;
+        while index < ordered.len() {
+            let current = ordered[index].clone();
+            let mut importers: Vec<_> = imports
+                .iter()
+                .filter(|(importer, imported)| {
+                    imported.contains(&current) && !seen.contains(importer.as_str())
+                })
+                .map(|(importer, _)| importer.clone())
+                .collect();
+            importers.sort();
+            for importer in importers {
+                seen.insert(importer.clone());
+                ordered.push(importer);
+            }
+            index += 1;
+        }
+        ordered
+    }
+}
+
+/// Runs `import` with `name` marked as imported by the engine rather than by
+/// JavaScript, so it resolves without a `type` attribute whatever its kind.
+///
+/// QuickJS resolves the whole graph before `Module::import` returns, and
+/// only the top-level specifier is exempt.
+pub(crate) fn import_from_host<R>(ctx: &Ctx<'_>, name: &str, import: impl FnOnce() -> R) -> R {
+    let Some(loaded_modules) = ctx.userdata::<LoadedDynamicModules>() else {
+        return import();
+    };
+    let previous = loaded_modules
+        .host_import
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .replace(name.to_string());
+    let result = import();
+    *loaded_modules
+        .host_import
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = previous;
+    result
+}
+
+fn is_host_import(ctx: &Ctx<'_>, name: &str) -> bool {
+    ctx.userdata::<LoadedDynamicModules>()
+        .is_some_and(|loaded_modules| {
+            loaded_modules
+                .host_import
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .as_deref()
+                == Some(name)
+        })
 }
 
 pub(crate) fn mark_dynamic_module_loaded(ctx: &Ctx<'_>, name: &str) {
@@ -116,11 +542,207 @@ pub(crate) fn is_dynamic_module_loaded(ctx: &Ctx<'_>, name: &str) -> bool {
         .is_some_and(|loaded_modules| loaded_modules.contains(name))
 }
 
+/// Returns whether `name` is in the QuickJS module cache, whether a loader
+/// produced it or `evaluate_module()` evaluated it directly.
+pub(crate) fn is_module_loaded(ctx: &Ctx<'_>, name: &str) -> bool {
+    ctx.userdata::<LoadedDynamicModules>()
+        .is_some_and(|loaded_modules| {
+            loaded_modules.is_linked(name) || loaded_modules.contains(name)
+        })
+}
+
 pub(crate) fn get_loaded_dynamic_module_names(ctx: &Ctx<'_>) -> Vec<String> {
     ctx.userdata::<LoadedDynamicModules>()
         .map_or_else(Vec::new, |loaded_modules| loaded_modules.snapshot())
 }
 
+/// Returns the name a dynamic module is currently registered under in QuickJS.
+///
+/// This is the public name until the module has been reloaded at least once.
+fn dynamic_module_internal_name(ctx: &Ctx<'_>, name: &str) -> String {
+    match ctx
+        .userdata::<LoadedDynamicModules>()
+        .map_or(0, |loaded_modules| loaded_modules.generation(name))
+    {
+        0 => name.to_string(),
+        generation => format!("{name}{RELOAD_GENERATION_MARKER}{generation}"),
+    }
+}
+
+/// Strips the reload generation from an internal dynamic module name.
+pub(crate) fn dynamic_module_public_name(name: &str) -> &str {
+    match name.rsplit_once(RELOAD_GENERATION_MARKER) {
+        Some((public, generation))
+            if !generation.is_empty() && generation.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            public
+        }
+        _ => name,
+    }
+}
+
+/// Returns `name` followed by every dynamic module that transitively imports it.
+///
+/// Dependents are ordered breadth-first, so each module appears after the
+/// module it imports.
+pub(crate) fn dynamic_module_dependents(ctx: &Ctx<'_>, name: &str) -> Vec<String> {
+    ctx.userdata::<LoadedDynamicModules>()
+        .map_or_else(|| vec![name.to_string()], |loaded| loaded.dependents(name))
+}
+
+/// Records that `base` imports the module `resolved` resolved to.
+fn record_module_import(ctx: &Ctx<'_>, base: &str, resolved: &str) {
+    if let Some(loaded_modules) = ctx.userdata::<LoadedDynamicModules>() {
+        loaded_modules.record_import(
+            dynamic_module_public_name(base),
+            dynamic_module_public_name(resolved),
+        );
+    }
+}
+
+/// Records that a loader produced `name`.
+fn record_module_linked(ctx: &Ctx<'_>, name: &str) {
+    if let Some(loaded_modules) = ctx.userdata::<LoadedDynamicModules>() {
+        loaded_modules.mark_linked(dynamic_module_public_name(name));
+    }
+}
+
+/// Records that the engine imported or evaluated `name` successfully.
+///
+/// `namespace` lists the module's exports when the engine holds it.
+pub(crate) fn record_module_evaluated(ctx: &Ctx<'_>, name: &str, namespace: Option<&Object<'_>>) {
+    if let Some(loaded_modules) = ctx.userdata::<LoadedDynamicModules>() {
+        let exports = namespace.map(|namespace| {
+            let mut exports: Vec<String> =
+                namespace.keys::<String>().filter_map(Result::ok).collect();
+            exports.sort();
+            exports
+        });
+        let name = dynamic_module_public_name(name);
+        loaded_modules.mark_linked(name);
+        loaded_modules.record_evaluated(name, exports);
+    }
+}
+
+/// Records that importing or evaluating a loaded module `name` failed.
+pub(crate) fn record_module_errored(ctx: &Ctx<'_>, name: &str) {
+    if let Some(loaded_modules) = ctx.userdata::<LoadedDynamicModules>() {
+        let name = dynamic_module_public_name(name);
+        if loaded_modules.is_linked(name) || loaded_modules.contains(name) {
+            loaded_modules.record_errored(name);
+        }
+    }
+}
+
+/// Tracker state of a set of dynamic modules, captured before a reload so a
+/// failed reload can put it back.
+#[derive(Debug)]
+pub(crate) struct DynamicModuleSnapshot {
+    modules: Vec<ModuleState>,
+}
+
+#[derive(Debug)]
+struct ModuleState {
+    name: String,
+    loaded: bool,
+    linked: bool,
+    generation: u32,
+    imports: Option<BTreeSet<String>>,
+    outcome: Option<ModuleOutcome>,
+}
+
+impl DynamicModuleSnapshot {
+    /// Captures the tracker state of `names`.
+    pub(crate) fn capture(ctx: &Ctx<'_>, names: &[String]) -> Self {
+        let Some(tracker) = ctx.userdata::<LoadedDynamicModules>() else {
+            return Self {
+                modules: Vec::new(),
+            };
+        };
+        let imports = tracker
+            .imports
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let modules = names
+            .iter()
+            .map(|name| ModuleState {
+                name: name.clone(),
+                loaded: tracker.contains(name),
+                linked: tracker.is_linked(name),
+                generation: tracker.generation(name),
+                imports: imports.get(name).cloned(),
+                outcome: tracker.outcome(name),
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// Puts the captured state back, so imports resolve to the instances that
+    /// were current when the snapshot was taken.
+    pub(crate) fn restore(self, ctx: &Ctx<'_>) {
+        let Some(tracker) = ctx.userdata::<LoadedDynamicModules>() else {
+            return;
+        };
+        for state in self.modules {
+            let write = |set: &RwLock<HashSet<String>>, present: bool| {
+                let mut set = set
+                    .write()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                if present {
+                    set.insert(state.name.clone());
+                } else {
+                    set.remove(&state.name);
+                }
+            };
+            write(&tracker.names, state.loaded);
+            write(&tracker.linked, state.linked);
+            tracker
+                .generations
+                .write()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .insert(state.name.clone(), state.generation);
+            let mut imports = tracker
+                .imports
+                .write()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            match state.imports {
+                Some(set) => imports.insert(state.name.clone(), set),
+                None => imports.remove(&state.name),
+            };
+            let mut outcomes = tracker
+                .outcomes
+                .write()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            match state.outcome {
+                Some(outcome) => outcomes.insert(state.name, outcome),
+                None => outcomes.remove(&state.name),
+            };
+        }
+    }
+}
+
+/// Invalidates a loaded dynamic module so its next import loads it again.
+///
+/// The previous instance stays alive for anything that still references it,
+/// but new imports resolve to a fresh generation.
+pub(crate) fn invalidate_dynamic_module(ctx: &Ctx<'_>, name: &str) {
+    if let Some(loaded_modules) = ctx.userdata::<LoadedDynamicModules>() {
+        loaded_modules.remove(name);
+        loaded_modules
+            .linked
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(name);
+        loaded_modules.clear_imports(name);
+        loaded_modules
+            .outcomes
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(name);
+        loaded_modules.bump_generation(name);
+    }
+}
+
 /// Resolves a relative specifier (`./x`, `../y`) against the importing
 /// module's path. `base` is the importer; its last segment is dropped before
 /// applying `name`'s segments. A rooted base remains rooted after resolution.
@@ -171,25 +793,32 @@ impl Resolver for DynamicModuleResolver {
     ///
     /// # Returns
     ///
-    /// Returns the resolved module name if found in storage.
+    /// Returns the resolved module name if found in storage, or an error when
+    /// the import's `type` attribute does not match the module's kind.
     fn resolve<'js>(
         &mut self,
         ctx: &Ctx<'js>,
         base: &str,
         name: &str,
-        _attributes: Option<ImportAttributes<'js>>,
+        attributes: Option<ImportAttributes<'js>>,
     ) -> rquickjs::Result<String> {
         if let Some(modules_storage) = ctx.userdata::<DynamicModuleStorage>() {
             let modules = modules_storage
                 .read()
                 .unwrap_or_else(std::sync::PoisonError::into_inner);
-            if modules.contains_key(name) {
-                return Ok(name.to_string());
-            }
-            if let Some(normalized) = normalize_dynamic_module_name(base, name)
-                && modules.contains_key(&normalized)
-            {
-                return Ok(normalized);
+            let resolved = if modules.contains_key(name) {
+                Some(name.to_string())
+            } else {
+                normalize_dynamic_module_name(dynamic_module_public_name(base), name)
+                    .filter(|normalized| modules.contains_key(normalized))
+            };
+            if let Some(resolved) = resolved {
+                let kind = modules
+                    .get(&resolved)
+                    .and_then(DynamicModuleEntry::kind)
+                    .unwrap_or_default();
+                check_import_kind(ctx, base, name, kind, attributes.as_ref())?;
+                return Ok(dynamic_module_internal_name(ctx, &resolved));
             }
         }
         // Not found in dynamic storage, let other resolvers try
@@ -216,7 +845,7 @@ impl Loader for DynamicModuleLoader {
     ///
     /// - `ctx`: The JavaScript context
     /// - `name`: The module name to load
-    /// - `_attributes`: Import attributes (not used for dynamic modules)
+    /// - `attributes`: Import attributes, already checked against the module's kind
     ///
     /// # Returns
     ///
@@ -227,16 +856,37 @@ impl Loader for DynamicModuleLoader {
         name: &str,
         _attributes: Option<ImportAttributes<'js>>,
     ) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+        let public_name = dynamic_module_public_name(name);
         if let Some(modules_storage) = ctx.userdata::<DynamicModuleStorage>() {
             let entry = modules_storage
                 .read()
                 .unwrap_or_else(std::sync::PoisonError::into_inner)
-                .get(name)
+                .get(public_name)
                 .cloned();
             if let Some(entry) = entry {
                 let module = match entry {
                     DynamicModuleEntry::Source(source) => {
-                        Module::declare(ctx.clone(), name, source)?
+                        let module = match ctx.userdata::<ModuleBytecodeCache>() {
+                            Some(cache) if public_name == name => declare_cached_module(
+                                ctx,
+                                &cache,
+                                name,
+                                source,
+                                EVALUATION_TRAILER.as_bytes(),
+                            )?,
+                            _ => {
+                                let source = instrument_module_source(ctx, public_name, source);
+                                Module::declare(ctx.clone(), name, with_evaluation_trailer(source))?
+                            }
+                        };
+                        watch_evaluation(ctx, &module, public_name)?;
+                        module
+                    }
+                    DynamicModuleEntry::Bytecode(_) if public_name != name => {
+                        return Err(rquickjs::Error::new_loading_message(
+                            name,
+                            "Bytecode modules cannot be reloaded",
+                        ));
                     }
                     DynamicModuleEntry::Bytecode(bytecode) => {
                         let module = load_module_bytecode_checked(ctx.clone(), name, &bytecode)?;
@@ -252,8 +902,15 @@ impl Loader for DynamicModuleLoader {
                         }
                         module
                     }
+                    data => {
+                        let (kind, bytes) = data.data().unwrap_or_default();
+                        let module =
+                            declare_data_module(ctx, name, kind, bytes, EVALUATION_TRAILER)?;
+                        watch_evaluation(ctx, &module, public_name)?;
+                        module
+                    }
                 };
-                mark_dynamic_module_loaded(ctx, name);
+                mark_dynamic_module_loaded(ctx, public_name);
                 return Ok(module);
             }
         }
@@ -261,6 +918,438 @@ impl Loader for DynamicModuleLoader {
     }
 }
 
+/// Property of `import.meta` holding the hook run by [`EVALUATION_TRAILER`].
+const EVALUATION_HOOK: &str = "__fjs_evaluated__";
+
+/// Appended to module sources produced by [`DynamicModuleLoader`]. It runs
+/// once the module body, including any top-level `await`, has completed, and
+/// removes the hook before calling it so module code cannot reach it later.
+/// It starts on a new line, so positions in the original source are kept.
+const EVALUATION_TRAILER: &str = "\n;{ const done = import.meta.__fjs_evaluated__; delete import.meta.__fjs_evaluated__; done?.(); }\n";
+
+fn with_evaluation_trailer(mut source: Vec<u8>) -> Vec<u8> {
+    source.extend_from_slice(EVALUATION_TRAILER.as_bytes());
+    source
+}
+
+/// Installs the hook that records `name` as evaluated when its trailer runs,
+/// so modules imported only from JavaScript report their real status.
+fn watch_evaluation<'js>(
+    ctx: &Ctx<'js>,
+    module: &Module<'js, rquickjs::module::Declared>,
+    name: &str,
+) -> rquickjs::Result<()> {
+    let name = name.to_string();
+    let hook = Function::new(ctx.clone(), move |ctx: Ctx<'_>| {
+        record_module_evaluated(&ctx, &name, None);
+    })?;
+    let meta: Object = module.meta()?;
+    meta.set(EVALUATION_HOOK, hook)
+}
+
+/// A resolver for JSON, text, and byte files imported with a `type` attribute.
+///
+/// Only imports carrying a non-JavaScript `type` attribute are handled here;
+/// everything else falls through to the regular file resolver. Relative names
+/// are resolved against the importing module.
+#[derive(Debug, Default)]
+#[frb(ignore)]
+pub struct TypedFileResolver {}
+
+impl Resolver for TypedFileResolver {
+    fn resolve<'js>(
+        &mut self,
+        _ctx: &Ctx<'js>,
+        base: &str,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> rquickjs::Result<String> {
+        let kind = import_attribute_kind(attributes.as_ref())
+            .map_err(|message| rquickjs::Error::new_resolving_message(base, name, message))?;
+        if kind.is_none_or(|kind| kind == JsModuleKind::JavaScript) {
+            return Err(rquickjs::Error::new_resolving(base, name));
+        }
+        let path = if name.starts_with('.') {
+            resolve_relative_specifier(base, name)
+        } else {
+            name.to_string()
+        };
+        if std::path::Path::new(&path).is_file() {
+            return Ok(path);
+        }
+        Err(rquickjs::Error::new_resolving(base, name))
+    }
+}
+
+/// A loader for JSON, text, and byte files imported with a `type` attribute.
+///
+/// Files are read with the same size limit as other source files and declared
+/// as a module with a single default export.
+#[derive(Debug, Default)]
+#[frb(ignore)]
+pub struct TypedFileLoader {}
+
+impl Loader for TypedFileLoader {
+    fn load<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+        let kind = import_attribute_kind(attributes.as_ref())
+            .map_err(|message| rquickjs::Error::new_loading_message(name, message))?;
+        let Some(kind) = kind.filter(|kind| *kind != JsModuleKind::JavaScript) else {
+            return Err(rquickjs::Error::new_loading(name));
+        };
+        let bytes = get_raw_source_code_sync(JsCode::Path(name.to_string()))
+            .map_err(|e| rquickjs::Error::new_loading_message(name, e.to_string()))?;
+        declare_data_module(ctx, name, kind, &bytes, "")
+    }
+}
+
+/// A resolver for the modules a runtime is created with.
+///
+/// Imports must carry the `type` attribute matching each module's kind.
+#[derive(Debug, Default)]
+#[frb(ignore)]
+pub struct NamedModuleResolver {
+    kinds: HashMap<String, JsModuleKind>,
+}
+
+impl NamedModuleResolver {
+    /// Adds a module of `kind` under `name`.
+    pub fn with_module(mut self, name: impl Into<String>, kind: JsModuleKind) -> Self {
+        self.kinds.insert(name.into(), kind);
+        self
+    }
+}
+
+impl Resolver for NamedModuleResolver {
+    fn resolve<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        base: &str,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> rquickjs::Result<String> {
+        let resolved = if self.kinds.contains_key(name) {
+            Some(name.to_string())
+        } else {
+            normalize_dynamic_module_name(dynamic_module_public_name(base), name)
+                .filter(|normalized| self.kinds.contains_key(normalized))
+        };
+        let Some(resolved) = resolved else {
+            return Err(rquickjs::Error::new_resolving(base, name));
+        };
+        check_import_kind(ctx, base, name, self.kinds[&resolved], attributes.as_ref())?;
+        Ok(resolved)
+    }
+}
+
+/// A loader for the modules a runtime is created with.
+///
+/// JavaScript, JSON, and text modules hold their module source; byte modules
+/// hold their raw content and are declared with [`declare_bytes_module`].
+#[derive(Debug, Default)]
+#[frb(ignore)]
+pub struct NamedModuleLoader {
+    modules: HashMap<String, (JsModuleKind, Vec<u8>)>,
+}
+
+impl NamedModuleLoader {
+    /// Adds a module of `kind` under `name`.
+    pub fn with_module(
+        mut self,
+        name: impl Into<String>,
+        kind: JsModuleKind,
+        content: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.modules.insert(name.into(), (kind, content.into()));
+        self
+    }
+}
+
+impl Loader for NamedModuleLoader {
+    fn load<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        name: &str,
+        _attributes: Option<ImportAttributes<'js>>,
+    ) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+        match self.modules.get(name) {
+            Some((JsModuleKind::Bytes, bytes)) => declare_bytes_module(ctx, name, bytes, ""),
+            Some((_, source)) => Module::declare(ctx.clone(), name, source.clone()),
+            None => Err(rquickjs::Error::new_loading(name)),
+        }
+    }
+}
+
+/// Wraps the runtime resolver stack, refuses imports from isolated
+/// evaluation contexts and specifiers naming an internal reload generation,
+/// and records every resolved import.
+#[derive(Debug, Default)]
+#[frb(ignore)]
+pub struct TrackingResolver<R>(pub R);
+
+impl<R: Resolver> Resolver for TrackingResolver<R> {
+    fn resolve<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        base: &str,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> rquickjs::Result<String> {
+        if crate::runtime::compartment::is_isolated(ctx) {
+            return Err(rquickjs::Error::new_resolving_message(
+                base,
+                name,
+                "Imports are not available in isolated evaluation",
+            ));
+        }
+        if name.contains(RELOAD_GENERATION_MARKER) {
+            return Err(rquickjs::Error::new_resolving_message(
+                base,
+                name,
+                "Module specifiers cannot contain the reserved reload marker",
+            ));
+        }
+        let resolved = self.0.resolve(ctx, base, name, attributes)?;
+        record_module_import(ctx, base, &resolved);
+        Ok(resolved)
+    }
+}
+
+/// Wraps the runtime loader stack, serves the `timers` builtin from the
+/// virtual clock in deterministic mode, and records every module it produces.
+#[derive(Debug, Default)]
+#[frb(ignore)]
+pub struct TrackingLoader<L>(pub L);
+
+impl<L: Loader> Loader for TrackingLoader<L> {
+    fn load<'js>(
+        &mut self,
+        ctx: &Ctx<'js>,
+        name: &str,
+        attributes: Option<ImportAttributes<'js>>,
+    ) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+        let started = std::time::Instant::now();
+        let module = in_span(SpanKind::ModuleLoad, name, || {
+            crate::runtime::clock::declare_timers_module(ctx, name)
+                .unwrap_or_else(|| self.0.load(ctx, name, attributes))
+        })?;
+        if let Some(metrics) = ctx.userdata::<RuntimeMetrics>() {
+            metrics.record_module_load(started.elapsed());
+        }
+        record_module_linked(ctx, name);
+        Ok(module)
+    }
+}
+
+/// Where a module's code came from.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum JsModuleOrigin {
+    /// Declared at runtime from source text (including JSON, text, and bytes modules).
+    Source,
+    /// Declared at runtime from precompiled QuickJS bytecode.
+    Bytecode,
+    /// A builtin module or a module passed to `JsEngine.create(modules: ...)`.
+    Builtin,
+    /// Loaded from the file system by path.
+    File,
+}
+
+/// Lifecycle state of a module.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum JsModuleStatus {
+    /// Declared but not imported yet.
+    Declared,
+    /// Loaded and linked; its evaluation has not been observed to complete.
+    Linked,
+    /// Evaluated successfully; exports are available.
+    Evaluated,
+    /// Loading or evaluation failed.
+    Errored,
+}
+
+/// Kind of value a module exports.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum JsModuleExportKind {
+    /// A plain function.
+    Function,
+    /// An `async` function.
+    AsyncFunction,
+    /// A generator function, sync or async.
+    GeneratorFunction,
+    /// A class.
+    Class,
+    /// Any other value.
+    Value,
+}
+
+/// An export returned by `JsEngine::module_exports()`.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct JsModuleExport {
+    /// The export name; `default` for the default export.
+    pub name: String,
+    /// What kind of value the export holds.
+    pub kind: JsModuleExportKind,
+}
+
+/// Classifies an exported value by its kind.
+const CLASSIFY_EXPORT: &str = r#"
+(value) => {
+  if (typeof value !== "function") return "value";
+  if (/^class[\s{]/.test(Function.prototype.toString.call(value))) return "class";
+  const kind = Object.getPrototypeOf(value)?.constructor?.name;
+  if (kind === "AsyncFunction") return "async";
+  if (kind === "GeneratorFunction" || kind === "AsyncGeneratorFunction") return "generator";
+  return "function";
+}
+"#;
+
+/// Lists the exports of an evaluated module namespace, sorted by name.
+pub(crate) fn describe_exports<'js>(
+    ctx: &Ctx<'js>,
+    namespace: &Object<'js>,
+) -> Result<Vec<JsModuleExport>, JsError> {
+    let classify = ctx
+        .eval::<Function, _>(CLASSIFY_EXPORT)
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to classify exports: {e}")))?;
+    let mut exports = Vec::new();
+    for name in namespace.keys::<String>() {
+        let name = name.catch(ctx).map_err(|e| JsError::from_caught(ctx, e))?;
+        let kind = namespace
+            .get::<_, Value>(&name)
+            .and_then(|value| classify.call::<_, String>((value,)))
+            .catch(ctx)
+            .map_err(|e| JsError::from_caught(ctx, e))?;
+        let kind = match kind.as_str() {
+            "class" => JsModuleExportKind::Class,
+            "async" => JsModuleExportKind::AsyncFunction,
+            "generator" => JsModuleExportKind::GeneratorFunction,
+            "function" => JsModuleExportKind::Function,
+            _ => JsModuleExportKind::Value,
+        };
+        exports.push(JsModuleExport { name, kind });
+    }
+    exports.sort();
+    Ok(exports)
+}
+
+/// Converts a module namespace to an object of its exports.
+///
+/// Exported functions become [`JsValue::Function`] handles named after the
+/// export, which can be passed to `call()` or `invoke()` as the method.
+pub(crate) fn namespace_value<'js>(
+    ctx: &Ctx<'js>,
+    namespace: &Object<'js>,
+) -> Result<JsValue, JsError> {
+    let mut exports = HashMap::new();
+    for name in namespace.keys::<String>() {
+        let name = name.catch(ctx).map_err(|e| JsError::from_caught(ctx, e))?;
+        let value = namespace
+            .get::<_, Value>(&name)
+            .catch(ctx)
+            .map_err(|e| JsError::from_caught(ctx, e))?;
+        let value = if value.is_function() {
+            JsValue::Function(name.clone())
+        } else {
+            JsValue::from_js(ctx, value)
+                .catch(ctx)
+                .map_err(|e| JsError::from_caught(ctx, e))?
+        };
+        exports.insert(name, value);
+    }
+    Ok(JsValue::Object(exports))
+}
+
+/// A node in the module graph returned by `JsEngine::module_graph()`.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct JsModuleInfo {
+    /// The module name.
+    pub name: String,
+    /// Where the module's code came from.
+    pub origin: JsModuleOrigin,
+    /// Current lifecycle state.
+    pub status: JsModuleStatus,
+    /// Resolved names of the modules this module imports, sorted.
+    pub imports: Vec<String>,
+    /// Export names, sorted; empty unless the engine itself imported the module.
+    pub exports: Vec<String>,
+}
+
+/// Collects every declared or loaded module with its imports, exports, and state.
+///
+/// The graph is built from what the resolver and loaders recorded, without
+/// importing anything or running queued jobs. A loaded module reports
+/// `Evaluated` once its body has run, whoever imported it: declared source,
+/// JSON, text, and byte modules report completion themselves, and any module
+/// is `Evaluated` once a module statically importing it is. `Errored` is
+/// reported when an engine entry point observed the failure; a declared
+/// module whose evaluation failed only inside JavaScript, or a bytecode or
+/// file module only imported from JavaScript, stays `Linked`.
+pub(crate) fn collect_module_graph(ctx: &Ctx<'_>) -> Vec<JsModuleInfo> {
+    let Some(tracker) = ctx.userdata::<LoadedDynamicModules>() else {
+        return Vec::new();
+    };
+    let declared: HashMap<String, bool> = ctx
+        .userdata::<DynamicModuleStorage>()
+        .map(|storage| {
+            storage
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .iter()
+                .map(|(name, entry)| {
+                    (
+                        name.clone(),
+                        matches!(entry, DynamicModuleEntry::Bytecode(_)),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let builtins: HashSet<String> = ctx
+        .userdata::<ModuleNames>()
+        .map(|names| names.list.clone())
+        .unwrap_or_default();
+
+    let mut names: BTreeSet<String> = declared.keys().cloned().collect();
+    names.extend(tracker.linked_snapshot());
+
+    names
+        .into_iter()
+        .map(|name| {
+            let origin = match declared.get(&name) {
+                Some(true) => JsModuleOrigin::Bytecode,
+                Some(false) => JsModuleOrigin::Source,
+                None if builtins.contains(&name) => JsModuleOrigin::Builtin,
+                None => JsModuleOrigin::File,
+            };
+            let (status, exports) = match tracker.outcome(&name) {
+                Some(ModuleOutcome::Evaluated(exports)) => (JsModuleStatus::Evaluated, exports),
+                Some(ModuleOutcome::Errored) => (JsModuleStatus::Errored, Vec::new()),
+                None if tracker.is_linked(&name) || tracker.contains(&name) => {
+                    (JsModuleStatus::Linked, Vec::new())
+                }
+                None => (JsModuleStatus::Declared, Vec::new()),
+            };
+            JsModuleInfo {
+                imports: tracker.imports_of(&name),
+                name,
+                origin,
+                status,
+                exports,
+            }
+        })
+        .collect()
+}
+
 /// Stores a set of module names for a specific JavaScript context.
 ///
 /// This struct maintains a list of available module names within a
@@ -697,6 +1786,15 @@ impl JsBuiltinOptions {
         if self.temporal.unwrap_or(false) {
             builder = builder.with_global(llrt_temporal::init);
         }
+        if self.test.unwrap_or(false) {
+            if !self.assert.unwrap_or(false) {
+                builder = builder.with_module(llrt_assert::AssertModule);
+            }
+            builder = builder.with_module(ModuleInfo {
+                name: TEST_MODULE_NAME,
+                module: TestModule,
+            });
+        }
         if self.timers.unwrap_or(false) {
             builder = builder
                 .with_global(init_guarded_timers)
@@ -731,15 +1829,70 @@ fn init_guarded_timers(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
     install_timer_callback_error_guard(ctx)
 }
 
-fn record_timer_callback_error<'js>(ctx: Ctx<'js>, error: Value<'js>) -> rquickjs::Result<bool> {
+pub(crate) fn record_timer_callback_error<'js>(
+    ctx: Ctx<'js>,
+    error: Value<'js>,
+    function: String,
+    delay: Value<'js>,
+    timer_id: Value<'js>,
+) -> rquickjs::Result<bool> {
     if let Some(sink) = ctx.userdata::<crate::runtime::error_sink::RuntimeErrorSink>() {
-        sink.push(crate::runtime::error_sink::format_value(&ctx, error));
+        let origin = crate::api::error::JsUnhandledErrorOrigin::Timer {
+            function,
+            timer_id: timer_id
+                .as_number()
+                .filter(|id| *id >= 0.0 && id.fract() == 0.0)
+                .map(|id| id as u64),
+            delay_ms: delay.as_number(),
+        };
+        sink.push_value(&ctx, origin, error);
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
+/// Global holding the `pause`/`resume` controls of the guarded timers.
+const TIMER_CONTROL_GLOBAL: &str = "__fjs_timers__";
+
+/// Calls `method` on the timer controls of `ctx`, if the timers builtin is
+/// installed there.
+fn control_timers(ctx: &Ctx<'_>, method: &str) -> Result<(), JsError> {
+    let Ok(controls) = ctx.globals().get::<_, Object>(TIMER_CONTROL_GLOBAL) else {
+        return Ok(());
+    };
+    controls
+        .get::<_, Function>(method)
+        .and_then(|function| function.call::<_, ()>(()))
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to {method} timers: {e}")))
+}
+
+/// Freezes the delay timers of `ctx` at their remaining time.
+pub(crate) fn pause_timers(ctx: &Ctx<'_>) -> Result<(), JsError> {
+    control_timers(ctx, "pause")
+}
+
+/// Re-arms the delay timers of `ctx` with the time they had left when paused.
+pub(crate) fn resume_timers(ctx: &Ctx<'_>) -> Result<(), JsError> {
+    control_timers(ctx, "resume")
+}
+
+/// Returns the milliseconds until the earliest armed delay timer of `ctx` is
+/// due, or `None` when no timer is armed. Under a virtual clock the delay is
+/// measured in virtual time.
+pub(crate) fn next_timer_delay(ctx: &Ctx<'_>) -> Option<u64> {
+    let delay = match crate::runtime::clock::next_delay(ctx) {
+        Some(delay) => delay,
+        None => {
+            let controls = ctx.globals().get::<_, Object>(TIMER_CONTROL_GLOBAL).ok()?;
+            let next = controls.get::<_, Function>("next").ok()?;
+            next.call::<_, Option<f64>>(()).ok().flatten()
+        }
+    };
+    delay.map(|delay| delay.max(0.0).ceil() as u64)
+}
+
 fn install_timer_callback_error_guard(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
     let reporter = Function::new(ctx.clone(), record_timer_callback_error)?;
     ctx.globals()
@@ -750,45 +1903,130 @@ fn install_timer_callback_error_guard(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
           const reporter = globalThis.__fjsInstallTimerErrorReporter;
           delete globalThis.__fjsInstallTimerErrorReporter;
 
-          const guardDelayTimer = (nativeTimer) => function(callback, delay, ...args) {
+          const nativeSetTimeout = globalThis.setTimeout;
+          const nativeSetInterval = globalThis.setInterval;
+          const nativeClearTimeout = globalThis.clearTimeout;
+          const nativeClearInterval = globalThis.clearInterval;
+          // Delay timers by public id. The public id is the first native id,
+          // so it stays valid after a pause re-arms the native timer.
+          const timers = new Map();
+          let paused = false;
+
+          const guardDelayTimer = (nativeTimer, name) => function(callback, delay, ...args) {
             if (typeof callback !== "function") {
               return nativeTimer(callback, delay, ...args);
             }
-            return nativeTimer(function() {
+            const repeat = name === "setInterval";
+            const period = Math.max(0, Number(delay) || 0);
+            const entry = { id: undefined, native: undefined, interval: false, due: 0, remaining: period };
+            const run = function() {
               try {
                 return callback.apply(this, args);
               } catch (error) {
-                if (!reporter(error)) {
+                if (!reporter(error, name, Number(delay) || 0, entry.id)) {
                   throw error;
                 }
               }
-            }, delay);
+            };
+            const tick = function() {
+              if (!repeat) {
+                timers.delete(entry.id);
+              } else if (entry.interval) {
+                entry.due = Date.now() + period;
+              } else {
+                entry.arm(period);
+              }
+              return run.call(this);
+            };
+            entry.arm = (wait) => {
+              entry.due = Date.now() + wait;
+              entry.interval = repeat && wait === period;
+              entry.native = entry.interval ? nativeSetInterval(tick, period) : nativeSetTimeout(tick, wait);
+            };
+            entry.disarm = () => {
+              (entry.interval ? nativeClearInterval : nativeClearTimeout)(entry.native);
+            };
+            entry.arm(period);
+            entry.id = entry.native;
+            timers.set(entry.id, entry);
+            return entry.id;
           };
 
-          const guardImmediate = (nativeTimer) => function(callback, ...args) {
+          const guardClear = (nativeClear) => function(id) {
+            const entry = timers.get(id);
+            if (entry === undefined) {
+              return nativeClear(id);
+            }
+            timers.delete(id);
+            if (!paused) {
+              entry.disarm();
+            }
+          };
+
+          const guardImmediate = (nativeTimer, name) => function(callback, ...args) {
             if (typeof callback !== "function") {
               return nativeTimer(callback, ...args);
             }
-            return nativeTimer(function() {
+            let id;
+            id = nativeTimer(function() {
               try {
                 return callback.apply(this, args);
               } catch (error) {
-                if (!reporter(error)) {
+                if (!reporter(error, name, undefined, id)) {
                   throw error;
                 }
               }
             });
+            return id;
           };
 
-          if (typeof globalThis.setTimeout === "function") {
-            globalThis.setTimeout = guardDelayTimer(globalThis.setTimeout);
+          if (typeof nativeSetTimeout === "function") {
+            globalThis.setTimeout = guardDelayTimer(nativeSetTimeout, "setTimeout");
+          }
+          if (typeof nativeSetInterval === "function") {
+            globalThis.setInterval = guardDelayTimer(nativeSetInterval, "setInterval");
           }
-          if (typeof globalThis.setInterval === "function") {
-            globalThis.setInterval = guardDelayTimer(globalThis.setInterval);
+          if (typeof nativeClearTimeout === "function") {
+            globalThis.clearTimeout = guardClear(nativeClearTimeout);
+          }
+          if (typeof nativeClearInterval === "function") {
+            globalThis.clearInterval = guardClear(nativeClearInterval);
           }
           if (typeof globalThis.setImmediate === "function") {
-            globalThis.setImmediate = guardImmediate(globalThis.setImmediate);
+            globalThis.setImmediate = guardImmediate(globalThis.setImmediate, "setImmediate");
           }
+
+          // Freezes delay timers at their remaining time while the engine is
+          // paused, so intervals do not fire in a burst on resume, and reports
+          // the next deadline to hosts that pump the event loop themselves.
+          Object.defineProperty(globalThis, "__fjs_timers__", {
+            value: Object.freeze({
+              pause() {
+                if (paused) return;
+                paused = true;
+                const now = Date.now();
+                for (const entry of timers.values()) {
+                  entry.remaining = Math.max(0, entry.due - now);
+                  entry.disarm();
+                }
+              },
+              resume() {
+                if (!paused) return;
+                paused = false;
+                for (const entry of timers.values()) {
+                  entry.arm(entry.remaining);
+                }
+              },
+              next() {
+                if (paused || timers.size === 0) return undefined;
+                let due = Infinity;
+                for (const entry of timers.values()) {
+                  due = Math.min(due, entry.due);
+                }
+                return due - Date.now();
+              },
+            }),
+          });
         })();
         "#,
     )
@@ -0,0 +1,154 @@
+//! # CPU Profiles
+//!
+//! Types returned by `JsEngine.stopProfiling()`.
+//!
+//! A profile is a call tree built from periodic samples of the JavaScript
+//! call stack. It can be exported as a Chrome `.cpuprofile` (loadable in the
+//! Performance panel of Chrome DevTools or in VS Code) or as folded stacks
+//! for flame graph tools such as `inferno` and `flamegraph.pl`.
+
+use crate::api::module::js_string_literal;
+use flutter_rust_bridge::frb;
+use std::collections::HashMap;
+
+/// One node of a CPU profile call tree.
+///
+/// Line and column numbers are zero-based, matching the Chrome format, and
+/// `-1` when unknown.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsProfileNode {
+    /// Node id, unique within the profile. The root node has id `1`.
+    pub id: u32,
+    /// Id of the calling node, or `None` for the root.
+    pub parent_id: Option<u32>,
+    /// Module or script that was running, or a synthetic name such as
+    /// `(root)` or `(program)`. QuickJS does not expose function names to
+    /// the sampler.
+    pub function_name: String,
+    /// Module or script name the function was defined in.
+    pub url: String,
+    /// Zero-based line of the sampled position, or `-1`.
+    pub line_number: i32,
+    /// Zero-based column of the sampled position, or `-1`.
+    pub column_number: i32,
+    /// Number of samples taken while this node was on top of the stack.
+    pub hit_count: u64,
+}
+
+/// A sampled CPU profile.
+///
+/// ## Example
+///
+/// ```dart
+/// await engine.startProfiling();
+/// await engine.call(module: 'plugin', method: 'render');
+/// final profile = await engine.stopProfiling();
+/// await File('${dir.path}/render.cpuprofile')
+///     .writeAsString(profile.toCpuprofileJson());
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsCpuProfile {
+    /// Call tree nodes; parents always precede their children.
+    pub nodes: Vec<JsProfileNode>,
+    /// Leaf node id of each sample, in sampling order.
+    pub samples: Vec<u32>,
+    /// Microseconds between consecutive samples; the first entry is
+    /// relative to `start_time_us`.
+    pub time_deltas_us: Vec<u64>,
+    /// Profiling start, in microseconds since the Unix epoch.
+    pub start_time_us: u64,
+    /// Profiling end, in microseconds since the Unix epoch.
+    pub end_time_us: u64,
+}
+
+impl JsCpuProfile {
+    /// Serializes the profile in the Chrome DevTools `.cpuprofile` format.
+    #[frb(sync)]
+    pub fn to_cpuprofile_json(&self) -> String {
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for node in &self.nodes {
+            if let Some(parent) = node.parent_id {
+                children.entry(parent).or_default().push(node.id);
+            }
+        }
+        let mut script_ids: HashMap<&str, usize> = HashMap::new();
+
+        let mut json = String::with_capacity(128 * self.nodes.len() + 16 * self.samples.len());
+        json.push_str("{\"nodes\":[");
+        for (index, node) in self.nodes.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            let script_id = match node.url.as_str() {
+                "" => 0,
+                url => {
+                    let next = script_ids.len() + 1;
+                    *script_ids.entry(url).or_insert(next)
+                }
+            };
+            json.push_str(&format!(
+                "{{\"id\":{},\"callFrame\":{{\"functionName\":{},\"scriptId\":\"{}\",\"url\":{},\"lineNumber\":{},\"columnNumber\":{}}},\"hitCount\":{}",
+                node.id,
+                js_string_literal(&node.function_name),
+                script_id,
+                js_string_literal(&node.url),
+                node.line_number,
+                node.column_number,
+                node.hit_count
+            ));
+            if let Some(children) = children.get(&node.id) {
+                json.push_str(&format!(",\"children\":{}", json_list(children)));
+            }
+            json.push('}');
+        }
+        json.push_str(&format!(
+            "],\"startTime\":{},\"endTime\":{},\"samples\":{},\"timeDeltas\":{}}}",
+            self.start_time_us,
+            self.end_time_us,
+            json_list(&self.samples),
+            json_list(&self.time_deltas_us)
+        ));
+        json
+    }
+
+    /// Serializes the profile as folded stacks, one `frame;frame;frame count`
+    /// line per distinct stack, outermost frame first.
+    #[frb(sync)]
+    pub fn to_folded_stacks(&self) -> String {
+        let by_id: HashMap<u32, &JsProfileNode> =
+            self.nodes.iter().map(|node| (node.id, node)).collect();
+        let mut folded = String::new();
+        for node in self.nodes.iter().filter(|node| node.hit_count > 0) {
+            let mut frames = Vec::new();
+            let mut current = Some(node);
+            while let Some(frame) = current {
+                if frame.parent_id.is_some() {
+                    frames.push(folded_frame(frame));
+                }
+                current = frame
+                    .parent_id
+                    .and_then(|parent| by_id.get(&parent).copied());
+            }
+            frames.reverse();
+            folded.push_str(&frames.join(";"));
+            folded.push_str(&format!(" {}\n", node.hit_count));
+        }
+        folded
+    }
+}
+
+fn folded_frame(node: &JsProfileNode) -> String {
+    let name = node.function_name.replace(';', ":");
+    match (node.url.as_str(), node.line_number) {
+        ("", _) => name,
+        (url, line) if line >= 0 => format!("{name} ({}:{})", url.replace(';', ":"), line + 1),
+        (url, _) => format!("{name} ({})", url.replace(';', ":")),
+    }
+}
+
+fn json_list<T: std::fmt::Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+    format!("[{}]", items.join(","))
+}
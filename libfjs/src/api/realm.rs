@@ -0,0 +1,582 @@
+//! # Realms
+//!
+//! Additional JavaScript contexts created from an engine.
+//!
+//! A realm has its own globals, its own dynamic modules, and optionally its
+//! own bridge, but shares the engine's runtime: memory limits, the background
+//! driver, timers, and unhandled-error reporting apply to every realm of an
+//! engine together. Closing the engine makes its realms unusable.
+
+use crate::api::engine::{
+    JsEngine, JsEngineResources, declare_dynamic_modules_in, evaluate_dynamic_module_in,
+    register_fjs,
+};
+use crate::api::error::{JsError, JsResult};
+use crate::api::module::{DynamicModuleEntry, js_string_literal};
+use crate::api::runtime::{JsAsyncContext, call_module_method, result_from_promise};
+use crate::api::source::{JsCode, JsEvalOptions, JsModule, get_raw_source_code};
+use crate::api::value::JsValue;
+use crate::runtime::bindings::{bind_source, unbind};
+use crate::runtime::module_cache::ModuleBytecodeCache;
+use flutter_rust_bridge::{DartFnFuture, frb};
+use rquickjs::{CatchResultExt, Object, Persistent};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Instant;
+
+/// Options for `JsEngine.createRealm()`.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct JsRealmOptions {
+    /// Engine modules the realm can import.
+    ///
+    /// Each module is loaded in the engine context if needed and exposed in
+    /// the realm under the same name. The realm never receives engine
+    /// objects: exported functions become realm functions that call the
+    /// engine's instance, so they share its module state, while data exports,
+    /// arguments, return values, and errors cross as deep copies (plain
+    /// objects, arrays, dates, maps, sets, binary data, and promises that
+    /// settle with copies). Class instances lose their prototype when copied.
+    /// Data exports are copied when the realm is created; later changes in
+    /// the engine are only visible through exported functions.
+    pub shared_modules: Option<Vec<String>>,
+}
+
+/// An isolated JavaScript context sharing its engine's runtime.
+///
+/// ## Example
+///
+/// ```dart
+/// final realm = await engine.createRealm(
+///   options: JsRealmOptions(sharedModules: ['host-api']),
+/// );
+/// await realm.declareNewModule(module: JsModule.code(module: 'plugin', code: pluginSource));
+/// final result = await realm.call(module: 'plugin', method: 'run');
+/// await realm.close();
+/// ```
+#[frb(opaque)]
+pub struct JsRealm {
+    engine: Weak<JsEngineResources>,
+    context: RwLock<Option<JsAsyncContext>>,
+}
+
+/// A shared module captured from the engine context.
+struct SharedModule {
+    name: String,
+    exports: Vec<String>,
+    namespace: Persistent<Object<'static>>,
+}
+
+fn share_error(e: impl std::fmt::Display) -> JsError {
+    JsError::context(format!("Failed to share modules: {e}"))
+}
+
+/// Evaluates [`MEMBRANE`] in `ctx`.
+fn membrane<'js>(ctx: &rquickjs::Ctx<'js>) -> Result<Object<'js>, JsError> {
+    ctx.eval::<Object, _>(MEMBRANE)
+        .catch(ctx)
+        .map_err(|e| JsError::context(format!("Failed to install shared module membrane: {e}")))
+}
+
+impl JsRealm {
+    /// Creates a realm sharing the runtime of `resources`.
+    pub(crate) async fn create(
+        resources: &Arc<JsEngineResources>,
+        options: Option<JsRealmOptions>,
+    ) -> Result<Self, JsError> {
+        let shared_names = options
+            .and_then(|options| options.shared_modules)
+            .unwrap_or_default();
+        JsEngine::ensure_unique_module_names(shared_names.iter().map(String::as_str))?;
+
+        let shutdown = resources.runtime.shutdown();
+        let driver = resources.runtime.driver.clone();
+        let (cache, shared, engine_membrane) = resources
+            .context
+            .with_js(async move |ctx| {
+                let cache = ctx
+                    .userdata::<ModuleBytecodeCache>()
+                    .map(|cache| (*cache).clone());
+                let acknowledge = |source| driver.remove_error_source(source);
+                let mut shared = Vec::with_capacity(shared_names.len());
+                for name in shared_names {
+                    let namespace = crate::api::runtime::import_module_namespace(
+                        &ctx,
+                        &name,
+                        shutdown.clone(),
+                        &acknowledge,
+                    )
+                    .await?;
+                    let exports = namespace
+                        .keys::<String>()
+                        .collect::<rquickjs::Result<Vec<_>>>()
+                        .catch(&ctx)
+                        .map_err(|e| {
+                            JsError::module(
+                                Some(name.clone()),
+                                None,
+                                format!("Failed to read exports: {e}"),
+                            )
+                        })?;
+                    shared.push(SharedModule {
+                        name,
+                        exports,
+                        namespace: Persistent::save(&ctx, namespace),
+                    });
+                }
+                let membrane = if shared.is_empty() {
+                    None
+                } else {
+                    Some(Persistent::save(&ctx, membrane(&ctx)?))
+                };
+                Ok::<_, JsError>((cache, shared, membrane))
+            })
+            .await?;
+
+        let context = JsAsyncContext::from(&resources.runtime).await?;
+        if let Some(cache) = cache {
+            context.install_module_cache(cache).await?;
+        }
+        let attachment = context.global_attachment.clone();
+        let sources = context
+            .with_js(async move |ctx| {
+                if let Some(attachment) = &attachment {
+                    attachment.attach(&ctx).map_err(|e| {
+                        JsError::context(format!("Failed to attach global context: {e}"))
+                    })?;
+                }
+                let Some(engine_membrane) = engine_membrane else {
+                    return Ok(Vec::new());
+                };
+                let engine_membrane = engine_membrane
+                    .restore(&ctx)
+                    .catch(&ctx)
+                    .map_err(share_error)?;
+                let realm_membrane = membrane(&ctx)?;
+                let realm_copy = realm_membrane
+                    .get::<_, rquickjs::Function>("copy")
+                    .catch(&ctx)
+                    .map_err(share_error)?;
+                engine_membrane
+                    .get::<_, rquickjs::Function>("copy")
+                    .and_then(|engine_copy| {
+                        realm_membrane
+                            .get::<_, rquickjs::Function>("link")?
+                            .call::<_, ()>((engine_copy,))
+                    })
+                    .and_then(|()| {
+                        engine_membrane
+                            .get::<_, rquickjs::Function>("link")?
+                            .call::<_, ()>((realm_copy.clone(),))
+                    })
+                    .catch(&ctx)
+                    .map_err(share_error)?;
+                let registry = Object::new(ctx.clone())
+                    .and_then(|registry| {
+                        ctx.globals().set("__fjs_shared__", registry.clone())?;
+                        Ok(registry)
+                    })
+                    .map_err(share_error)?;
+                let mut sources = Vec::with_capacity(shared.len());
+                for module in shared {
+                    let namespace = module.namespace.restore(&ctx).map_err(|e| {
+                        JsError::module(
+                            Some(module.name.clone()),
+                            None,
+                            format!("Failed to share module: {e}"),
+                        )
+                    })?;
+                    realm_copy
+                        .call::<_, rquickjs::Value>((namespace,))
+                        .and_then(|copied| registry.set(module.name.as_str(), copied))
+                        .catch(&ctx)
+                        .map_err(|e| {
+                            JsError::module(
+                                Some(module.name.clone()),
+                                None,
+                                format!("Failed to share module: {e}"),
+                            )
+                        })?;
+                    sources.push((
+                        module.name.clone(),
+                        DynamicModuleEntry::Source(
+                            shared_module_source(&module.name, &module.exports).into_bytes(),
+                        ),
+                    ));
+                }
+                Ok::<_, JsError>(sources)
+            })
+            .await?;
+        if !sources.is_empty() {
+            declare_dynamic_modules_in(&context, sources).await?;
+        }
+
+        Ok(Self {
+            engine: Arc::downgrade(resources),
+            context: RwLock::new(Some(context)),
+        })
+    }
+
+    /// Returns the engine resources and the realm context, failing once
+    /// either side has been closed.
+    ///
+    /// Unhandled errors are queued per runtime and belong to the engine: a
+    /// realm refuses to run while any are pending but leaves them queued, so
+    /// the engine's next call still reports them.
+    fn ensure_open(&self) -> Result<(Arc<JsEngineResources>, JsAsyncContext), JsError> {
+        let resources = self
+            .engine
+            .upgrade()
+            .ok_or_else(|| JsError::engine("Engine is closed"))?;
+        let context = self
+            .context
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+            .ok_or_else(|| JsError::engine("Realm is closed"))?;
+        if resources.runtime.driver.paused() {
+            return Err(JsError::engine("Engine is paused"));
+        }
+        let errors = resources.runtime.driver.peek_errors();
+        if !errors.is_empty() {
+            return Err(JsError::runtime(JsEngine::format_unhandled_job_errors(
+                &errors,
+            )));
+        }
+        Ok((resources, context))
+    }
+
+    /// Returns whether the realm has been closed.
+    #[frb(sync, getter)]
+    pub fn closed(&self) -> bool {
+        self.engine.strong_count() == 0
+            || self
+                .context
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .is_none()
+    }
+
+    /// Installs `fjs.bridge_call()` in this realm.
+    ///
+    /// Each realm has its own bridge callback, so the Dart side can tell
+    /// tenants apart.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await realm.initBridge(bridge: (value) async {
+    ///   return JsResult.ok(await handleTenantCall(tenantId, value));
+    /// });
+    /// ```
+    pub async fn init_bridge(
+        &self,
+        bridge: impl Fn(JsValue) -> DartFnFuture<JsResult> + Sync + Send + 'static,
+    ) -> Result<(), JsError> {
+        let (resources, context) = self.ensure_open()?;
+        let bridge = resources.runtime.driver.recorder().wrap(Arc::new(
+            move |value: JsValue, _: Option<JsValue>| bridge(value),
+        ));
+        let shutdown = resources.runtime.shutdown();
+        let metrics = resources.runtime.driver.metrics();
+        let limits = resources.bridge_limits.clone();
+        context
+            .with_js(async move |ctx| {
+                if ctx.globals().contains_key("fjs").unwrap_or(false) {
+                    return Err(JsError::bridge("Realm bridge is already initialized"));
+                }
+                register_fjs(ctx.clone(), bridge, shutdown, metrics, limits)
+                    .map_err(|e| JsError::bridge(format!("Failed to register fjs bridge: {e}")))
+            })
+            .await
+    }
+
+    /// Evaluates JavaScript code in this realm.
+    ///
+    /// Behaves like `JsEngine.eval()`, with this realm's globals.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await realm.eval(source: JsCode.code('globalThis.tenant = "acme"'));
+    /// ```
+    pub async fn eval(
+        &self,
+        source: JsCode,
+        options: Option<JsEvalOptions>,
+    ) -> Result<JsValue, JsError> {
+        let (resources, context) = self.ensure_open()?;
+
+        let mut options = options.unwrap_or_default();
+        options.promise = Some(true);
+        let bindings = options.bindings.take();
+        let source_code = get_raw_source_code(source).await?;
+
+        let started = Instant::now();
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        let result = context
+            .with_foreground_js_result(async move |ctx, checkpoint| {
+                let source_code = match bind_source(&ctx, source_code, bindings) {
+                    Ok(source_code) => source_code,
+                    Err(e) => return JsResult::Err(e),
+                };
+                let res = ctx.eval_with_options(source_code, options.into());
+                unbind(&ctx);
+                let driver = driver.clone();
+                result_from_promise(&ctx, res, shutdown, move |source| {
+                    driver.remove_error_source_since(checkpoint, source);
+                })
+                .await
+            })
+            .await
+            .into_result();
+        resources
+            .runtime
+            .driver
+            .metrics()
+            .record_eval(started.elapsed());
+        result
+    }
+
+    /// Declares a module in this realm without executing it.
+    ///
+    /// The module is only visible to this realm.
+    pub async fn declare_new_module(&self, module: JsModule) -> Result<(), JsError> {
+        self.declare_new_modules(vec![module]).await
+    }
+
+    /// Declares several modules in this realm without executing them.
+    pub async fn declare_new_modules(&self, modules: Vec<JsModule>) -> Result<(), JsError> {
+        let (_resources, context) = self.ensure_open()?;
+        JsEngine::ensure_unique_module_names(modules.iter().map(|module| module.name.as_str()))?;
+
+        let mut entries = Vec::with_capacity(modules.len());
+        for module in modules {
+            let JsModule { name, source, kind } = module;
+            let source_code = get_raw_source_code(source).await?;
+            entries.push((name, DynamicModuleEntry::from_source(kind, source_code)));
+        }
+        declare_dynamic_modules_in(&context, entries).await
+    }
+
+    /// Registers a module in this realm and runs its top-level code.
+    pub async fn evaluate_module(&self, module: JsModule) -> Result<JsValue, JsError> {
+        let (_resources, context) = self.ensure_open()?;
+
+        let JsModule { name, source, kind } = module;
+        let source_code = get_raw_source_code(source).await?;
+        evaluate_dynamic_module_in(
+            &context,
+            name,
+            DynamicModuleEntry::from_source(kind, source_code),
+        )
+        .await
+    }
+
+    /// Calls a function exported by a module of this realm.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final total = await realm.call(
+    ///   module: 'plugin',
+    ///   method: 'total',
+    ///   params: [JsValue.integer(2)],
+    /// );
+    /// ```
+    pub async fn call(
+        &self,
+        module: String,
+        method: String,
+        params: Option<Vec<JsValue>>,
+    ) -> Result<JsValue, JsError> {
+        let (resources, context) = self.ensure_open()?;
+
+        let params = params.unwrap_or_default();
+        let started = Instant::now();
+        let driver = resources.runtime.driver.clone();
+        let shutdown = resources.runtime.shutdown();
+        let result = context
+            .with_foreground_js_result(async move |ctx, checkpoint| {
+                let driver = driver.clone();
+                call_module_method(&ctx, module, method, params, shutdown, move |source| {
+                    driver.remove_error_source_since(checkpoint, source);
+                })
+                .await
+            })
+            .await
+            .into_result();
+        resources
+            .runtime
+            .driver
+            .metrics()
+            .record_call(started.elapsed());
+        result
+    }
+
+    /// Closes the realm and releases its context.
+    ///
+    /// The engine and its other realms keep running. Later calls on this
+    /// realm fail; the context itself is freed once timers or promises it
+    /// scheduled no longer reference it. Closing twice is a no-op.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await realm.close();
+    /// ```
+    pub async fn close(&self) -> Result<(), JsError> {
+        let Some(context) = self
+            .context
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+        else {
+            return Ok(());
+        };
+        if self.engine.strong_count() > 0 {
+            let _ = context
+                .with_js(async |ctx| {
+                    let globals = ctx.globals();
+                    let _ = globals.remove("fjs");
+                    let _ = globals.remove("__fjs_shared__");
+                })
+                .await;
+        }
+        drop(context);
+        Ok(())
+    }
+}
+
+/// Copies values between the engine context and a realm.
+///
+/// Evaluated once in each context; `link` connects it to the other side's
+/// `copy`. `copy` turns a value of the other context into a local one: data
+/// is deep-copied, promises settle a local promise with copied results, and
+/// functions become local wrappers that copy their arguments out and their
+/// results and errors back. No object of the other context is ever handed to
+/// local code, so its `constructor` chain cannot reach the other global.
+///
+/// Intrinsics are captured up front so realm code that later patches
+/// `Reflect`, `Map.prototype`, or the like never sees foreign values.
+const MEMBRANE: &str = r#"
+(() => {
+  const { apply, construct, defineProperty } = Reflect;
+  const { isArray } = Array;
+  const { keys, freeze } = Object;
+  const toTag = Object.prototype.toString;
+  const SafeMap = Map;
+  const SafeSet = Set;
+  const { get: mapGet, set: mapSet, has: mapHas, forEach: mapForEach } = Map.prototype;
+  const { add: setAdd, forEach: setForEach } = Set.prototype;
+  const SafePromise = Promise;
+  const SafeError = Error;
+  const SafeDate = Date;
+  const getTime = Date.prototype.getTime;
+  const SafeUint8Array = Uint8Array;
+  const typedSet = Object.getPrototypeOf(Uint8Array.prototype).set;
+  const typed = { __proto__: null };
+  for (const name of [
+    "Int8Array", "Uint8Array", "Uint8ClampedArray", "Int16Array", "Uint16Array",
+    "Int32Array", "Uint32Array", "Float32Array", "Float64Array",
+    "BigInt64Array", "BigUint64Array",
+  ]) {
+    if (typeof globalThis[name] === "function") typed[`[object ${name}]`] = globalThis[name];
+  }
+  let peer;
+  // Converts local values for the other side, without touching local arrays.
+  const out = (values) => {
+    const result = { __proto__: null, length: values.length };
+    for (let i = 0; i < values.length; i++) result[i] = peer(values[i]);
+    return result;
+  };
+  const define = (target, key, value) =>
+    defineProperty(target, key, {
+      __proto__: null, value, writable: true, enumerable: true, configurable: true,
+    });
+  const copy = (value, seen) => {
+    if (value === null || (typeof value !== "object" && typeof value !== "function")) {
+      return value;
+    }
+    seen ??= new SafeMap();
+    if (apply(mapHas, seen, [value])) return apply(mapGet, seen, [value]);
+    if (typeof value === "function") {
+      const fn = value;
+      const wrapper = function (...args) {
+        let result;
+        try {
+          result = new.target === undefined
+            ? apply(fn, this === undefined ? undefined : peer(this), out(args))
+            : construct(fn, out(args));
+        } catch (error) {
+          throw copy(error);
+        }
+        return copy(result);
+      };
+      apply(mapSet, seen, [value, wrapper]);
+      return wrapper;
+    }
+    const tag = apply(toTag, value, []);
+    if (tag === "[object Promise]") {
+      const then = value.then;
+      const promise = new SafePromise((resolve, reject) => {
+        apply(then, value, [peer((v) => resolve(copy(v))), peer((e) => reject(copy(e)))]);
+      });
+      apply(mapSet, seen, [value, promise]);
+      return promise;
+    }
+    if (tag === "[object Error]") {
+      const error = new SafeError(String(value.message));
+      define(error, "name", String(value.name));
+      if (typeof value.stack === "string") define(error, "stack", value.stack);
+      apply(mapSet, seen, [value, error]);
+      return error;
+    }
+    if (tag === "[object Date]") return new SafeDate(apply(getTime, value, []));
+    if (tag === "[object ArrayBuffer]") {
+      const bytes = new SafeUint8Array(value.byteLength);
+      apply(typedSet, bytes, [new SafeUint8Array(value)]);
+      return bytes.buffer;
+    }
+    if (typed[tag] !== undefined) return new typed[tag](value);
+    if (tag === "[object Map]") {
+      const map = new SafeMap();
+      apply(mapSet, seen, [value, map]);
+      apply(mapForEach, value, [(v, k) => apply(mapSet, map, [copy(k, seen), copy(v, seen)])]);
+      return map;
+    }
+    if (tag === "[object Set]") {
+      const set = new SafeSet();
+      apply(mapSet, seen, [value, set]);
+      apply(setForEach, value, [(v) => apply(setAdd, set, [copy(v, seen)])]);
+      return set;
+    }
+    const result = isArray(value) ? [] : {};
+    apply(mapSet, seen, [value, result]);
+    const names = keys(value);
+    for (let i = 0; i < names.length; i++) define(result, names[i], copy(value[names[i]], seen));
+    return result;
+  };
+  return freeze({
+    copy: (value) => copy(value),
+    link(other) {
+      peer = other;
+    },
+  });
+})()
+"#;
+
+/// Builds a module that re-exports a namespace stored in `__fjs_shared__`.
+fn shared_module_source(name: &str, exports: &[String]) -> String {
+    let mut source = format!(
+        "const ns = globalThis.__fjs_shared__[{}];\n",
+        js_string_literal(name)
+    );
+    for (index, export) in exports.iter().enumerate() {
+        let export = js_string_literal(export);
+        source.push_str(&format!(
+            "const __fjs_export_{index} = ns[{export}];\nexport {{ __fjs_export_{index} as {export} }};\n"
+        ));
+    }
+    source
+}
@@ -0,0 +1,1121 @@
+//! # Recording
+//!
+//! Records of bridge traffic and engine inputs, for reproducing bugs.
+//!
+//! `JsEngine.startRecording()` captures the inputs of every `eval()`,
+//! `evaluateModule()`, `call()`, `callBatch()`, and `invoke()`, and every
+//! `fjs.bridge_call()` argument with the response Dart gave, in the order
+//! they started. `JsRecording.toJson()` produces a portable file, and
+//! `JsEngine.replay()` runs the recorded inputs again on a fresh engine while
+//! answering bridge calls from the recording, without Dart.
+
+use crate::api::engine::{JsBatchCall, JsInvocation, JsThisBinding};
+use crate::api::error::JsError;
+use crate::api::module::js_string_literal;
+use crate::api::source::{JsCode, JsEvalOptions, JsModule, JsModuleEvalOptions, JsModuleKind};
+use crate::api::value::JsValue;
+use flutter_rust_bridge::frb;
+use std::collections::HashMap;
+
+/// Version written to, and required from, recording files.
+const FORMAT_VERSION: i64 = 1;
+
+/// Deepest array or object nesting accepted when parsing a recording.
+///
+/// Values nest at most 128 levels and each level takes two in the file, so
+/// this leaves room for the envelope while keeping the parser's recursion
+/// well within the stack.
+const MAX_JSON_DEPTH: usize = 512;
+
+/// How a recorded operation finished.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsRecordedOutcome {
+    /// The operation returned this value.
+    Ok(JsValue),
+    /// The operation failed with this error.
+    Err(JsError),
+    /// A `callBatch()` finished with one outcome per call, in order.
+    Batch(Vec<JsRecordedOutcome>),
+}
+
+/// One recorded operation.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsRecordedEvent {
+    /// An `eval()` of script source.
+    Eval {
+        /// Milliseconds from the start of the recording to the call.
+        at_ms: u64,
+        /// Evaluated source.
+        source: String,
+        /// Options passed to `eval()`, including bindings.
+        options: Option<JsEvalOptions>,
+        /// Result of the evaluation.
+        outcome: JsRecordedOutcome,
+    },
+    /// An `evaluateModule()` of module source.
+    EvaluateModule {
+        /// Milliseconds from the start of the recording to the call.
+        at_ms: u64,
+        /// The evaluated module, with its source read into memory.
+        module: JsModule,
+        /// Options passed to `evaluateModule()`.
+        options: Option<JsModuleEvalOptions>,
+        /// Result of the evaluation.
+        outcome: JsRecordedOutcome,
+    },
+    /// A `call()` of a module export.
+    Call {
+        /// Milliseconds from the start of the recording to the call.
+        at_ms: u64,
+        /// Module name.
+        module: String,
+        /// Export name.
+        method: String,
+        /// Arguments passed to the export.
+        params: Vec<JsValue>,
+        /// Result of the call.
+        outcome: JsRecordedOutcome,
+    },
+    /// A `callBatch()` of module exports.
+    CallBatch {
+        /// Milliseconds from the start of the recording to the call.
+        at_ms: u64,
+        /// The batched calls.
+        calls: Vec<JsBatchCall>,
+        /// Whether the calls ran concurrently.
+        concurrent: bool,
+        /// One outcome per call, or the error that failed the whole batch.
+        outcome: JsRecordedOutcome,
+    },
+    /// An `invoke()` of a function by path.
+    Invoke {
+        /// Milliseconds from the start of the recording to the call.
+        at_ms: u64,
+        /// The invocation.
+        invocation: JsInvocation,
+        /// Result of the call.
+        outcome: JsRecordedOutcome,
+    },
+    /// An `fjs.bridge_call()` from JavaScript to Dart.
+    BridgeCall {
+        /// Milliseconds from the start of the recording to the call.
+        at_ms: u64,
+        /// Time Dart took to respond, in microseconds.
+        duration_us: u64,
+        /// Value passed by JavaScript.
+        argument: JsValue,
+        /// Response from Dart.
+        outcome: JsRecordedOutcome,
+    },
+}
+
+/// A recording of engine inputs and bridge traffic.
+///
+/// ## Example
+///
+/// ```dart
+/// await engine.startRecording();
+/// // ... reproduce the issue ...
+/// final recording = await engine.stopRecording();
+/// await File('${dir.path}/session.fjsrec.json').writeAsString(recording.toJson());
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JsRecording {
+    /// Recorded operations, in the order they started.
+    pub events: Vec<JsRecordedEvent>,
+}
+
+impl JsRecording {
+    /// Serializes the recording as JSON.
+    ///
+    /// Values keep their exact `JsValue` variant, so integers, floats,
+    /// BigInts, bytes, and dates survive a round trip through `fromJson()`,
+    /// and errors keep their `JsError` variant and fields.
+    #[frb(sync)]
+    pub fn to_json(&self) -> String {
+        let events: Vec<String> = self.events.iter().map(event_to_json).collect();
+        format!(
+            "{{\"version\":{FORMAT_VERSION},\"events\":[{}]}}",
+            events.join(",")
+        )
+    }
+
+    /// Parses a recording produced by `toJson()`.
+    ///
+    /// ## Throws
+    /// - If the text is not a recording in a supported format version
+    #[frb(sync)]
+    pub fn from_json(json: String) -> Result<JsRecording, JsError> {
+        let invalid = |message: &str| JsError::generic(format!("Invalid recording: {message}"));
+        let root = Parser::new(&json)
+            .parse_document()
+            .map_err(|e| invalid(&e))?;
+        if root.field("version").and_then(Json::as_i64) != Some(FORMAT_VERSION) {
+            return Err(invalid("unsupported format version"));
+        }
+        let events = root
+            .field("events")
+            .and_then(Json::as_array)
+            .ok_or_else(|| invalid("missing events"))?
+            .iter()
+            .map(event_from_json)
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("malformed event"))?;
+        Ok(Self { events })
+    }
+}
+
+/// Result of replaying one recorded engine input.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsReplayStep {
+    /// Index of the replayed event in `JsRecording.events`.
+    pub event_index: u64,
+    /// Outcome captured in the recording.
+    pub recorded: JsRecordedOutcome,
+    /// Outcome of the replay.
+    pub replayed: JsRecordedOutcome,
+}
+
+impl JsReplayStep {
+    /// Whether the replay finished differently from the recording.
+    #[frb(sync, getter)]
+    pub fn diverged(&self) -> bool {
+        self.recorded != self.replayed
+    }
+}
+
+/// Writes a JSON object, leaving out fields whose value is `None`.
+fn object_to_json(fields: &[(&str, Option<String>)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .filter_map(|(name, value)| Some(format!("\"{name}\":{}", value.as_ref()?)))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn values_to_json(values: &[JsValue]) -> String {
+    let values: Vec<String> = values.iter().map(value_to_json).collect();
+    format!("[{}]", values.join(","))
+}
+
+fn entries_to_json(entries: &HashMap<String, JsValue>) -> String {
+    let mut entries: Vec<_> = entries.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let entries: Vec<String> = entries
+        .iter()
+        .map(|(key, value)| format!("{}:{}", js_string_literal(key), value_to_json(value)))
+        .collect();
+    format!("{{{}}}", entries.join(","))
+}
+
+fn hex_to_json(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("\"{hex}\"")
+}
+
+/// Encodes an error as its `code()` plus the fields of its variant.
+fn error_to_json(error: &JsError) -> String {
+    let text = |value: &str| Some(js_string_literal(value));
+    let optional = |value: &Option<String>| value.as_deref().map(js_string_literal);
+    let code = Some(js_string_literal(&error.code()));
+    match error {
+        JsError::Promise(message)
+        | JsError::Context(message)
+        | JsError::Storage(message)
+        | JsError::Runtime(message)
+        | JsError::Generic(message)
+        | JsError::Engine(message)
+        | JsError::Bridge(message)
+        | JsError::MemoryLimit(message)
+        | JsError::StackOverflow(message)
+        | JsError::Reference(message)
+        | JsError::Type(message)
+        | JsError::Cancelled(message) => {
+            object_to_json(&[("code", code), ("message", text(message))])
+        }
+        JsError::Module {
+            module,
+            method,
+            message,
+        } => object_to_json(&[
+            ("code", code),
+            ("module", optional(module)),
+            ("method", optional(method)),
+            ("message", text(message)),
+        ]),
+        JsError::Io { path, message } => object_to_json(&[
+            ("code", code),
+            ("path", optional(path)),
+            ("message", text(message)),
+        ]),
+        JsError::Conversion { from, to, message } => object_to_json(&[
+            ("code", code),
+            ("from", text(from)),
+            ("to", text(to)),
+            ("message", text(message)),
+        ]),
+        JsError::Timeout {
+            operation,
+            timeout_ms,
+        } => object_to_json(&[
+            ("code", code),
+            ("operation", text(operation)),
+            ("timeoutMs", Some(timeout_ms.to_string())),
+        ]),
+        JsError::Syntax {
+            line,
+            column,
+            message,
+        } => object_to_json(&[
+            ("code", code),
+            ("line", line.map(|line| line.to_string())),
+            ("column", column.map(|column| column.to_string())),
+            ("message", text(message)),
+        ]),
+    }
+}
+
+fn outcome_to_json(outcome: &JsRecordedOutcome) -> String {
+    match outcome {
+        JsRecordedOutcome::Ok(value) => format!("{{\"ok\":{}}}", value_to_json(value)),
+        JsRecordedOutcome::Err(error) => format!("{{\"err\":{}}}", error_to_json(error)),
+        JsRecordedOutcome::Batch(outcomes) => format!(
+            "{{\"batch\":[{}]}}",
+            outcomes
+                .iter()
+                .map(outcome_to_json)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+    }
+}
+
+fn eval_options_to_json(options: &JsEvalOptions) -> String {
+    object_to_json(&[
+        ("global", options.global.map(|value| value.to_string())),
+        ("strict", options.strict.map(|value| value.to_string())),
+        (
+            "backtraceBarrier",
+            options.backtrace_barrier.map(|value| value.to_string()),
+        ),
+        ("promise", options.promise.map(|value| value.to_string())),
+        ("bindings", options.bindings.as_ref().map(entries_to_json)),
+    ])
+}
+
+fn code_to_json(code: &JsCode) -> String {
+    match code {
+        JsCode::Code(code) => format!("[\"code\",{}]", js_string_literal(code)),
+        JsCode::Path(path) => format!("[\"path\",{}]", js_string_literal(path)),
+        JsCode::Bytes(bytes) => format!("[\"bytes\",{}]", hex_to_json(bytes)),
+    }
+}
+
+fn module_kind_name(kind: JsModuleKind) -> &'static str {
+    match kind {
+        JsModuleKind::JavaScript => "javaScript",
+        JsModuleKind::Json => "json",
+        JsModuleKind::Text => "text",
+        JsModuleKind::Bytes => "bytes",
+    }
+}
+
+fn batch_call_to_json(call: &JsBatchCall) -> String {
+    object_to_json(&[
+        ("module", Some(js_string_literal(&call.module))),
+        ("method", Some(js_string_literal(&call.method))),
+        ("params", call.params.as_deref().map(values_to_json)),
+    ])
+}
+
+fn invocation_to_json(invocation: &JsInvocation) -> String {
+    object_to_json(&[
+        (
+            "module",
+            invocation.module.as_deref().map(js_string_literal),
+        ),
+        ("path", Some(js_string_literal(&invocation.path))),
+        ("params", invocation.params.as_deref().map(values_to_json)),
+        (
+            "construct",
+            invocation.construct.map(|value| value.to_string()),
+        ),
+        (
+            "thisBinding",
+            invocation
+                .this_binding
+                .as_ref()
+                .map(|binding| match binding {
+                    JsThisBinding::Owner => "[\"owner\"]".to_string(),
+                    JsThisBinding::Value(value) => {
+                        format!("[\"value\",{}]", value_to_json(value))
+                    }
+                    JsThisBinding::Path(path) => format!("[\"path\",{}]", js_string_literal(path)),
+                }),
+        ),
+    ])
+}
+
+fn event_to_json(event: &JsRecordedEvent) -> String {
+    match event {
+        JsRecordedEvent::Eval {
+            at_ms,
+            source,
+            options,
+            outcome,
+        } => object_to_json(&[
+            ("kind", Some("\"eval\"".to_string())),
+            ("atMs", Some(at_ms.to_string())),
+            ("source", Some(js_string_literal(source))),
+            ("options", options.as_ref().map(eval_options_to_json)),
+            ("outcome", Some(outcome_to_json(outcome))),
+        ]),
+        JsRecordedEvent::EvaluateModule {
+            at_ms,
+            module,
+            options,
+            outcome,
+        } => object_to_json(&[
+            ("kind", Some("\"evaluateModule\"".to_string())),
+            ("atMs", Some(at_ms.to_string())),
+            ("module", Some(js_string_literal(&module.name))),
+            (
+                "moduleKind",
+                Some(format!("\"{}\"", module_kind_name(module.kind))),
+            ),
+            ("source", Some(code_to_json(&module.source))),
+            (
+                "options",
+                options.as_ref().map(|options| {
+                    object_to_json(&[(
+                        "returnExports",
+                        options.return_exports.map(|value| value.to_string()),
+                    )])
+                }),
+            ),
+            ("outcome", Some(outcome_to_json(outcome))),
+        ]),
+        JsRecordedEvent::Call {
+            at_ms,
+            module,
+            method,
+            params,
+            outcome,
+        } => format!(
+            "{{\"kind\":\"call\",\"atMs\":{at_ms},\"module\":{},\"method\":{},\"params\":{},\"outcome\":{}}}",
+            js_string_literal(module),
+            js_string_literal(method),
+            values_to_json(params),
+            outcome_to_json(outcome)
+        ),
+        JsRecordedEvent::CallBatch {
+            at_ms,
+            calls,
+            concurrent,
+            outcome,
+        } => object_to_json(&[
+            ("kind", Some("\"callBatch\"".to_string())),
+            ("atMs", Some(at_ms.to_string())),
+            (
+                "calls",
+                Some(format!(
+                    "[{}]",
+                    calls
+                        .iter()
+                        .map(batch_call_to_json)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )),
+            ),
+            ("concurrent", Some(concurrent.to_string())),
+            ("outcome", Some(outcome_to_json(outcome))),
+        ]),
+        JsRecordedEvent::Invoke {
+            at_ms,
+            invocation,
+            outcome,
+        } => object_to_json(&[
+            ("kind", Some("\"invoke\"".to_string())),
+            ("atMs", Some(at_ms.to_string())),
+            ("invocation", Some(invocation_to_json(invocation))),
+            ("outcome", Some(outcome_to_json(outcome))),
+        ]),
+        JsRecordedEvent::BridgeCall {
+            at_ms,
+            duration_us,
+            argument,
+            outcome,
+        } => format!(
+            "{{\"kind\":\"bridgeCall\",\"atMs\":{at_ms},\"durationUs\":{duration_us},\"argument\":{},\"outcome\":{}}}",
+            value_to_json(argument),
+            outcome_to_json(outcome)
+        ),
+    }
+}
+
+/// Encodes a value as a `[tag, payload]` pair so every variant round-trips.
+fn value_to_json(value: &JsValue) -> String {
+    match value {
+        JsValue::None => "[\"none\"]".to_string(),
+        JsValue::Boolean(value) => format!("[\"boolean\",{value}]"),
+        JsValue::Integer(value) => format!("[\"integer\",\"{value}\"]"),
+        JsValue::Float(value) => format!("[\"float\",\"{value:?}\"]"),
+        JsValue::Bigint(value) => format!("[\"bigint\",{}]", js_string_literal(value)),
+        JsValue::String(value) => format!("[\"string\",{}]", js_string_literal(value)),
+        JsValue::Bytes(bytes) => format!("[\"bytes\",{}]", hex_to_json(bytes)),
+        JsValue::Array(items) => format!("[\"array\",{}]", values_to_json(items)),
+        JsValue::Object(entries) => format!("[\"object\",{}]", entries_to_json(entries)),
+        JsValue::Date(ms) => format!("[\"date\",\"{ms}\"]"),
+        JsValue::Symbol(value) => format!("[\"symbol\",{}]", js_string_literal(value)),
+        JsValue::Function(value) => format!("[\"function\",{}]", js_string_literal(value)),
+    }
+}
+
+fn value_from_json(json: &Json) -> Option<JsValue> {
+    let pair = json.as_array()?;
+    let tag = pair.first()?.as_str()?;
+    let payload = pair.get(1);
+    let text = || payload.and_then(Json::as_str);
+    Some(match tag {
+        "none" => JsValue::None,
+        "boolean" => JsValue::Boolean(payload?.as_bool()?),
+        "integer" => JsValue::Integer(text()?.parse().ok()?),
+        "float" => JsValue::Float(text()?.parse().ok()?),
+        "bigint" => JsValue::Bigint(text()?.to_string()),
+        "string" => JsValue::String(text()?.to_string()),
+        "bytes" => JsValue::Bytes(hex_from_json(payload?)?),
+        "array" => JsValue::Array(values_from_json(payload?)?),
+        "object" => JsValue::Object(entries_from_json(payload?)?),
+        "date" => JsValue::Date(text()?.parse().ok()?),
+        "symbol" => JsValue::Symbol(text()?.to_string()),
+        "function" => JsValue::Function(text()?.to_string()),
+        _ => return None,
+    })
+}
+
+fn values_from_json(json: &Json) -> Option<Vec<JsValue>> {
+    json.as_array()?.iter().map(value_from_json).collect()
+}
+
+fn entries_from_json(json: &Json) -> Option<HashMap<String, JsValue>> {
+    match json {
+        Json::Object(entries) => entries
+            .iter()
+            .map(|(key, value)| Some((key.clone(), value_from_json(value)?)))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn hex_from_json(json: &Json) -> Option<Vec<u8>> {
+    let hex = json.as_str()?;
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Reads an optional field: missing and `null` give `Some(None)`, a present
+/// value that `parse` rejects gives `None`.
+fn optional_field<T>(
+    json: &Json,
+    name: &str,
+    parse: impl FnOnce(&Json) -> Option<T>,
+) -> Option<Option<T>> {
+    match json.field(name) {
+        None | Some(Json::Null) => Some(None),
+        Some(value) => parse(value).map(Some),
+    }
+}
+
+fn string_field(json: &Json, name: &str) -> Option<String> {
+    Some(json.field(name)?.as_str()?.to_string())
+}
+
+fn optional_string(json: &Json, name: &str) -> Option<Option<String>> {
+    optional_field(json, name, |value| Some(value.as_str()?.to_string()))
+}
+
+fn error_from_json(json: &Json) -> Option<JsError> {
+    let message = || string_field(json, "message");
+    Some(match json.field("code")?.as_str()? {
+        "PROMISE_ERROR" => JsError::Promise(message()?),
+        "MODULE_ERROR" => JsError::Module {
+            module: optional_string(json, "module")?,
+            method: optional_string(json, "method")?,
+            message: message()?,
+        },
+        "CONTEXT_ERROR" => JsError::Context(message()?),
+        "STORAGE_ERROR" => JsError::Storage(message()?),
+        "IO_ERROR" => JsError::Io {
+            path: optional_string(json, "path")?,
+            message: message()?,
+        },
+        "RUNTIME_ERROR" => JsError::Runtime(message()?),
+        "GENERIC_ERROR" => JsError::Generic(message()?),
+        "ENGINE_ERROR" => JsError::Engine(message()?),
+        "BRIDGE_ERROR" => JsError::Bridge(message()?),
+        "CONVERSION_ERROR" => JsError::Conversion {
+            from: string_field(json, "from")?,
+            to: string_field(json, "to")?,
+            message: message()?,
+        },
+        "TIMEOUT_ERROR" => JsError::Timeout {
+            operation: string_field(json, "operation")?,
+            timeout_ms: json.field("timeoutMs")?.as_u64()?,
+        },
+        "MEMORY_LIMIT_ERROR" => JsError::MemoryLimit(message()?),
+        "STACK_OVERFLOW_ERROR" => JsError::StackOverflow(message()?),
+        "SYNTAX_ERROR" => JsError::Syntax {
+            line: optional_field(json, "line", Json::as_u32)?,
+            column: optional_field(json, "column", Json::as_u32)?,
+            message: message()?,
+        },
+        "REFERENCE_ERROR" => JsError::Reference(message()?),
+        "TYPE_ERROR" => JsError::Type(message()?),
+        "CANCELLED_ERROR" => JsError::Cancelled(message()?),
+        _ => return None,
+    })
+}
+
+fn outcome_from_json(json: &Json) -> Option<JsRecordedOutcome> {
+    if let Some(value) = json.field("ok") {
+        Some(JsRecordedOutcome::Ok(value_from_json(value)?))
+    } else if let Some(outcomes) = json.field("batch") {
+        Some(JsRecordedOutcome::Batch(
+            outcomes
+                .as_array()?
+                .iter()
+                .map(outcome_from_json)
+                .collect::<Option<_>>()?,
+        ))
+    } else {
+        Some(JsRecordedOutcome::Err(error_from_json(json.field("err")?)?))
+    }
+}
+
+fn eval_options_from_json(json: &Json) -> Option<JsEvalOptions> {
+    Some(JsEvalOptions {
+        global: optional_field(json, "global", Json::as_bool)?,
+        strict: optional_field(json, "strict", Json::as_bool)?,
+        backtrace_barrier: optional_field(json, "backtraceBarrier", Json::as_bool)?,
+        promise: optional_field(json, "promise", Json::as_bool)?,
+        bindings: optional_field(json, "bindings", entries_from_json)?,
+    })
+}
+
+fn code_from_json(json: &Json) -> Option<JsCode> {
+    let pair = json.as_array()?;
+    let payload = pair.get(1)?;
+    Some(match pair.first()?.as_str()? {
+        "code" => JsCode::Code(payload.as_str()?.to_string()),
+        "path" => JsCode::Path(payload.as_str()?.to_string()),
+        "bytes" => JsCode::Bytes(hex_from_json(payload)?),
+        _ => return None,
+    })
+}
+
+fn module_kind_from_name(name: &str) -> Option<JsModuleKind> {
+    [
+        JsModuleKind::JavaScript,
+        JsModuleKind::Json,
+        JsModuleKind::Text,
+        JsModuleKind::Bytes,
+    ]
+    .into_iter()
+    .find(|kind| module_kind_name(*kind) == name)
+}
+
+fn batch_call_from_json(json: &Json) -> Option<JsBatchCall> {
+    Some(JsBatchCall {
+        module: string_field(json, "module")?,
+        method: string_field(json, "method")?,
+        params: optional_field(json, "params", values_from_json)?,
+    })
+}
+
+fn this_binding_from_json(json: &Json) -> Option<JsThisBinding> {
+    let pair = json.as_array()?;
+    Some(match pair.first()?.as_str()? {
+        "owner" => JsThisBinding::Owner,
+        "value" => JsThisBinding::Value(value_from_json(pair.get(1)?)?),
+        "path" => JsThisBinding::Path(pair.get(1)?.as_str()?.to_string()),
+        _ => return None,
+    })
+}
+
+fn invocation_from_json(json: &Json) -> Option<JsInvocation> {
+    Some(JsInvocation {
+        module: optional_string(json, "module")?,
+        path: string_field(json, "path")?,
+        params: optional_field(json, "params", values_from_json)?,
+        construct: optional_field(json, "construct", Json::as_bool)?,
+        this_binding: optional_field(json, "thisBinding", this_binding_from_json)?,
+    })
+}
+
+fn event_from_json(json: &Json) -> Option<JsRecordedEvent> {
+    let at_ms = json.field("atMs")?.as_u64()?;
+    let string = |name: &str| Some(json.field(name)?.as_str()?.to_string());
+    let outcome = outcome_from_json(json.field("outcome")?)?;
+    Some(match json.field("kind")?.as_str()? {
+        "eval" => JsRecordedEvent::Eval {
+            at_ms,
+            source: string("source")?,
+            options: optional_field(json, "options", eval_options_from_json)?,
+            outcome,
+        },
+        "evaluateModule" => JsRecordedEvent::EvaluateModule {
+            at_ms,
+            module: JsModule {
+                name: string("module")?,
+                source: code_from_json(json.field("source")?)?,
+                kind: module_kind_from_name(json.field("moduleKind")?.as_str()?)?,
+            },
+            options: optional_field(json, "options", |options| {
+                Some(JsModuleEvalOptions {
+                    return_exports: optional_field(options, "returnExports", Json::as_bool)?,
+                })
+            })?,
+            outcome,
+        },
+        "call" => JsRecordedEvent::Call {
+            at_ms,
+            module: string("module")?,
+            method: string("method")?,
+            params: values_from_json(json.field("params")?)?,
+            outcome,
+        },
+        "callBatch" => JsRecordedEvent::CallBatch {
+            at_ms,
+            calls: json
+                .field("calls")?
+                .as_array()?
+                .iter()
+                .map(batch_call_from_json)
+                .collect::<Option<_>>()?,
+            concurrent: json.field("concurrent")?.as_bool()?,
+            outcome,
+        },
+        "invoke" => JsRecordedEvent::Invoke {
+            at_ms,
+            invocation: invocation_from_json(json.field("invocation")?)?,
+            outcome,
+        },
+        "bridgeCall" => JsRecordedEvent::BridgeCall {
+            at_ms,
+            duration_us: json.field("durationUs")?.as_u64()?,
+            argument: value_from_json(json.field("argument")?)?,
+            outcome,
+        },
+        _ => return None,
+    })
+}
+
+/// Parsed JSON. Numbers keep their source text so integers stay exact.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn field(&self, name: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_u32(&self) -> Option<u32> {
+        match self {
+            Json::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Json::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A strict JSON parser, sufficient for recording files.
+///
+/// Nesting is limited to [`MAX_JSON_DEPTH`], so a hostile file cannot
+/// exhaust the stack.
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            position: 0,
+            depth: 0,
+        }
+    }
+
+    fn parse_document(mut self) -> Result<Json, String> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.position == self.text.len() {
+            Ok(value)
+        } else {
+            Err(self.error("trailing characters"))
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{message} at byte {}", self.position)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Json) -> Result<Json, String> {
+        if self.text[self.position..].starts_with(literal) {
+            self.position += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("unexpected token"))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.parse_literal("null", Json::Null),
+            Some(b't') => self.parse_literal("true", Json::Bool(true)),
+            Some(b'f') => self.parse_literal("false", Json::Bool(false)),
+            Some(b'"') => self.parse_string().map(Json::String),
+            Some(open @ (b'[' | b'{')) => {
+                if self.depth == MAX_JSON_DEPTH {
+                    return Err(self.error("nesting too deep"));
+                }
+                self.position += 1;
+                self.depth += 1;
+                let value = if open == b'[' {
+                    self.parse_array()
+                } else {
+                    self.parse_object()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.position;
+                while matches!(
+                    self.peek(),
+                    Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')
+                ) {
+                    self.position += 1;
+                }
+                Ok(Json::Number(self.text[start..self.position].to_string()))
+            }
+            _ => Err(self.error("unexpected token")),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json, String> {
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, String> {
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            entries.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(entries));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        if self.peek() != Some(b'"') {
+            return Err(self.error("expected string"));
+        }
+        self.position += 1;
+        let mut value = String::new();
+        loop {
+            let rest = &self.text[self.position..];
+            let Some(offset) = rest.find(['"', '\\']) else {
+                return Err(self.error("unterminated string"));
+            };
+            value.push_str(&rest[..offset]);
+            self.position += offset;
+            if self.peek() == Some(b'"') {
+                self.position += 1;
+                return Ok(value);
+            }
+            self.position += 1;
+            let escape = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.position += 1;
+            match escape {
+                b'"' => value.push('"'),
+                b'\\' => value.push('\\'),
+                b'/' => value.push('/'),
+                b'b' => value.push('\u{8}'),
+                b'f' => value.push('\u{c}'),
+                b'n' => value.push('\n'),
+                b'r' => value.push('\r'),
+                b't' => value.push('\t'),
+                b'u' => {
+                    let unit = self.parse_hex4()?;
+                    let code = if (0xd800..0xdc00).contains(&unit) {
+                        if !self.text[self.position..].starts_with("\\u") {
+                            return Err(self.error("unpaired surrogate"));
+                        }
+                        self.position += 2;
+                        let low = self.parse_hex4()?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return Err(self.error("unpaired surrogate"));
+                        }
+                        0x10000 + ((unit - 0xd800) << 10) + (low - 0xdc00)
+                    } else {
+                        unit
+                    };
+                    value.push(char::from_u32(code).ok_or_else(|| self.error("invalid escape"))?);
+                }
+                _ => return Err(self.error("invalid escape")),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, String> {
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .ok_or_else(|| self.error("invalid escape"))?;
+        let unit = u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid escape"))?;
+        self.position += 4;
+        Ok(unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsRecordedEvent, JsRecordedOutcome, JsRecording};
+    use crate::api::engine::{JsBatchCall, JsInvocation, JsThisBinding};
+    use crate::api::error::JsError;
+    use crate::api::source::{JsCode, JsEvalOptions, JsModule, JsModuleEvalOptions, JsModuleKind};
+    use crate::api::value::JsValue;
+    use std::collections::HashMap;
+
+    #[test]
+    fn json_round_trip_keeps_value_variants() {
+        let recording = JsRecording {
+            events: vec![
+                JsRecordedEvent::BridgeCall {
+                    at_ms: 5,
+                    duration_us: 120,
+                    argument: JsValue::Object(HashMap::from([
+                        (
+                            "op".to_string(),
+                            JsValue::String("read \"α\"\n\u{1}".to_string()),
+                        ),
+                        ("bytes".to_string(), JsValue::Bytes(vec![0, 15, 255])),
+                        ("when".to_string(), JsValue::Date(-1)),
+                    ])),
+                    outcome: JsRecordedOutcome::Err("denied".to_string()),
+                },
+                JsRecordedEvent::Call {
+                    at_ms: 1,
+                    module: "m".to_string(),
+                    method: "f".to_string(),
+                    params: vec![
+                        JsValue::Integer(9_007_199_254_740_991),
+                        JsValue::Float(1.0),
+                        JsValue::Float(f64::INFINITY),
+                        JsValue::Bigint("-12345678901234567890".to_string()),
+                        JsValue::None,
+                        JsValue::Boolean(true),
+                    ],
+                    outcome: JsRecordedOutcome::Ok(JsValue::Array(vec![])),
+                },
+            ],
+        };
+
+        let parsed = JsRecording::from_json(recording.to_json()).unwrap();
+        assert_eq!(parsed, recording);
+        assert!(JsRecording::from_json("{\"version\":2,\"events\":[]}".to_string()).is_err());
+    }
+
+    #[test]
+    fn json_round_trip_keeps_error_variants_and_inputs() {
+        let recording = JsRecording {
+            events: vec![
+                JsRecordedEvent::Eval {
+                    at_ms: 0,
+                    source: "input.n".to_string(),
+                    options: Some(JsEvalOptions {
+                        strict: Some(false),
+                        bindings: Some(HashMap::from([(
+                            "input".to_string(),
+                            JsValue::Object(HashMap::from([(
+                                "n".to_string(),
+                                JsValue::Integer(1),
+                            )])),
+                        )])),
+                        ..Default::default()
+                    }),
+                    outcome: JsRecordedOutcome::Err(JsError::Syntax {
+                        line: Some(3),
+                        column: None,
+                        message: "unexpected token".to_string(),
+                    }),
+                },
+                JsRecordedEvent::EvaluateModule {
+                    at_ms: 1,
+                    module: JsModule {
+                        name: "blob".to_string(),
+                        source: JsCode::Bytes(vec![0xff, 0]),
+                        kind: JsModuleKind::Bytes,
+                    },
+                    options: Some(JsModuleEvalOptions {
+                        return_exports: Some(true),
+                    }),
+                    outcome: JsRecordedOutcome::Ok(JsValue::None),
+                },
+                JsRecordedEvent::CallBatch {
+                    at_ms: 2,
+                    calls: vec![JsBatchCall {
+                        module: "m".to_string(),
+                        method: "f".to_string(),
+                        params: None,
+                    }],
+                    concurrent: true,
+                    outcome: JsRecordedOutcome::Batch(vec![
+                        JsRecordedOutcome::Ok(JsValue::Boolean(false)),
+                        JsRecordedOutcome::Err(JsError::module(
+                            Some("m".to_string()),
+                            None,
+                            "missing",
+                        )),
+                    ]),
+                },
+                JsRecordedEvent::Invoke {
+                    at_ms: 3,
+                    invocation: JsInvocation {
+                        module: None,
+                        path: "Math.max".to_string(),
+                        params: Some(vec![JsValue::Integer(2)]),
+                        construct: Some(false),
+                        this_binding: Some(JsThisBinding::Value(JsValue::None)),
+                    },
+                    outcome: JsRecordedOutcome::Err(JsError::timeout("invoke", 50)),
+                },
+            ],
+        };
+
+        let parsed = JsRecording::from_json(recording.to_json()).unwrap();
+        assert_eq!(parsed, recording);
+    }
+
+    #[test]
+    fn from_json_rejects_deep_nesting() {
+        let depth = 100_000;
+        let json = format!(
+            "{{\"version\":1,\"events\":{}{}}}",
+            "[".repeat(depth),
+            "]".repeat(depth)
+        );
+        assert!(JsRecording::from_json(json).is_err());
+    }
+}
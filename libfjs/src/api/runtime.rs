@@ -6,19 +6,22 @@
 use crate::api::error::{JsError, JsResult};
 use crate::api::module::{
     DynamicModuleEntry, DynamicModuleLoader, DynamicModuleResolver, DynamicModuleStorage,
-    GlobalAttachment, LoadedDynamicModules, ModuleBuilder, get_available_module_names,
+    GlobalAttachment, LoadedDynamicModules, ModuleBuilder, NamedModuleLoader, NamedModuleResolver,
+    TrackingLoader, TrackingResolver, TypedFileLoader, TypedFileResolver,
+    get_available_module_names, import_from_host, module_source_code,
 };
 use crate::api::source::{
-    JsBuiltinOptions, JsCode, JsEvalOptions, JsModule, get_raw_source_code,
+    JsBuiltinOptions, JsCode, JsEvalOptions, JsModule, JsModuleKind, get_raw_source_code,
     get_raw_source_code_sync,
 };
 use crate::api::value::{JsValue, install_value_intrinsics};
+use crate::runtime::bindings::{bind_source, unbind};
 use crate::runtime::driver::DriverErrorSource;
 use crate::runtime::shutdown::RuntimeShutdown;
 use flutter_rust_bridge::frb;
 use rquickjs::loader::{BuiltinLoader, BuiltinResolver, FileResolver, NativeLoader, ScriptLoader};
 use rquickjs::promise::MaybePromise;
-use rquickjs::{CatchResultExt, FromJs, Module, Promise};
+use rquickjs::{CatchResultExt, FromJs, IntoJs, Module, Promise};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
@@ -247,44 +250,88 @@ impl MemoryUsage {
     }
 }
 
-type RuntimeResolverStack = (
+/// Severity of an operating-system memory pressure signal.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsMemoryPressureLevel {
+    /// Memory is getting low; reclaim garbage.
+    Moderate,
+    /// Memory is critically low; reclaim garbage and drop caches.
+    Critical,
+}
+
+/// What triggered a [`JsMemoryEvent`].
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsMemoryEventKind {
+    /// Usage crossed the soft limit; a GC pass ran before this event.
+    SoftLimitExceeded,
+    /// Usage dropped back below the soft limit.
+    SoftLimitRecovered,
+    /// A call failed because the hard memory limit was reached.
+    HardLimitExceeded,
+    /// The host reported memory pressure through `notifyMemoryPressure`.
+    MemoryPressure(JsMemoryPressureLevel),
+}
+
+/// A memory event delivered to `JsEngine.memoryEvents()` listeners.
+///
+/// Sizes are QuickJS allocator bytes, as reported by `MemoryUsage.mallocSize`.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsMemoryEvent {
+    /// What triggered the event.
+    pub kind: JsMemoryEventKind,
+    /// Allocator usage when the event was detected.
+    pub usage_before: i64,
+    /// Allocator usage after the engine reacted (GC, cache trimming).
+    pub usage_after: i64,
+    /// The configured soft limit, if any.
+    pub soft_limit: Option<u64>,
+}
+
+type RuntimeResolverStack = TrackingResolver<(
     crate::api::module::ModuleResolver,
-    BuiltinResolver,
+    NamedModuleResolver,
     BuiltinResolver,
     DynamicModuleResolver,
+    TypedFileResolver,
     FileResolver,
-);
+)>;
 
-type RuntimeLoaderStack = (
+type RuntimeLoaderStack = TrackingLoader<(
     rquickjs::loader::ModuleLoader,
-    BuiltinLoader,
+    NamedModuleLoader,
     BuiltinLoader,
     DynamicModuleLoader,
+    TypedFileLoader,
     NativeLoader,
     ScriptLoader,
-);
+)>;
 
 fn make_loader_stack(
     module_resolver: crate::api::module::ModuleResolver,
     module_loader: rquickjs::loader::ModuleLoader,
-    additional_resolver: BuiltinResolver,
-    additional_loader: BuiltinLoader,
+    additional_resolver: NamedModuleResolver,
+    additional_loader: NamedModuleLoader,
 ) -> (RuntimeResolverStack, RuntimeLoaderStack) {
-    let resolver = (
+    let resolver = TrackingResolver((
         module_resolver,
         additional_resolver,
         BuiltinResolver::default(),
         DynamicModuleResolver::default(),
+        TypedFileResolver::default(),
         FileResolver::default(),
-    );
-    let loader = (
+    ));
+    let loader = TrackingLoader((
         module_loader,
         additional_loader,
         BuiltinLoader::default(),
         DynamicModuleLoader::default(),
+        TypedFileLoader::default(),
         NativeLoader::default(),
         ScriptLoader::default(),
-    );
+    ));
     (resolver, loader)
 }
 
@@ -293,8 +340,8 @@ fn install_default_async_loaders(runtime: &rquickjs::AsyncRuntime) {
     let (resolver, loader) = make_loader_stack(
         module_resolver,
         module_loader,
-        BuiltinResolver::default(),
-        BuiltinLoader::default(),
+        NamedModuleResolver::default(),
+        NamedModuleLoader::default(),
     );
     futures::executor::block_on(runtime.set_loader(resolver, loader));
 }
@@ -343,8 +390,8 @@ impl JsRuntime {
         let (resolver, loader) = make_loader_stack(
             module_resolver,
             module_loader,
-            BuiltinResolver::default(),
-            BuiltinLoader::default(),
+            NamedModuleResolver::default(),
+            NamedModuleLoader::default(),
         );
         runtime.set_loader(resolver, loader);
         Ok(Self {
@@ -411,8 +458,8 @@ impl JsRuntime {
         (
             crate::api::module::ModuleResolver,
             rquickjs::loader::ModuleLoader,
-            BuiltinResolver,
-            BuiltinLoader,
+            NamedModuleResolver,
+            NamedModuleLoader,
             GlobalAttachment,
         ),
         JsError,
@@ -424,14 +471,18 @@ impl JsRuntime {
                 ModuleBuilder::new().build()
             };
 
-        let mut additional_resolver = BuiltinResolver::default();
-        let mut additional_loader = BuiltinLoader::default();
+        let mut additional_resolver = NamedModuleResolver::default();
+        let mut additional_loader = NamedModuleLoader::default();
 
         if let Some(named_modules) = modules {
             for module in named_modules {
                 let code = get_raw_source_code(module.source).await?;
-                additional_resolver = additional_resolver.with_module(&module.name);
-                additional_loader = additional_loader.with_module(&module.name, code);
+                let code = match module.kind {
+                    JsModuleKind::Bytes => code,
+                    kind => module_source_code(&module.name, kind, &code)?,
+                };
+                additional_resolver = additional_resolver.with_module(&module.name, module.kind);
+                additional_loader = additional_loader.with_module(&module.name, module.kind, code);
                 global_attachment = global_attachment.add_name(module.name);
             }
         }
@@ -713,6 +764,8 @@ impl JsContext {
         if options.promise.unwrap_or(false) {
             return JsResult::Err(JsError::promise("Promise not supported in sync context"));
         }
+        let mut options = options;
+        let bindings = options.bindings.take();
         self.ctx.with(|ctx| {
             if let Some(attachment) = &self.global_attachment {
                 if let Err(e) = attachment.attach(&ctx) {
@@ -722,7 +775,12 @@ impl JsContext {
                     )));
                 }
             }
+            let code = match bind_source(&ctx, code.into_bytes(), bindings) {
+                Ok(code) => code,
+                Err(e) => return JsResult::Err(e),
+            };
             let res = ctx.eval_with_options(code, options.into());
+            unbind(&ctx);
             result_from_sync(&ctx, res)
         })
     }
@@ -783,6 +841,8 @@ impl JsContext {
             Ok(source) => source,
             Err(error) => return JsResult::Err(error),
         };
+        let mut options = options;
+        let bindings = options.bindings.take();
         let options = file_eval_options(&path, options);
         self.ctx.with(|ctx| {
             if let Some(attachment) = &self.global_attachment {
@@ -793,7 +853,12 @@ impl JsContext {
                     )));
                 }
             }
+            let source = match bind_source(&ctx, source, bindings) {
+                Ok(source) => source,
+                Err(e) => return JsResult::Err(e),
+            };
             let res = ctx.eval_with_options(source, options);
+            unbind(&ctx);
             result_from_sync(&ctx, res)
         })
     }
@@ -851,9 +916,10 @@ impl JsAsyncRuntime {
             .set_host_promise_rejection_tracker(Some(Box::new(
                 move |ctx, promise, reason, is_handled| {
                     let source = DriverErrorSource::promise(&promise);
-                    let error = crate::runtime::job_error::format_value(&ctx, reason);
                     if !is_handled {
-                        rejection_driver.push_error_from(source, error);
+                        let message = crate::runtime::job_error::format_value(&ctx, reason.clone());
+                        let error = JsError::from_thrown_value(&ctx, reason);
+                        rejection_driver.push_unhandled(source, source.origin(), error, message);
                     } else {
                         rejection_driver.remove_error_source(source);
                     }
@@ -887,9 +953,14 @@ impl JsAsyncRuntime {
         futures::executor::block_on(Self::install_error_tracker(&runtime, driver.clone()));
         let shutdown = RuntimeShutdown::default();
         let interrupt_shutdown = shutdown.clone();
-        futures::executor::block_on(
-            runtime.set_interrupt_handler(Some(Box::new(move || interrupt_shutdown.requested()))),
-        );
+        let profiler = driver.profiler();
+        let memory = driver.memory();
+        let metrics = driver.metrics();
+        futures::executor::block_on(runtime.set_interrupt_handler(Some(Box::new(move || {
+            profiler.sample();
+            memory.poll(&metrics);
+            interrupt_shutdown.requested()
+        }))));
         install_default_async_loaders(&runtime);
         let runtime = Self {
             rt: runtime,
@@ -939,8 +1010,15 @@ impl JsAsyncRuntime {
         Self::install_error_tracker(&runtime, driver.clone()).await;
         let shutdown = RuntimeShutdown::default();
         let interrupt_shutdown = shutdown.clone();
+        let profiler = driver.profiler();
+        let memory = driver.memory();
+        let metrics = driver.metrics();
         runtime
-            .set_interrupt_handler(Some(Box::new(move || interrupt_shutdown.requested())))
+            .set_interrupt_handler(Some(Box::new(move || {
+                profiler.sample();
+                memory.poll(&metrics);
+                interrupt_shutdown.requested()
+            })))
             .await;
         let (
             module_resolver,
@@ -1090,8 +1168,11 @@ impl JsAsyncRuntime {
     /// ```
     pub async fn run_gc(&self) {
         let runtime = self.rt.clone();
+        let metrics = self.driver.metrics();
         crate::runtime::executor::run_js(async move {
+            let started = std::time::Instant::now();
             runtime.run_gc().await;
+            metrics.record_gc(started.elapsed());
         })
         .await;
     }
@@ -1203,11 +1284,19 @@ impl JsAsyncRuntime {
     /// The driver keeps timers, fetches, spawned futures, and queued Promise
     /// work moving without requiring the host to poll `execute_pending_job()`.
     /// Starting an already-running driver is a no-op.
-    #[cfg(test)]
     pub(crate) async fn start_driver(&self) {
         self.start_driver_now();
     }
 
+    /// Installs the promise hook behind trace contexts and async stacks.
+    ///
+    /// The hook only acts in contexts where one of them is installed.
+    pub(crate) async fn install_promise_hook(&self) {
+        self.rt
+            .set_promise_hook(Some(crate::runtime::promise_hook::promise_hook()))
+            .await;
+    }
+
     /// Stops the runtime background driver.
     ///
     /// Stopping is idempotent. The runtime remains usable afterwards; callers
@@ -1320,19 +1409,90 @@ impl JsAsyncContext {
     pub(crate) async fn with_foreground_js_result<F>(&self, f: F) -> JsResult
     where
         F: for<'js> AsyncFnOnce(rquickjs::Ctx<'js>, u64) -> JsResult + Send + 'static,
+    {
+        self.with_foreground_js(f, JsResult::Err, |result| {
+            matches!(result, JsResult::Err(JsError::MemoryLimit(_)))
+        })
+        .await
+    }
+
+    /// Runs several calls' worth of foreground work as one task.
+    pub(crate) async fn with_foreground_js_results<F>(&self, f: F) -> Result<Vec<JsResult>, JsError>
+    where
+        F: for<'js> AsyncFnOnce(rquickjs::Ctx<'js>, u64) -> Vec<JsResult> + Send + 'static,
+    {
+        self.with_foreground_js(
+            async move |ctx, checkpoint| Ok(f(ctx, checkpoint).await),
+            Err,
+            |results| {
+                results.as_ref().is_ok_and(|results| {
+                    results
+                        .iter()
+                        .any(|result| matches!(result, JsResult::Err(JsError::MemoryLimit(_))))
+                })
+            },
+        )
+        .await
+    }
+
+    /// Runs foreground work that produces a Rust value instead of a [`JsResult`].
+    pub(crate) async fn with_foreground_js_value<F, T>(&self, f: F) -> Result<T, JsError>
+    where
+        F: for<'js> AsyncFnOnce(rquickjs::Ctx<'js>, u64) -> Result<T, JsError> + Send + 'static,
+        T: Send + 'static,
+    {
+        self.with_foreground_js(f, Err, |result| {
+            matches!(result, Err(JsError::MemoryLimit(_)))
+        })
+        .await
+    }
+
+    async fn with_foreground_js<F, R>(
+        &self,
+        f: F,
+        cancelled: fn(JsError) -> R,
+        hit_memory_limit: fn(&R) -> bool,
+    ) -> R
+    where
+        F: for<'js> AsyncFnOnce(rquickjs::Ctx<'js>, u64) -> R + Send + 'static,
+        R: Send + 'static,
     {
         if self.shutdown.requested() {
-            return JsResult::Err(self.shutdown.error());
+            return cancelled(self.shutdown.error());
         }
         let checkpoint = self.driver.error_checkpoint();
         let shutdown = self.shutdown.clone();
-        self.with_js(async move |ctx| {
-            if shutdown.requested() {
-                return JsResult::Err(shutdown.error());
-            }
-            f(ctx, checkpoint).await
+        let result = crate::runtime::metrics::instrument(
+            crate::runtime::metrics::SpanKind::Foreground,
+            self.with_js(async move |ctx| {
+                if shutdown.requested() {
+                    return cancelled(shutdown.error());
+                }
+                f(ctx, checkpoint).await
+            }),
+        )
+        .await;
+        if hit_memory_limit(&result) {
+            self.report_memory_limit().await;
+        }
+        result
+    }
+
+    /// Reclaims what it can after a call hit the hard memory limit and
+    /// reports it to memory event listeners.
+    async fn report_memory_limit(&self) {
+        let runtime = self.runtime.clone();
+        let memory = self.driver.memory();
+        let metrics = self.driver.metrics();
+        crate::runtime::executor::run_js(async move {
+            let before = runtime.memory_usage().await.malloc_size;
+            let started = std::time::Instant::now();
+            runtime.run_gc().await;
+            metrics.record_gc(started.elapsed());
+            let after = runtime.memory_usage().await.malloc_size;
+            memory.emit(memory.event(JsMemoryEventKind::HardLimitExceeded, before, after));
         })
-        .await
+        .await;
     }
 
     pub(crate) async fn with_js<F, R>(&self, f: F) -> R
@@ -1353,6 +1513,25 @@ impl JsAsyncContext {
         result
     }
 
+    /// Installs the compiled-module cache used when declaring source modules.
+    pub(crate) async fn install_module_cache(
+        &self,
+        cache: crate::runtime::module_cache::ModuleBytecodeCache,
+    ) -> Result<(), JsError> {
+        self.with_js(async move |ctx| {
+            ctx.store_userdata(cache)
+                .map(|_| ())
+                .map_err(|e| JsError::storage(format!("Failed to store module cache: {e:?}")))
+        })
+        .await
+    }
+
+    /// Enables coverage instrumentation for modules declared from source.
+    pub(crate) async fn install_coverage(&self) -> Result<(), JsError> {
+        self.with_js(async |ctx| crate::runtime::coverage::install(&ctx))
+            .await
+    }
+
     /// Creates a new async context from a runtime.
     ///
     /// The context will inherit the runtime's module configuration
@@ -1383,6 +1562,13 @@ impl JsAsyncContext {
             rquickjs::AsyncContext::full(&runtime_handle).await
         })
         .await?;
+        // SAFETY: `context` is live; `JS_GetRuntime` only reads the runtime
+        // that owns it, which outlives every context created from it.
+        if let Some(rt) = std::ptr::NonNull::new(unsafe {
+            rquickjs::qjs::JS_GetRuntime(context.as_raw().as_ptr())
+        }) {
+            runtime.driver.memory().attach(rt);
+        }
         let dynamic_modules: DynamicModuleStorage =
             Arc::new(RwLock::new(std::collections::HashMap::<
                 String,
@@ -1392,6 +1578,7 @@ impl JsAsyncContext {
 
         let context_for_userdata = context.clone();
         let error_sink = crate::runtime::error_sink::RuntimeErrorSink::new(runtime.driver.clone());
+        let metrics = runtime.driver.metrics();
         crate::runtime::executor::run_js(async move {
             context_for_userdata
                 .async_with(async |ctx| {
@@ -1404,6 +1591,9 @@ impl JsAsyncContext {
                     ctx.store_userdata(loaded_dynamic_modules).map_err(|e| {
                         JsError::storage(format!("Failed to store loaded dynamic modules: {e:?}"))
                     })?;
+                    ctx.store_userdata(metrics).map_err(|e| {
+                        JsError::storage(format!("Failed to store runtime metrics: {e:?}"))
+                    })?;
                     Ok::<(), JsError>(())
                 })
                 .await
@@ -1491,7 +1681,13 @@ impl JsAsyncContext {
             }
             let mut options = options;
             options.promise = Some(true);
+            let bindings = options.bindings.take();
+            let code = match bind_source(&ctx, code.into_bytes(), bindings) {
+                Ok(code) => code,
+                Err(e) => return JsResult::Err(e),
+            };
             let res = ctx.eval_with_options(code, options.into());
+            unbind(&ctx);
             let driver = driver.clone();
             result_from_promise(&ctx, res, shutdown, move |source| {
                 driver.remove_error_source_since(checkpoint, source);
@@ -1561,6 +1757,7 @@ impl JsAsyncContext {
         };
         let mut options = options;
         options.promise = Some(true);
+        let bindings = options.bindings.take();
         let options = file_eval_options(&path, options);
 
         let attachment = self.global_attachment.clone();
@@ -1572,7 +1769,12 @@ impl JsAsyncContext {
             {
                 return JsResult::Err(JsError::context(e.to_string()));
             }
+            let source = match bind_source(&ctx, source, bindings) {
+                Ok(source) => source,
+                Err(e) => return JsResult::Err(e),
+            };
             let res = ctx.eval_with_options(source, options);
+            unbind(&ctx);
             let driver = driver.clone();
             result_from_promise(&ctx, res, shutdown, move |source| {
                 driver.remove_error_source_since(checkpoint, source);
@@ -1692,6 +1894,7 @@ impl Drop for JsAsyncContext {
         let Some(context) = self.ctx.take() else {
             return;
         };
+        self.driver.profiler().detach(context.as_raw());
         let runtime = self.runtime.clone();
         let driver = self.driver.clone();
         let shutdown = self.shutdown.clone();
@@ -1716,75 +1919,313 @@ pub(crate) async fn call_module_method<'js>(
     shutdown: RuntimeShutdown,
     acknowledge_error_source: impl Fn(DriverErrorSource),
 ) -> JsResult {
-    let promise = match Module::import(ctx, module.clone()).catch(ctx) {
-        Ok(p) => p,
-        Err(e) => {
-            return JsResult::Err(JsError::module(
-                Some(module),
-                None,
-                format!("Failed to import: {}", e),
-            ));
+    call_module_method_traced(
+        ctx,
+        module,
+        method,
+        params,
+        None,
+        shutdown,
+        acknowledge_error_source,
+    )
+    .await
+}
+
+/// Calls a method on a module with `trace_context` current while the method
+/// runs synchronously; async work it starts keeps the context.
+pub(crate) async fn call_module_method_traced<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    module: String,
+    method: String,
+    params: Vec<JsValue>,
+    trace_context: Option<std::collections::HashMap<String, JsValue>>,
+    shutdown: RuntimeShutdown,
+    acknowledge_error_source: impl Fn(DriverErrorSource),
+) -> JsResult {
+    let obj =
+        match import_module_namespace(ctx, &module, shutdown.clone(), &acknowledge_error_source)
+            .await
+        {
+            Ok(obj) => obj,
+            Err(e) => return JsResult::Err(e),
+        };
+
+    let func = match module_function(ctx, &obj, &module, &method) {
+        Ok(func) => func,
+        Err(e) => return JsResult::Err(e),
+    };
+
+    let previous = match trace_context {
+        Some(context) => match crate::runtime::trace::enter(ctx, context) {
+            Ok(previous) => Some(previous),
+            Err(e) => return JsResult::Err(e),
+        },
+        None => None,
+    };
+    let res = invoke_module_function(ctx, &func, &module, &method, params);
+    if let Some(previous) = previous {
+        crate::runtime::trace::exit(ctx, previous);
+    }
+    result_from_maybe_promise(ctx, res, shutdown, acknowledge_error_source).await
+}
+
+/// One call in a batch passed to [`call_module_methods`].
+pub(crate) struct ModuleMethodCall {
+    pub(crate) module: String,
+    pub(crate) method: String,
+    pub(crate) params: Vec<JsValue>,
+}
+
+/// Calls several module methods, importing each module once.
+///
+/// Sequential batches start each call after the previous one settles.
+/// Concurrent batches start every call first, then wait for all of them.
+/// Results are in call order; a failing call does not affect the others.
+pub(crate) async fn call_module_methods<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    calls: Vec<ModuleMethodCall>,
+    concurrent: bool,
+    shutdown: RuntimeShutdown,
+    acknowledge_error_source: impl Fn(DriverErrorSource),
+) -> Vec<JsResult> {
+    let mut namespaces = std::collections::HashMap::new();
+    for call in &calls {
+        if !namespaces.contains_key(&call.module) {
+            let namespace = import_module_namespace(
+                ctx,
+                &call.module,
+                shutdown.clone(),
+                &acknowledge_error_source,
+            )
+            .await;
+            namespaces.insert(call.module.clone(), namespace);
         }
+    }
+    let start = |call: ModuleMethodCall| -> Result<rquickjs::Result<MaybePromise<'js>>, JsError> {
+        let namespace = namespaces[&call.module].clone()?;
+        let func = module_function(ctx, &namespace, &call.module, &call.method)?;
+        Ok(invoke_module_function(
+            ctx,
+            &func,
+            &call.module,
+            &call.method,
+            call.params,
+        ))
     };
 
-    let import_source = DriverErrorSource::promise(promise.as_ref());
-    let module_value = match promise_value(ctx, promise, shutdown.clone()).await {
-        Ok(v) => v,
-        Err(e) => {
-            if !matches!(e, JsError::Cancelled(_)) {
-                acknowledge_error_source(import_source);
+    if !concurrent {
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            results.push(match start(call) {
+                Ok(res) => {
+                    result_from_maybe_promise(ctx, res, shutdown.clone(), &acknowledge_error_source)
+                        .await
+                }
+                Err(e) => JsResult::Err(e),
+            });
+        }
+        return results;
+    }
+
+    let started: Vec<Result<_, JsError>> = calls.into_iter().map(start).collect();
+    futures::future::join_all(started.into_iter().map(|started| {
+        let shutdown = shutdown.clone();
+        let acknowledge_error_source = &acknowledge_error_source;
+        async move {
+            match started {
+                Ok(res) => {
+                    result_from_maybe_promise(ctx, res, shutdown, acknowledge_error_source).await
+                }
+                Err(e) => JsResult::Err(e),
             }
-            return JsResult::Err(JsError::module(
-                Some(module),
-                None,
-                format!("Failed to import: {e}"),
-            ));
         }
+    }))
+    .await
+}
+
+/// Looks up `method` on a module namespace and checks that it is callable.
+fn module_function<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    namespace: &rquickjs::Object<'js>,
+    module: &str,
+    method: &str,
+) -> Result<rquickjs::Function<'js>, JsError> {
+    let not_a_function = || {
+        JsError::module(
+            Some(module.to_string()),
+            Some(method.to_string()),
+            "Method is not a function",
+        )
     };
+    let func_value: rquickjs::Result<rquickjs::Value> = namespace.get(method);
+    match func_value.catch(ctx) {
+        Ok(v) if v.is_function() => v.as_function().cloned().ok_or_else(not_a_function),
+        Ok(_) => Err(not_a_function()),
+        Err(e) => Err(JsError::module(
+            Some(module.to_string()),
+            Some(method.to_string()),
+            format!("Failed to get method: {}", e),
+        )),
+    }
+}
 
-    let obj = match module_value.as_object() {
-        Some(o) => o,
-        None => {
-            return JsResult::Err(JsError::module(
-                Some(module),
-                None,
-                "Module is not an object",
-            ));
+/// Hidden global caching the compiled generator collector.
+const COLLECT_GENERATOR_GLOBAL: &str = "__fjs_collect_generator__";
+
+/// Collects generator results into arrays; other values pass through.
+const COLLECT_GENERATOR: &str = r#"
+(value) => {
+  const tag = Object.prototype.toString.call(value);
+  if (tag === "[object AsyncGenerator]") {
+    return (async () => {
+      const items = [];
+      for await (const item of value) items.push(item);
+      return items;
+    })();
+  }
+  if (tag === "[object Generator]") return Array.from(value);
+  return value;
+}
+"#;
+
+/// Resolves and invokes the function described by `invocation`.
+///
+/// The path is resolved one property at a time from the module namespace,
+/// or from `globalThis` when no module is given. By default the object that
+/// holds the function is its `this`, as in `api.users.get()`.
+pub(crate) async fn invoke_function<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    invocation: crate::api::engine::JsInvocation,
+    shutdown: RuntimeShutdown,
+    acknowledge_error_source: impl Fn(DriverErrorSource),
+) -> JsResult {
+    use crate::api::engine::JsThisBinding;
+
+    enum Invoke<'js> {
+        Call(rquickjs::Value<'js>),
+        Construct(rquickjs::function::Constructor<'js>),
+    }
+
+    let crate::api::engine::JsInvocation {
+        module,
+        path,
+        params,
+        construct,
+        this_binding,
+    } = invocation;
+    let fail = |message: String| JsError::module(module.clone(), Some(path.clone()), message);
+    let base = match &module {
+        Some(module) => {
+            match import_module_namespace(ctx, module, shutdown.clone(), &acknowledge_error_source)
+                .await
+            {
+                Ok(namespace) => namespace,
+                Err(e) => return JsResult::Err(e),
+            }
         }
+        None => ctx.globals(),
     };
-
-    let func_value: rquickjs::Result<rquickjs::Value> = obj.get(&method);
-    let func = match func_value.catch(ctx) {
-        Ok(v) if v.is_function() => match v.as_function() {
-            Some(f) => f.clone(),
-            None => {
-                return JsResult::Err(JsError::module(
-                    Some(module),
-                    Some(method),
-                    "Method is not a function",
-                ));
+    let resolve = |path: &str| -> Result<(rquickjs::Value<'js>, rquickjs::Value<'js>), JsError> {
+        let mut owner = rquickjs::Value::new_undefined(ctx.clone());
+        let mut value = base.clone().into_value();
+        for segment in path.split('.') {
+            if segment.is_empty() {
+                return Err(fail(format!("Invalid path `{path}`")));
             }
-        },
-        Ok(_) => {
-            return JsResult::Err(JsError::module(
-                Some(module),
-                Some(method),
-                "Method is not a function",
-            ));
+            let Some(object) = value.as_object().cloned() else {
+                return Err(fail(format!("Cannot read `{segment}` of a non-object")));
+            };
+            value = object
+                .get::<_, rquickjs::Value>(segment)
+                .catch(ctx)
+                .map_err(|e| fail(format!("Failed to get `{segment}`: {e}")))?;
+            if value.is_undefined() {
+                return Err(fail(format!("`{segment}` is not defined")));
+            }
+            owner = object.into_value();
         }
-        Err(e) => {
-            return JsResult::Err(JsError::module(
-                Some(module),
-                Some(method),
-                format!("Failed to get method: {}", e),
+        Ok((owner, value))
+    };
+    let (owner, target) = match resolve(&path) {
+        Ok(resolved) => resolved,
+        Err(e) => return JsResult::Err(e),
+    };
+    let Some(func) = target.as_function().cloned() else {
+        return JsResult::Err(fail("Target is not a function".to_string()));
+    };
+    let params = rquickjs::function::Rest(params.unwrap_or_default());
+
+    let construct = construct.unwrap_or(false);
+    let call = if construct {
+        if this_binding.is_some() {
+            return JsResult::Err(fail(
+                "Constructors cannot take a `this` binding".to_string(),
             ));
         }
+        match rquickjs::function::Constructor::from_js(ctx, target) {
+            Ok(constructor) => Invoke::Construct(constructor),
+            Err(_) => return JsResult::Err(fail("Target is not a constructor".to_string())),
+        }
+    } else {
+        let this = match this_binding.unwrap_or_default() {
+            JsThisBinding::Owner => Ok(owner),
+            JsThisBinding::Value(value) => value
+                .into_js(ctx)
+                .catch(ctx)
+                .map_err(|e| fail(format!("Failed to convert `this`: {e}"))),
+            JsThisBinding::Path(path) => resolve(&path).map(|(_, value)| value),
+        };
+        match this {
+            Ok(this) => Invoke::Call(this),
+            Err(e) => return JsResult::Err(e),
+        }
     };
 
-    let res = func.call::<_, MaybePromise>((rquickjs::function::Rest(params),));
+    let label = match &module {
+        Some(module) => format!("call {module}.{path}"),
+        None => format!("call {path}"),
+    };
+    let root = crate::runtime::async_stack::enter(ctx, || label);
+    let res = match call {
+        Invoke::Call(this) => {
+            func.call::<_, rquickjs::Value>((rquickjs::function::This(this), params))
+        }
+        Invoke::Construct(constructor) => constructor.construct::<_, rquickjs::Value>((params,)),
+    };
+    crate::runtime::async_stack::exit(ctx, root);
+
+    let res = res.and_then(|value| collect_generator(ctx)?.call::<_, MaybePromise>((value,)));
     result_from_maybe_promise(ctx, res, shutdown, acknowledge_error_source).await
 }
 
+/// Returns the generator collector of `ctx`, compiling it on first use.
+fn collect_generator<'js>(ctx: &rquickjs::Ctx<'js>) -> rquickjs::Result<rquickjs::Function<'js>> {
+    let globals = ctx.globals();
+    if let Ok(collect) = globals.get::<_, rquickjs::Function>(COLLECT_GENERATOR_GLOBAL) {
+        return Ok(collect);
+    }
+    let collect = ctx.eval::<rquickjs::Function, _>(COLLECT_GENERATOR)?;
+    globals.prop(
+        COLLECT_GENERATOR_GLOBAL,
+        rquickjs::object::Property::from(collect.clone()),
+    )?;
+    Ok(collect)
+}
+
+/// Invokes a module function with the call as the root of its async stack.
+fn invoke_module_function<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    func: &rquickjs::Function<'js>,
+    module: &str,
+    method: &str,
+    params: Vec<JsValue>,
+) -> rquickjs::Result<MaybePromise<'js>> {
+    let root = crate::runtime::async_stack::enter(ctx, || format!("call {module}.{method}"));
+    let res = func.call::<_, MaybePromise>((rquickjs::function::Rest(params),));
+    crate::runtime::async_stack::exit(ctx, root);
+    res
+}
+
 /// Helper function to convert sync result.
 fn result_from_sync<'js>(
     ctx: &rquickjs::Ctx<'js>,
@@ -1799,6 +2240,56 @@ fn result_from_sync<'js>(
     }
 }
 
+/// Imports `module` and returns its namespace object.
+pub(crate) async fn import_module_namespace<'js>(
+    ctx: &rquickjs::Ctx<'js>,
+    module: &str,
+    shutdown: RuntimeShutdown,
+    acknowledge_error_source: &impl Fn(DriverErrorSource),
+) -> Result<rquickjs::Object<'js>, JsError> {
+    let root = crate::runtime::async_stack::enter(ctx, || format!("import {module}"));
+    let imported = import_from_host(ctx, module, || Module::import(ctx, module.to_string()));
+    crate::runtime::async_stack::exit(ctx, root);
+    let promise = match imported.catch(ctx) {
+        Ok(p) => p,
+        Err(e) => {
+            return Err(JsError::module(
+                Some(module.to_string()),
+                None,
+                format!("Failed to import: {}", e),
+            ));
+        }
+    };
+
+    let import_source = DriverErrorSource::promise(promise.as_ref());
+    let module_value = match promise_value(ctx, promise, shutdown).await {
+        Ok(v) => v,
+        Err(e) => {
+            if !matches!(e, JsError::Cancelled(_)) {
+                acknowledge_error_source(import_source);
+                crate::api::module::record_module_errored(ctx, module);
+            }
+            return Err(JsError::module(
+                Some(module.to_string()),
+                None,
+                format!("Failed to import: {e}"),
+            ));
+        }
+    };
+
+    match module_value.into_object() {
+        Some(o) => {
+            crate::api::module::record_module_evaluated(ctx, module, Some(&o));
+            Ok(o)
+        }
+        None => Err(JsError::module(
+            Some(module.to_string()),
+            None,
+            "Module is not an object",
+        )),
+    }
+}
+
 /// Helper function to convert promise result.
 pub(crate) async fn result_from_promise<'js>(
     ctx: &rquickjs::Ctx<'js>,
@@ -4,8 +4,10 @@
 //! modules, and evaluation options.
 
 use crate::api::error::JsError;
+use crate::api::value::JsValue;
 use flutter_rust_bridge::frb;
 use rquickjs::{WriteOptions, WriteOptionsEndianness};
+use std::collections::HashMap;
 use std::io::Read;
 use tokio::io::AsyncReadExt;
 
@@ -168,6 +170,61 @@ pub struct JsModule {
     pub name: String,
     /// The source code for the module
     pub source: JsCode,
+    /// How `source` is interpreted when the module is loaded
+    pub kind: JsModuleKind,
+}
+
+/// Content type of a declared module.
+///
+/// Non-JavaScript kinds are exposed to importers as a module with a single
+/// default export, matching `import x from "..." with { type: "..." }`:
+///
+/// - `json`: the source is parsed as JSON and the parsed value is exported
+/// - `text`: the source is decoded as UTF-8 and exported as a string
+/// - `bytes`: the source bytes are exported as a `Uint8Array`
+///
+/// Imports from JavaScript must carry the `type` attribute matching the
+/// module's kind, and JavaScript modules must be imported without one; any
+/// other import is rejected. The engine's own imports, such as `call()`,
+/// need no attribute.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Default)]
+pub enum JsModuleKind {
+    /// ES module source text.
+    #[default]
+    JavaScript,
+    /// A JSON document exported as its parsed value.
+    Json,
+    /// UTF-8 text exported as a string.
+    Text,
+    /// Raw bytes exported as a `Uint8Array`.
+    Bytes,
+}
+
+impl JsModuleKind {
+    /// Returns the import attribute `type` value for this kind.
+    ///
+    /// JavaScript modules have no `type` attribute and return `None`.
+    #[frb(sync)]
+    pub fn import_type(&self) -> Option<String> {
+        match self {
+            JsModuleKind::JavaScript => None,
+            JsModuleKind::Json => Some("json".to_string()),
+            JsModuleKind::Text => Some("text".to_string()),
+            JsModuleKind::Bytes => Some("bytes".to_string()),
+        }
+    }
+
+    /// Maps an import attribute `type` value to a module kind.
+    #[frb(ignore)]
+    pub fn from_import_type(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(JsModuleKind::Json),
+            "text" => Some(JsModuleKind::Text),
+            "bytes" => Some(JsModuleKind::Bytes),
+            _ => None,
+        }
+    }
 }
 
 impl JsModule {
@@ -192,7 +249,11 @@ impl JsModule {
     /// ```
     #[frb(sync)]
     pub fn new(name: String, source: JsCode) -> Self {
-        JsModule { name, source }
+        JsModule {
+            name,
+            source,
+            kind: JsModuleKind::JavaScript,
+        }
     }
 
     /// Creates a module from inline source text.
@@ -213,6 +274,7 @@ impl JsModule {
         JsModule {
             name: module,
             source: JsCode::Code(code),
+            kind: JsModuleKind::JavaScript,
         }
     }
 
@@ -233,6 +295,7 @@ impl JsModule {
         JsModule {
             name: module,
             source: JsCode::Path(path),
+            kind: JsModuleKind::JavaScript,
         }
     }
 
@@ -254,10 +317,82 @@ impl JsModule {
         JsModule {
             name: module,
             source: JsCode::Bytes(bytes),
+            kind: JsModuleKind::JavaScript,
         }
     }
 }
 
+impl JsModule {
+    /// Creates a module whose source is interpreted as `kind`.
+    ///
+    /// Use this for file-backed JSON, text, or binary modules.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final module = JsModule.typed(
+    ///   module: 'config.json',
+    ///   source: JsCode.path('/absolute/path/to/config.json'),
+    ///   kind: JsModuleKind.json,
+    /// );
+    /// ```
+    #[frb(sync)]
+    pub fn typed(module: String, source: JsCode, kind: JsModuleKind) -> Self {
+        JsModule {
+            name: module,
+            source,
+            kind,
+        }
+    }
+
+    /// Creates a JSON module from an inline JSON document.
+    ///
+    /// The document is parsed when the module is first loaded and exposed as
+    /// the module's default export.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// await engine.declareNewModule(
+    ///   module: JsModule.json(module: 'config.json', json: '{"debug": true}'),
+    /// );
+    /// final debug = await engine.eval(source: JsCode.code('''
+    ///   const { default: cfg } = await import('config.json', { with: { type: 'json' } });
+    ///   cfg.debug
+    /// '''));
+    /// ```
+    #[frb(sync)]
+    pub fn json(module: String, json: String) -> Self {
+        Self::typed(module, JsCode::Code(json), JsModuleKind::Json)
+    }
+
+    /// Creates a text module whose default export is `text`.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final module = JsModule.text(module: 'templates/greeting.txt', text: 'Hello!');
+    /// ```
+    #[frb(sync)]
+    pub fn text(module: String, text: String) -> Self {
+        Self::typed(module, JsCode::Code(text), JsModuleKind::Text)
+    }
+
+    /// Creates a binary module whose default export is a `Uint8Array` of `bytes`.
+    ///
+    /// Unlike `JsModule.bytes(...)`, the bytes are data, not JavaScript source.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final module = JsModule.rawBytes(module: 'assets/logo.bin', bytes: logoBytes);
+    /// ```
+    #[frb(sync)]
+    pub fn raw_bytes(module: String, bytes: Vec<u8>) -> Self {
+        Self::typed(module, JsCode::Bytes(bytes), JsModuleKind::Bytes)
+    }
+}
+
 /// Byte order to use when writing QuickJS module bytecode.
 ///
 /// Use a fixed endianness when bytecode must be shared between devices.
@@ -419,6 +554,58 @@ impl JsModuleBytecodeBundle {
     }
 }
 
+/// Compression applied to module payloads in a bytecode bundle file.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Default)]
+pub enum JsBytecodeCompression {
+    /// Store bytecode uncompressed.
+    #[default]
+    None,
+    /// Compress each module with zstd.
+    Zstd,
+    /// Compress each module with brotli.
+    Brotli,
+}
+
+/// Options used when packing a `JsModuleBytecodeBundle` into a single file.
+///
+/// The file records the embedded QuickJS version, the bytecode byte order, the
+/// entry module, and a SHA-256 checksum for every module. Loading fails when
+/// any of these do not match. Signing is optional; when `signingKey` is set the
+/// whole file is signed with Ed25519 so it can be verified on load.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, Default)]
+pub struct JsBytecodeBundleFileOptions {
+    /// Byte order the bundle's bytecode was compiled with.
+    ///
+    /// Defaults to little-endian, matching `JsModuleBytecodeOptions.defaults()`.
+    /// `JsBytecode.compileModuleBundleFile(...)` fills this in from its compile options.
+    pub endianness: Option<JsBytecodeEndianness>,
+    /// Compression applied to each module payload. Defaults to none.
+    pub compression: Option<JsBytecodeCompression>,
+    /// 32-byte Ed25519 private key seed used to sign the file.
+    pub signing_key: Option<Vec<u8>>,
+}
+
+impl JsBytecodeBundleFileOptions {
+    /// Creates unsigned, uncompressed file options for little-endian bytecode.
+    ///
+    /// ## Example
+    ///
+    /// ```dart
+    /// final file = await JsBytecode.packBundle(
+    ///   bundle: bundle,
+    ///   options: JsBytecodeBundleFileOptions.defaults().copyWith(
+    ///     compression: JsBytecodeCompression.zstd,
+    ///   ),
+    /// );
+    /// ```
+    #[frb(sync)]
+    pub fn defaults() -> Self {
+        Self::default()
+    }
+}
+
 /// Options used when compiling non-module JavaScript into QuickJS bytecode.
 ///
 /// This is intended for classic global/script evaluation, including optional top-level await.
@@ -555,9 +742,15 @@ impl JsScriptBytecode {
 ///   backtraceBarrier: false,
 ///   promise: true,
 /// );
+///
+/// // Passing data without building source strings
+/// final result = await engine.eval(
+///   source: JsCode.code('main(input)'),
+///   options: JsEvalOptions(bindings: {'input': JsValue.from(payload)}),
+/// );
 /// ```
 #[frb(dart_metadata = ("freezed"))]
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct JsEvalOptions {
     /// Whether the code should be evaluated in global scope.
     pub global: Option<bool>,
@@ -567,6 +760,15 @@ pub struct JsEvalOptions {
     pub backtrace_barrier: Option<bool>,
     /// Whether to enable top-level await support.
     pub promise: Option<bool>,
+    /// Values made available to the script as block-scoped constants.
+    ///
+    /// Keys must be valid identifiers. Values are converted to JavaScript
+    /// directly instead of being spliced into the source, so they may come
+    /// from untrusted input. When bindings are given, `let`, `const`, and
+    /// `class` declarations stay local to that evaluation, while `var`
+    /// declarations and sloppy-mode function declarations still become
+    /// globals.
+    pub bindings: Option<HashMap<String, JsValue>>,
 }
 
 impl JsEvalOptions {
@@ -594,6 +796,7 @@ impl JsEvalOptions {
             strict,
             backtrace_barrier,
             promise,
+            bindings: None,
         }
     }
 
@@ -621,6 +824,7 @@ impl JsEvalOptions {
             strict: Some(true),
             backtrace_barrier: Some(false),
             promise: Some(false),
+            bindings: None,
         }
     }
 
@@ -644,6 +848,7 @@ impl JsEvalOptions {
             strict: Some(true),
             backtrace_barrier: Some(false),
             promise: Some(true),
+            bindings: None,
         }
     }
 
@@ -661,10 +866,32 @@ impl JsEvalOptions {
             strict: Some(true),
             backtrace_barrier: Some(false),
             promise: Some(true),
+            bindings: None,
         }
     }
 }
 
+/// Options for `JsEngine.evaluateModule()`.
+///
+/// ## Example
+///
+/// ```dart
+/// final exports = await engine.evaluateModule(
+///   module: JsModule.code(module: 'math', code: 'export const pi = 3.14;'),
+///   options: JsModuleEvalOptions(returnExports: true),
+/// );
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct JsModuleEvalOptions {
+    /// Whether to return the module's exports instead of its completion value.
+    ///
+    /// The exports are returned as an object keyed by export name. Exported
+    /// functions become `JsValue.function` handles carrying the export name,
+    /// which can be passed to `call()` as the method.
+    pub return_exports: Option<bool>,
+}
+
 impl From<JsEvalOptions> for rquickjs::context::EvalOptions {
     fn from(v: JsEvalOptions) -> Self {
         let mut opts = rquickjs::context::EvalOptions::default();
@@ -751,6 +978,8 @@ pub struct JsBuiltinOptions {
     pub string_decoder: Option<bool>,
     /// Enable Temporal global
     pub temporal: Option<bool>,
+    /// Enable the `test` module (`node:test`-compatible runner)
+    pub test: Option<bool>,
     /// Enable timers module
     pub timers: Option<bool>,
     /// Enable tty module
@@ -808,6 +1037,7 @@ impl JsBuiltinOptions {
             stream_web: Some(true),
             string_decoder: Some(true),
             temporal: Some(true),
+            test: Some(true),
             timers: Some(true),
             tty: Some(true),
             url: Some(true),
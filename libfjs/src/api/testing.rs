@@ -0,0 +1,95 @@
+//! # Test Runner
+//!
+//! Types returned by `JsEngine.runTests()`.
+//!
+//! With `JsBuiltinOptions.test` enabled, JavaScript can import a
+//! `node:test`-compatible module (`describe`/`it`/`test`, `before`/`after`
+//! hooks, `skip`/`todo`) and assert with `node:assert`. `runTests()` imports
+//! an entry module, runs the tests it registered, and reports each result.
+
+use flutter_rust_bridge::frb;
+
+/// Outcome of one test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsTestStatus {
+    /// The test completed without throwing.
+    Pass,
+    /// The test, one of its hooks, or one of its subtests failed.
+    Fail,
+    /// The test was skipped with `skip`, or left out because other tests
+    /// are marked `only`.
+    Skip,
+    /// The test was marked `todo` or has no body. A `todo` test with a body
+    /// still runs; if it fails, the error is kept but the test is not failed.
+    Todo,
+}
+
+/// Error raised by a failing test.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsTestFailure {
+    /// Error name, such as `AssertionError`.
+    pub name: String,
+    /// Error message.
+    pub message: String,
+    /// JavaScript stack trace, when available.
+    pub stack: Option<String>,
+}
+
+/// Result of one test.
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsTestResult {
+    /// Test name.
+    pub name: String,
+    /// Names of the enclosing suites and parent tests, outermost first.
+    pub path: Vec<String>,
+    /// Outcome of the test.
+    pub status: JsTestStatus,
+    /// Time spent in the test and its `beforeEach`/`afterEach` hooks.
+    pub duration_ms: f64,
+    /// Failure details when `status` is [`JsTestStatus::Fail`].
+    pub error: Option<JsTestFailure>,
+}
+
+/// Results of a `runTests()` call.
+///
+/// ## Example
+///
+/// ```dart
+/// final report = await engine.runTests(entry: 'cart.test');
+/// for (final result in report.results.where((r) => r.status == JsTestStatus.fail)) {
+///   print('${[...result.path, result.name].join(' > ')}: ${result.error?.message}');
+/// }
+/// print('${report.passed} passed, ${report.failed} failed');
+/// ```
+#[frb(dart_metadata = ("freezed"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsTestReport {
+    /// Per-test results in completion order; subtests precede their parent.
+    pub results: Vec<JsTestResult>,
+    /// Number of passing tests.
+    pub passed: u32,
+    /// Number of failing tests.
+    pub failed: u32,
+    /// Number of skipped tests.
+    pub skipped: u32,
+    /// Number of `todo` tests.
+    pub todo: u32,
+    /// Wall-clock time of the whole run.
+    pub duration_ms: f64,
+}
+
+impl JsTestReport {
+    pub(crate) fn new(results: Vec<JsTestResult>, duration_ms: f64) -> Self {
+        let count = |status| results.iter().filter(|r| r.status == status).count() as u32;
+        Self {
+            passed: count(JsTestStatus::Pass),
+            failed: count(JsTestStatus::Fail),
+            skipped: count(JsTestStatus::Skip),
+            todo: count(JsTestStatus::Todo),
+            results,
+            duration_ms,
+        }
+    }
+}
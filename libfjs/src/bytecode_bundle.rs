@@ -0,0 +1,308 @@
+//! Single-file container format for QuickJS module bytecode bundles.
+//!
+//! Layout (all integers little-endian):
+//!
+//! ```text
+//! magic            b"FJSB"
+//! format version   u16
+//! flags            u8    bit 0: signed
+//! compression      u8    0 none, 1 zstd, 2 brotli
+//! endianness       u8    0 little, 1 big
+//! quickjs version  u16 length + UTF-8
+//! entry            u8 present flag, then u32 length + UTF-8 when present
+//! module count     u32
+//! per module       u32 name length + UTF-8 name
+//!                  [u8; 32] SHA-256 of the uncompressed bytecode
+//!                  u32 uncompressed length
+//!                  u32 stored length + stored bytes
+//! signature        [u8; 64] Ed25519 over every preceding byte, when signed
+//! ```
+
+use crate::api::error::JsError;
+use crate::api::source::{
+    JsBytecodeBundleFileOptions, JsBytecodeCompression, JsBytecodeEndianness, JsModuleBytecode,
+    JsModuleBytecodeBundle,
+};
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use std::ffi::CStr;
+use std::io::Read;
+
+const MAGIC: &[u8; 4] = b"FJSB";
+const FORMAT_VERSION: u16 = 1;
+const FLAG_SIGNED: u8 = 1;
+const SHA256_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+const ZSTD_LEVEL: i32 = 19;
+
+fn bundle_error(message: impl Into<String>) -> JsError {
+    JsError::generic(format!("Invalid bytecode bundle file: {}", message.into()))
+}
+
+/// Returns the version string of the embedded QuickJS engine.
+pub(crate) fn quickjs_version() -> String {
+    // SAFETY: `JS_GetVersion` returns a pointer to a static NUL-terminated
+    // string owned by QuickJS that stays valid for the life of the process.
+    unsafe { CStr::from_ptr(rquickjs::qjs::JS_GetVersion()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn native_endianness() -> JsBytecodeEndianness {
+    if cfg!(target_endian = "big") {
+        JsBytecodeEndianness::Big
+    } else {
+        JsBytecodeEndianness::Little
+    }
+}
+
+fn sha256(bytes: &[u8]) -> [u8; SHA256_LEN] {
+    let digest = ring::digest::digest(&ring::digest::SHA256, bytes);
+    let mut hash = [0; SHA256_LEN];
+    hash.copy_from_slice(digest.as_ref());
+    hash
+}
+
+fn compress(compression: JsBytecodeCompression, bytes: &[u8]) -> Result<Vec<u8>, JsError> {
+    let mut compressed = Vec::new();
+    let result = match compression {
+        JsBytecodeCompression::None => return Ok(bytes.to_vec()),
+        JsBytecodeCompression::Zstd => llrt_compression::zstd::encoder(bytes, ZSTD_LEVEL)
+            .and_then(|mut encoder| encoder.read_to_end(&mut compressed)),
+        JsBytecodeCompression::Brotli => {
+            llrt_compression::brotli::encoder(bytes).read_to_end(&mut compressed)
+        }
+    };
+    result.map_err(|e| JsError::generic(format!("Failed to compress bundle module: {e}")))?;
+    Ok(compressed)
+}
+
+fn decompress(
+    compression: JsBytecodeCompression,
+    bytes: &[u8],
+    expected_len: usize,
+) -> Result<Vec<u8>, JsError> {
+    // `expected_len` comes from the unverified header, so it only bounds how
+    // much is read; the buffer grows with the data actually decompressed.
+    let mut decompressed = Vec::new();
+    let result = match compression {
+        JsBytecodeCompression::None => return Ok(bytes.to_vec()),
+        JsBytecodeCompression::Zstd => llrt_compression::zstd::decoder(bytes).and_then(|decoder| {
+            decoder
+                .take(expected_len as u64 + 1)
+                .read_to_end(&mut decompressed)
+        }),
+        JsBytecodeCompression::Brotli => llrt_compression::brotli::decoder(bytes)
+            .take(expected_len as u64 + 1)
+            .read_to_end(&mut decompressed),
+    };
+    result.map_err(|e| bundle_error(format!("failed to decompress module: {e}")))?;
+    Ok(decompressed)
+}
+
+fn put_len(out: &mut Vec<u8>, len: usize, what: &str) -> Result<(), JsError> {
+    let len =
+        u32::try_from(len).map_err(|_| JsError::generic(format!("Bundle {what} exceeds 4 GiB")))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    Ok(())
+}
+
+/// Serializes `bundle` into the single-file format.
+pub(crate) fn pack_module_bundle(
+    bundle: &JsModuleBytecodeBundle,
+    options: JsBytecodeBundleFileOptions,
+) -> Result<Vec<u8>, JsError> {
+    let compression = options.compression.unwrap_or_default();
+    let endianness = match options.endianness.unwrap_or_default() {
+        JsBytecodeEndianness::Native => native_endianness(),
+        endianness => endianness,
+    };
+    let key_pair = options
+        .signing_key
+        .as_deref()
+        .map(|seed| {
+            Ed25519KeyPair::from_seed_unchecked(seed)
+                .map_err(|_| JsError::generic("Bundle signing key must be a 32-byte Ed25519 seed"))
+        })
+        .transpose()?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out.push(if key_pair.is_some() { FLAG_SIGNED } else { 0 });
+    out.push(match compression {
+        JsBytecodeCompression::None => 0,
+        JsBytecodeCompression::Zstd => 1,
+        JsBytecodeCompression::Brotli => 2,
+    });
+    out.push(match endianness {
+        JsBytecodeEndianness::Big => 1,
+        _ => 0,
+    });
+
+    let version = quickjs_version();
+    out.extend_from_slice(&(version.len() as u16).to_le_bytes());
+    out.extend_from_slice(version.as_bytes());
+
+    match &bundle.entry {
+        Some(entry) => {
+            out.push(1);
+            put_len(&mut out, entry.len(), "entry name")?;
+            out.extend_from_slice(entry.as_bytes());
+        }
+        None => out.push(0),
+    }
+
+    put_len(&mut out, bundle.modules.len(), "module count")?;
+    for module in &bundle.modules {
+        put_len(&mut out, module.name.len(), "module name")?;
+        out.extend_from_slice(module.name.as_bytes());
+        out.extend_from_slice(&sha256(&module.bytes));
+        put_len(&mut out, module.bytes.len(), "module")?;
+        let stored = compress(compression, &module.bytes)?;
+        put_len(&mut out, stored.len(), "module")?;
+        out.extend_from_slice(&stored);
+    }
+
+    if let Some(key_pair) = key_pair {
+        let signature = key_pair.sign(&out);
+        out.extend_from_slice(signature.as_ref());
+    }
+    Ok(out)
+}
+
+/// Returns the Ed25519 public key for a 32-byte signing seed.
+pub(crate) fn bundle_verifying_key(signing_key: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ed25519KeyPair::from_seed_unchecked(signing_key)
+        .map(|key_pair| key_pair.public_key().as_ref().to_vec())
+        .map_err(|_| JsError::generic("Bundle signing key must be a 32-byte Ed25519 seed"))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], JsError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| bundle_error("unexpected end of file"))?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, JsError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, JsError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<usize, JsError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    }
+
+    fn string(&mut self, len: usize) -> Result<String, JsError> {
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| bundle_error("string is not valid UTF-8"))
+    }
+}
+
+/// Parses a single-file bundle, checking its header, checksums, and signature.
+///
+/// When `verifying_key` is set the file must carry a valid Ed25519 signature
+/// from the matching private key.
+pub(crate) fn unpack_module_bundle(
+    bytes: &[u8],
+    verifying_key: Option<&[u8]>,
+) -> Result<JsModuleBytecodeBundle, JsError> {
+    let mut reader = Reader { bytes, offset: 0 };
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(bundle_error("missing FJSB header"));
+    }
+    let format_version = reader.u16()?;
+    if format_version != FORMAT_VERSION {
+        return Err(bundle_error(format!(
+            "unsupported format version {format_version}"
+        )));
+    }
+    let flags = reader.u8()?;
+    let compression = match reader.u8()? {
+        0 => JsBytecodeCompression::None,
+        1 => JsBytecodeCompression::Zstd,
+        2 => JsBytecodeCompression::Brotli,
+        other => return Err(bundle_error(format!("unknown compression {other}"))),
+    };
+    let endianness = match reader.u8()? {
+        0 => JsBytecodeEndianness::Little,
+        1 => JsBytecodeEndianness::Big,
+        other => return Err(bundle_error(format!("unknown endianness {other}"))),
+    };
+    if endianness != native_endianness() {
+        return Err(bundle_error(format!(
+            "bytecode is {endianness:?}-endian but this device is {:?}-endian",
+            native_endianness()
+        )));
+    }
+    let version_len = reader.u16()? as usize;
+    let version = reader.string(version_len)?;
+    if version != quickjs_version() {
+        return Err(bundle_error(format!(
+            "compiled for QuickJS {version}, but this engine embeds QuickJS {}",
+            quickjs_version()
+        )));
+    }
+
+    let signed = flags & FLAG_SIGNED != 0;
+    if let Some(key) = verifying_key {
+        if !signed {
+            return Err(bundle_error("file is not signed"));
+        }
+        let split = bytes
+            .len()
+            .checked_sub(SIGNATURE_LEN)
+            .ok_or_else(|| bundle_error("unexpected end of file"))?;
+        let (message, signature) = bytes.split_at(split);
+        UnparsedPublicKey::new(&ED25519, key)
+            .verify(message, signature)
+            .map_err(|_| bundle_error("signature verification failed"))?;
+    }
+
+    let entry = match reader.u8()? {
+        0 => None,
+        _ => {
+            let len = reader.u32()?;
+            Some(reader.string(len)?)
+        }
+    };
+
+    let count = reader.u32()?;
+    let mut modules = Vec::with_capacity(count.min(1024));
+    for _ in 0..count {
+        let name_len = reader.u32()?;
+        let name = reader.string(name_len)?;
+        let expected_hash = reader.take(SHA256_LEN)?;
+        let uncompressed_len = reader.u32()?;
+        let stored_len = reader.u32()?;
+        let stored = reader.take(stored_len)?;
+        let module_bytes = decompress(compression, stored, uncompressed_len)?;
+        if module_bytes.len() != uncompressed_len || sha256(&module_bytes) != expected_hash {
+            return Err(bundle_error(format!(
+                "checksum mismatch for module '{name}'"
+            )));
+        }
+        modules.push(JsModuleBytecode::new(name, module_bytes));
+    }
+
+    let trailer = if signed { SIGNATURE_LEN } else { 0 };
+    if bytes.len() - reader.offset != trailer {
+        return Err(bundle_error("unexpected trailing data"));
+    }
+    Ok(JsModuleBytecodeBundle::new(entry, modules))
+}
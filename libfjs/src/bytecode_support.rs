@@ -407,6 +407,22 @@ fn read_bytecode_value<'js>(ctx: &Ctx<'js>, bytecode: &[u8]) -> rquickjs::Result
     Ok(unsafe { Value::from_raw(ctx.clone(), raw) })
 }
 
+/// Returns the bytecode format version of the embedded QuickJS engine.
+///
+/// QuickJS writes its format version as the first byte of every serialized
+/// object, so it is read back from a serialized integer once per process.
+pub(crate) fn bytecode_format_version(ctx: &Ctx<'_>) -> Option<u8> {
+    static VERSION: std::sync::OnceLock<u8> = std::sync::OnceLock::new();
+    if let Some(version) = VERSION.get() {
+        return Some(*version);
+    }
+    let value = Value::new_int(ctx.clone(), 0);
+    let options = crate::api::source::JsModuleBytecodeOptions::default().into();
+    let bytes = write_bytecode_value(ctx, &value, options).ok()?;
+    let version = *bytes.first()?;
+    Some(*VERSION.get_or_init(|| version))
+}
+
 fn write_bytecode_value<'js>(
     ctx: &Ctx<'js>,
     value: &Value<'js>,
@@ -0,0 +1,806 @@
+//! Reachability-based heap snapshots.
+//!
+//! QuickJS does not expose its GC object list through the public C API, so
+//! snapshots walk the object graph reachable from the global object and the
+//! namespaces of loaded modules. The walk runs in Rust over the C API and
+//! keeps its bookkeeping in Rust collections, so it does not allocate in the
+//! heap being measured. Objects kept alive only by closure scopes, pending
+//! jobs, or native handles are not visited. Self sizes are estimates derived
+//! from the runtime's aggregate `JSMemoryUsage` averages.
+//!
+//! Retained sizes come from the dominator tree of the collected graph,
+//! computed with the Cooper–Harvey–Kennedy iterative algorithm.
+
+use crate::api::heap::{JsHeapClassStats, JsHeapSnapshot};
+use crate::api::module::js_string_literal;
+use crate::api::runtime::MemoryUsage;
+use rquickjs::function::This;
+use rquickjs::{ArrayBuffer, CatchResultExt, Ctx, Function, Object, Value, qjs};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::mem::MaybeUninit;
+use std::rc::Rc;
+
+/// Upper bound on visited nodes; larger heaps produce a truncated snapshot.
+const MAX_HEAP_NODES: usize = 2_000_000;
+/// Longest string prefix kept as a node name.
+const MAX_STRING_NAME: usize = 256;
+
+/// Chrome node types, in `.heapsnapshot` `node_types` order.
+const NODE_TYPES: &[&str] = &[
+    "hidden",
+    "array",
+    "string",
+    "object",
+    "code",
+    "closure",
+    "regexp",
+    "number",
+    "native",
+    "synthetic",
+    "concatenated string",
+    "sliced string",
+    "symbol",
+    "bigint",
+    "object shape",
+];
+/// Chrome edge types, in `.heapsnapshot` `edge_types` order.
+const EDGE_TYPES: &[&str] = &[
+    "context", "element", "property", "internal", "hidden", "shortcut", "weak",
+];
+
+const NODE_ARRAY: u32 = 1;
+const NODE_STRING: u32 = 2;
+const NODE_OBJECT: u32 = 3;
+const NODE_CLOSURE: u32 = 5;
+const NODE_REGEXP: u32 = 6;
+const NODE_SYNTHETIC: u32 = 9;
+const EDGE_ELEMENT: u32 = 1;
+const EDGE_PROPERTY: u32 = 2;
+const EDGE_INTERNAL: u32 = 3;
+
+/// Estimated per-item sizes, in bytes, used for node self sizes.
+pub(crate) struct HeapSizes {
+    object: f64,
+    property: f64,
+    function: f64,
+    string_header: f64,
+}
+
+impl HeapSizes {
+    /// Derives averages from aggregate runtime memory usage.
+    pub(crate) fn from_usage(usage: &MemoryUsage) -> Self {
+        let average = |size: i64, count: i64, fallback: f64| {
+            if count > 0 {
+                (size as f64 / count as f64).round()
+            } else {
+                fallback
+            }
+        };
+        Self {
+            object: average(usage.obj_size(), usage.obj_count(), 48.0),
+            property: average(usage.prop_size(), usage.prop_count(), 16.0),
+            function: average(usage.js_func_size(), usage.js_func_count(), 72.0),
+            string_header: 16.0,
+        }
+    }
+}
+
+/// A collected object graph in compressed sparse row form.
+pub(crate) struct HeapGraph {
+    strings: Vec<String>,
+    node_type: Vec<u32>,
+    node_name: Vec<u32>,
+    node_class: Vec<u32>,
+    self_size: Vec<u64>,
+    /// `first_edge[n]..first_edge[n + 1]` are the edges of node `n`.
+    first_edge: Vec<usize>,
+    edge_type: Vec<u32>,
+    edge_name: Vec<u32>,
+    edge_to: Vec<u32>,
+    truncated: bool,
+}
+
+impl HeapGraph {
+    /// Walks everything reachable from `roots`.
+    ///
+    /// Only own-property descriptors are read, so accessors are never
+    /// invoked. Proxy traps can still run.
+    pub(crate) fn collect<'js>(
+        ctx: &Ctx<'js>,
+        roots: Vec<(String, Value<'js>)>,
+        sizes: &HeapSizes,
+    ) -> Self {
+        let mut walker = Walker::new(ctx, sizes);
+        walker.graph.first_edge.push(0);
+        let root_name = walker.string("(GC roots)");
+        for (name, value) in roots {
+            let name = walker.string(&name);
+            walker.edge(EDGE_PROPERTY, name, value);
+        }
+        walker.push_node(NODE_SYNTHETIC, root_name, root_name, 0.0);
+
+        let mut next = 0;
+        while let Some(value) = walker.queue.get(next).cloned() {
+            next += 1;
+            walker.visit(value);
+        }
+        let mut graph = walker.graph;
+        graph.first_edge.push(graph.edge_to.len());
+        graph
+    }
+
+    fn node_count(&self) -> usize {
+        self.node_type.len()
+    }
+
+    fn successors(&self, node: usize) -> &[u32] {
+        &self.edge_to[self.first_edge[node]..self.first_edge[node + 1]]
+    }
+
+    /// Returns nodes in DFS postorder from the root.
+    fn postorder(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.node_count()];
+        let mut order = Vec::with_capacity(self.node_count());
+        let mut stack = vec![(0usize, 0usize)];
+        visited[0] = true;
+        while let Some(top) = stack.len().checked_sub(1) {
+            let (node, next) = stack[top];
+            if let Some(&child) = self.successors(node).get(next) {
+                stack[top].1 += 1;
+                let child = child as usize;
+                if !visited[child] {
+                    visited[child] = true;
+                    stack.push((child, 0));
+                }
+            } else {
+                order.push(node);
+                stack.pop();
+            }
+        }
+        order
+    }
+
+    /// Returns each node's immediate dominator (the root dominates itself)
+    /// together with the postorder used to compute it.
+    fn dominators(&self) -> (Vec<usize>, Vec<usize>) {
+        const UNDEFINED: usize = usize::MAX;
+        let postorder = self.postorder();
+        let mut rank = vec![UNDEFINED; self.node_count()];
+        for (index, node) in postorder.iter().enumerate() {
+            rank[*node] = index;
+        }
+        let mut predecessors = vec![Vec::new(); self.node_count()];
+        for node in 0..self.node_count() {
+            for &child in self.successors(node) {
+                predecessors[child as usize].push(node);
+            }
+        }
+
+        let mut idom = vec![UNDEFINED; self.node_count()];
+        idom[0] = 0;
+        let intersect = |idom: &[usize], mut a: usize, mut b: usize| {
+            while a != b {
+                while rank[a] < rank[b] {
+                    a = idom[a];
+                }
+                while rank[b] < rank[a] {
+                    b = idom[b];
+                }
+            }
+            a
+        };
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &node in postorder.iter().rev().skip(1) {
+                let mut new_idom = UNDEFINED;
+                for &pred in &predecessors[node] {
+                    if idom[pred] == UNDEFINED {
+                        continue;
+                    }
+                    new_idom = if new_idom == UNDEFINED {
+                        pred
+                    } else {
+                        intersect(&idom, pred, new_idom)
+                    };
+                }
+                if idom[node] != new_idom {
+                    idom[node] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        (idom, postorder)
+    }
+
+    /// Aggregates the graph into per-class statistics.
+    pub(crate) fn summary(&self) -> JsHeapSnapshot {
+        let (idom, postorder) = self.dominators();
+        let mut retained = self.self_size.clone();
+        for &node in &postorder {
+            if node != 0 {
+                retained[idom[node]] += retained[node];
+            }
+        }
+
+        // A class's retained size counts members that are not dominated by
+        // another member of the same class, so nested instances are not
+        // counted twice.
+        let mut children = vec![Vec::new(); self.node_count()];
+        for &node in &postorder {
+            if node != 0 {
+                children[idom[node]].push(node);
+            }
+        }
+        let mut classes: HashMap<u32, JsHeapClassStats> = HashMap::new();
+        let mut open: HashMap<u32, u32> = HashMap::new();
+        let mut stack = vec![(0usize, false)];
+        while let Some((node, exiting)) = stack.pop() {
+            let class = self.node_class[node];
+            if exiting {
+                if let Some(depth) = open.get_mut(&class) {
+                    *depth -= 1;
+                }
+                continue;
+            }
+            if node != 0 {
+                let stats = classes.entry(class).or_insert_with(|| JsHeapClassStats {
+                    name: self.strings[class as usize].clone(),
+                    count: 0,
+                    self_size: 0,
+                    retained_size: 0,
+                });
+                stats.count += 1;
+                stats.self_size += self.self_size[node];
+                let depth = open.entry(class).or_insert(0);
+                if *depth == 0 {
+                    stats.retained_size += retained[node];
+                }
+                *depth += 1;
+                stack.push((node, true));
+            }
+            stack.extend(children[node].iter().map(|child| (*child, false)));
+        }
+
+        let mut classes: Vec<_> = classes.into_values().collect();
+        classes.sort_by(|a, b| {
+            b.retained_size
+                .cmp(&a.retained_size)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        JsHeapSnapshot {
+            node_count: self.node_count().saturating_sub(1) as u64,
+            edge_count: self.edge_to.len() as u64,
+            total_size: retained[0],
+            truncated: self.truncated,
+            classes,
+        }
+    }
+
+    /// Serializes the graph in the Chrome DevTools `.heapsnapshot` format.
+    pub(crate) fn to_chrome_json(&self) -> String {
+        const NODE_FIELDS: usize = 7;
+        let quote_all = |items: &[&str]| {
+            items
+                .iter()
+                .map(|item| js_string_literal(item))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        let mut json = String::with_capacity(64 * self.node_count() + 32 * self.edge_to.len());
+        json.push_str("{\"snapshot\":{\"meta\":{");
+        json.push_str("\"node_fields\":[\"type\",\"name\",\"id\",\"self_size\",\"edge_count\",\"trace_node_id\",\"detachedness\"],");
+        json.push_str(&format!(
+            "\"node_types\":[[{}],\"string\",\"number\",\"number\",\"number\",\"number\",\"number\"],",
+            quote_all(NODE_TYPES)
+        ));
+        json.push_str("\"edge_fields\":[\"type\",\"name_or_index\",\"to_node\"],");
+        json.push_str(&format!(
+            "\"edge_types\":[[{}],\"string_or_number\",\"node\"],",
+            quote_all(EDGE_TYPES)
+        ));
+        json.push_str("\"trace_function_info_fields\":[\"function_id\",\"name\",\"script_name\",\"script_id\",\"line\",\"column\"],");
+        json.push_str("\"trace_node_fields\":[\"id\",\"function_info_index\",\"count\",\"size\",\"children\"],");
+        json.push_str("\"sample_fields\":[\"timestamp_us\",\"last_assigned_id\"],");
+        json.push_str("\"location_fields\":[\"object_index\",\"script_id\",\"line\",\"column\"]},");
+        json.push_str(&format!(
+            "\"node_count\":{},\"edge_count\":{},\"trace_function_count\":0}},",
+            self.node_count(),
+            self.edge_to.len()
+        ));
+
+        json.push_str("\"nodes\":[");
+        for node in 0..self.node_count() {
+            if node > 0 {
+                json.push(',');
+            }
+            // Odd ids mirror V8's convention for heap objects.
+            json.push_str(&format!(
+                "{},{},{},{},{},0,0",
+                self.node_type[node],
+                self.node_name[node],
+                node * 2 + 1,
+                self.self_size[node],
+                self.first_edge[node + 1] - self.first_edge[node]
+            ));
+        }
+        json.push_str("],\"edges\":[");
+        for edge in 0..self.edge_to.len() {
+            if edge > 0 {
+                json.push(',');
+            }
+            json.push_str(&format!(
+                "{},{},{}",
+                self.edge_type[edge],
+                self.edge_name[edge],
+                self.edge_to[edge] as usize * NODE_FIELDS
+            ));
+        }
+        json.push_str(
+            "],\"trace_function_infos\":[],\"trace_tree\":[],\"samples\":[],\"locations\":[],\"strings\":[",
+        );
+        for (index, string) in self.strings.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            json.push_str(&js_string_literal(string));
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Takes and drops the pending exception so the walk can continue.
+fn clear_exception(ctx: &Ctx<'_>) {
+    let raw = ctx.as_raw().as_ptr();
+    // SAFETY: `raw` is the live context behind `ctx`; the exception taken
+    // here is owned by this function and freed immediately.
+    unsafe {
+        if qjs::JS_HasException(raw) {
+            qjs::JS_FreeValue(raw, qjs::JS_GetException(raw));
+        }
+    }
+}
+
+/// An atom interned for the duration of a walk.
+struct OwnedAtom<'a, 'js> {
+    ctx: &'a Ctx<'js>,
+    atom: qjs::JSAtom,
+}
+
+impl<'a, 'js> OwnedAtom<'a, 'js> {
+    fn new(ctx: &'a Ctx<'js>, name: &CStr) -> Self {
+        // SAFETY: `name` is NUL-terminated; the atom is released on drop.
+        let atom = unsafe { qjs::JS_NewAtom(ctx.as_raw().as_ptr(), name.as_ptr()) };
+        Self { ctx, atom }
+    }
+}
+
+impl Drop for OwnedAtom<'_, '_> {
+    fn drop(&mut self) {
+        // SAFETY: the atom was created by `JS_NewAtom` in this context.
+        unsafe { qjs::JS_FreeAtom(self.ctx.as_raw().as_ptr(), self.atom) };
+    }
+}
+
+/// The string and symbol keys of one object, freed on drop.
+struct OwnKeys<'a, 'js> {
+    ctx: &'a Ctx<'js>,
+    table: *mut qjs::JSPropertyEnum,
+    len: u32,
+}
+
+impl<'a, 'js> OwnKeys<'a, 'js> {
+    /// Lists own keys. Proxy `ownKeys` traps can run.
+    fn of(ctx: &'a Ctx<'js>, object: &Value<'js>) -> Self {
+        let mut table = std::ptr::null_mut();
+        let mut len = 0;
+        // SAFETY: `object` is a live object of this context. On success the
+        // table and its atoms belong to us until `Drop` frees them.
+        let status = unsafe {
+            qjs::JS_GetOwnPropertyNames(
+                ctx.as_raw().as_ptr(),
+                &mut table,
+                &mut len,
+                object.as_raw(),
+                (qjs::JS_GPN_STRING_MASK | qjs::JS_GPN_SYMBOL_MASK) as _,
+            )
+        };
+        if status < 0 {
+            clear_exception(ctx);
+            table = std::ptr::null_mut();
+            len = 0;
+        }
+        Self { ctx, table, len }
+    }
+
+    fn atoms(&self) -> Vec<qjs::JSAtom> {
+        if self.table.is_null() {
+            return Vec::new();
+        }
+        // SAFETY: QuickJS filled `len` entries starting at `table`.
+        unsafe { std::slice::from_raw_parts(self.table, self.len as usize) }
+            .iter()
+            .map(|entry| entry.atom)
+            .collect()
+    }
+}
+
+impl Drop for OwnKeys<'_, '_> {
+    fn drop(&mut self) {
+        if !self.table.is_null() {
+            // SAFETY: the table came from `JS_GetOwnPropertyNames` in this
+            // context and is freed exactly once.
+            unsafe { qjs::JS_FreePropertyEnum(self.ctx.as_raw().as_ptr(), self.table, self.len) };
+        }
+    }
+}
+
+/// An own property, read without invoking accessors.
+enum OwnProperty<'js> {
+    Data(Value<'js>),
+    Accessor { get: Value<'js>, set: Value<'js> },
+}
+
+/// Reads the own property `atom` of `object`. Proxy
+/// `getOwnPropertyDescriptor` traps can run.
+fn own_property<'js>(
+    ctx: &Ctx<'js>,
+    object: &Value<'js>,
+    atom: qjs::JSAtom,
+) -> Option<OwnProperty<'js>> {
+    let raw = ctx.as_raw().as_ptr();
+    let mut descriptor = MaybeUninit::<qjs::JSPropertyDescriptor>::uninit();
+    // SAFETY: `object` is a live value of this context and `atom` is kept
+    // alive by its owner for the duration of the call.
+    let found =
+        unsafe { qjs::JS_GetOwnProperty(raw, descriptor.as_mut_ptr(), object.as_raw(), atom) };
+    if found < 0 {
+        clear_exception(ctx);
+        return None;
+    }
+    if found == 0 {
+        return None;
+    }
+    // SAFETY: a positive result fills the descriptor and hands us one
+    // reference to each of its values.
+    let (flags, value, get, set) = unsafe {
+        let descriptor = descriptor.assume_init();
+        (
+            descriptor.flags,
+            Value::from_raw(ctx.clone(), descriptor.value),
+            Value::from_raw(ctx.clone(), descriptor.getter),
+            Value::from_raw(ctx.clone(), descriptor.setter),
+        )
+    };
+    if flags & qjs::JS_PROP_GETSET as i32 != 0 {
+        Some(OwnProperty::Accessor { get, set })
+    } else {
+        Some(OwnProperty::Data(value))
+    }
+}
+
+/// Renders a property key, showing symbols as `Symbol(description)`.
+fn atom_name(ctx: &Ctx<'_>, atom: qjs::JSAtom) -> Option<String> {
+    let raw = ctx.as_raw().as_ptr();
+    // SAFETY: `atom` is alive for this call; both results are owned here and
+    // released before returning.
+    let key = unsafe { Value::from_raw(ctx.clone(), qjs::JS_AtomToValue(raw, atom)) };
+    let text = unsafe { qjs::JS_AtomToCString(raw, atom) };
+    if text.is_null() {
+        clear_exception(ctx);
+        return None;
+    }
+    // SAFETY: `text` is a NUL-terminated string owned by us until freed.
+    let name = unsafe {
+        let name = CStr::from_ptr(text).to_string_lossy().into_owned();
+        qjs::JS_FreeCString(raw, text);
+        name
+    };
+    Some(if key.is_symbol() {
+        format!("Symbol({name})")
+    } else {
+        name
+    })
+}
+
+/// Returns the element index named by `key`, if it is a canonical array index.
+fn array_index(key: &str) -> Option<u32> {
+    key.parse::<u32>()
+        .ok()
+        .filter(|index| *index != u32::MAX && index.to_string() == key)
+}
+
+/// Returns `value`'s heap address, which identifies it during a walk.
+fn identity(value: &Value<'_>) -> usize {
+    // SAFETY: only called for strings and objects, whose values carry a
+    // pointer to a reference-counted heap cell.
+    unsafe { qjs::JS_VALUE_GET_PTR(value.as_raw()) as usize }
+}
+
+/// Reaches into `Map` and `Set` entries, which are not properties.
+#[derive(Clone)]
+struct Collections<'js> {
+    map: Object<'js>,
+    map_for_each: Function<'js>,
+    set: Object<'js>,
+    set_for_each: Function<'js>,
+    /// Receives `[key, value]` pairs from `forEach`.
+    callback: Function<'js>,
+    entries: Rc<RefCell<Vec<Value<'js>>>>,
+}
+
+impl<'js> Collections<'js> {
+    /// Captures the intrinsics before the walk reads anything else.
+    fn capture(ctx: &Ctx<'js>) -> Option<Self> {
+        let for_each = |constructor: &Object<'js>| -> Option<Function<'js>> {
+            let prototype: Object = constructor.get("prototype").ok()?;
+            prototype.get("forEach").ok()
+        };
+        let globals = ctx.globals();
+        let map: Object = globals.get("Map").ok()?;
+        let set: Object = globals.get("Set").ok()?;
+        let entries = Rc::new(RefCell::new(Vec::new()));
+        let sink = entries.clone();
+        let callback = Function::new(ctx.clone(), move |value: Value<'js>, key: Value<'js>| {
+            sink.borrow_mut().extend([key, value]);
+        })
+        .ok()?;
+        Some(Self {
+            map_for_each: for_each(&map)?,
+            set_for_each: for_each(&set)?,
+            map,
+            set,
+            callback,
+            entries,
+        })
+    }
+}
+
+/// Breadth-first walk state. Everything it tracks lives in Rust collections;
+/// the queue only holds references to values that already exist.
+struct Walker<'a, 'js> {
+    ctx: &'a Ctx<'js>,
+    sizes: &'a HeapSizes,
+    name_atom: OwnedAtom<'a, 'js>,
+    constructor_atom: OwnedAtom<'a, 'js>,
+    collections: Option<Collections<'js>>,
+    string_ids: HashMap<String, u32>,
+    ids: HashMap<usize, u32>,
+    /// Node `n` is `queue[n - 1]`; node 0 is the synthetic root.
+    queue: Vec<Value<'js>>,
+    graph: HeapGraph,
+}
+
+impl<'a, 'js> Walker<'a, 'js> {
+    fn new(ctx: &'a Ctx<'js>, sizes: &'a HeapSizes) -> Self {
+        let collections = Collections::capture(ctx);
+        clear_exception(ctx);
+        Self {
+            ctx,
+            sizes,
+            name_atom: OwnedAtom::new(ctx, c"name"),
+            constructor_atom: OwnedAtom::new(ctx, c"constructor"),
+            collections,
+            string_ids: HashMap::new(),
+            ids: HashMap::new(),
+            queue: Vec::new(),
+            graph: HeapGraph {
+                strings: Vec::new(),
+                node_type: Vec::new(),
+                node_name: Vec::new(),
+                node_class: Vec::new(),
+                self_size: Vec::new(),
+                first_edge: Vec::new(),
+                edge_type: Vec::new(),
+                edge_name: Vec::new(),
+                edge_to: Vec::new(),
+                truncated: false,
+            },
+        }
+    }
+
+    fn string(&mut self, value: &str) -> u32 {
+        if let Some(id) = self.string_ids.get(value) {
+            return *id;
+        }
+        let id = self.graph.strings.len() as u32;
+        self.graph.strings.push(value.to_string());
+        self.string_ids.insert(value.to_string(), id);
+        id
+    }
+
+    /// Returns the node for `value`, queueing it on first sight. Primitives
+    /// other than non-empty strings are not nodes.
+    fn add(&mut self, value: Value<'js>) -> Option<u32> {
+        let tracked = match value.as_string() {
+            Some(string) => string.to_string().is_ok_and(|text| !text.is_empty()),
+            None => value.is_object() || value.is_function(),
+        };
+        if !tracked {
+            return None;
+        }
+        let key = identity(&value);
+        if let Some(index) = self.ids.get(&key) {
+            return Some(*index);
+        }
+        if self.queue.len() + 1 >= MAX_HEAP_NODES {
+            self.graph.truncated = true;
+            return None;
+        }
+        self.queue.push(value);
+        let index = self.queue.len() as u32;
+        self.ids.insert(key, index);
+        Some(index)
+    }
+
+    fn edge(&mut self, kind: u32, name: u32, target: Value<'js>) {
+        if let Some(to) = self.add(target) {
+            self.graph.edge_type.push(kind);
+            self.graph.edge_name.push(name);
+            self.graph.edge_to.push(to);
+        }
+    }
+
+    fn push_node(&mut self, kind: u32, name: u32, class: u32, size: f64) {
+        self.graph.node_type.push(kind);
+        self.graph.node_name.push(name);
+        self.graph.node_class.push(class);
+        self.graph.self_size.push(size.max(0.0) as u64);
+    }
+
+    fn visit(&mut self, value: Value<'js>) {
+        self.graph.first_edge.push(self.graph.edge_to.len());
+        if let Some(string) = value.as_string() {
+            let text = string.to_string().unwrap_or_default();
+            let units = text.encode_utf16().count();
+            let width = if text.chars().any(|c| c as u32 > 0xff) {
+                2
+            } else {
+                1
+            };
+            let shown: String = text.chars().take(MAX_STRING_NAME).collect();
+            let name = self.string(&shown);
+            let class = self.string("(string)");
+            let size = self.sizes.string_header + (units * width) as f64;
+            self.push_node(NODE_STRING, name, class, size);
+            return;
+        }
+
+        let ctx = self.ctx;
+        let keys = OwnKeys::of(ctx, &value);
+        let atoms = keys.atoms();
+        let array = value.is_array();
+        let (kind, name, class, mut size) = if value.is_function() {
+            let name = match own_property(ctx, &value, self.name_atom.atom) {
+                Some(OwnProperty::Data(name)) => name
+                    .as_string()
+                    .and_then(|name| name.to_string().ok())
+                    .filter(|name| !name.is_empty()),
+                _ => None,
+            };
+            let name = self.string(name.as_deref().unwrap_or("(anonymous)"));
+            let class = self.string("(closure)");
+            (NODE_CLOSURE, name, class, self.sizes.function)
+        } else {
+            let class_name = if array {
+                "Array".to_string()
+            } else {
+                self.constructor_name(&value)
+            };
+            let kind = match class_name.as_str() {
+                _ if array => NODE_ARRAY,
+                "RegExp" => NODE_REGEXP,
+                _ => NODE_OBJECT,
+            };
+            let class = self.string(&class_name);
+            let mut size = self.sizes.object;
+            if let Some(buffer) = value
+                .as_object()
+                .cloned()
+                .and_then(ArrayBuffer::from_object)
+            {
+                size += buffer.len() as f64;
+            }
+            (kind, class, class, size)
+        };
+        size += atoms.len() as f64 * self.sizes.property;
+
+        for atom in atoms {
+            let Some(property) = own_property(ctx, &value, atom) else {
+                continue;
+            };
+            let Some(key) = atom_name(ctx, atom) else {
+                continue;
+            };
+            match property {
+                OwnProperty::Data(target) => match array_index(&key) {
+                    Some(index) if array => self.edge(EDGE_ELEMENT, index, target),
+                    _ => {
+                        let name = self.string(&key);
+                        self.edge(EDGE_PROPERTY, name, target);
+                    }
+                },
+                OwnProperty::Accessor { get, set } => {
+                    let getter = self.string(&format!("get {key}"));
+                    self.edge(EDGE_PROPERTY, getter, get);
+                    let setter = self.string(&format!("set {key}"));
+                    self.edge(EDGE_PROPERTY, setter, set);
+                }
+            }
+        }
+        drop(keys);
+
+        let prototype = value.as_object().and_then(|object| object.get_prototype());
+        clear_exception(ctx);
+        if let Some(prototype) = prototype {
+            let name = self.string("__proto__");
+            self.edge(EDGE_PROPERTY, name, prototype.into_value());
+        }
+        self.collection_edges(&value);
+        self.push_node(kind, name, class, size);
+    }
+
+    /// Names an object after its prototype's own `constructor.name`.
+    fn constructor_name(&self, object: &Value<'js>) -> String {
+        let ctx = self.ctx;
+        let prototype = object.as_object().and_then(|object| object.get_prototype());
+        clear_exception(ctx);
+        let Some(prototype) = prototype else {
+            return "(null prototype)".to_string();
+        };
+        if let Some(OwnProperty::Data(constructor)) =
+            own_property(ctx, &prototype.into_value(), self.constructor_atom.atom)
+            && constructor.is_function()
+            && let Some(OwnProperty::Data(name)) =
+                own_property(ctx, &constructor, self.name_atom.atom)
+            && let Some(name) = name.as_string().and_then(|name| name.to_string().ok())
+            && !name.is_empty()
+        {
+            return name;
+        }
+        "Object".to_string()
+    }
+
+    fn collection_edges(&mut self, value: &Value<'js>) {
+        let Some(collections) = self.collections.clone() else {
+            return;
+        };
+        let ctx = self.ctx;
+        for (constructor, for_each, keyed) in [
+            (&collections.map, &collections.map_for_each, true),
+            (&collections.set, &collections.set_for_each, false),
+        ] {
+            // SAFETY: both values are live in this context. Checking first
+            // avoids allocating a TypeError for every non-collection object.
+            let status = unsafe {
+                qjs::JS_IsInstanceOf(ctx.as_raw().as_ptr(), value.as_raw(), constructor.as_raw())
+            };
+            if status < 0 {
+                clear_exception(ctx);
+            }
+            if status <= 0 {
+                continue;
+            }
+            collections.entries.borrow_mut().clear();
+            let called = for_each
+                .call::<_, ()>((This(value.clone()), collections.callback.clone()))
+                .catch(ctx);
+            let entries = std::mem::take(&mut *collections.entries.borrow_mut());
+            if called.is_err() {
+                continue;
+            }
+            let key_name = self.string("key");
+            let value_name = self.string("value");
+            for pair in entries.chunks_exact(2) {
+                if keyed {
+                    self.edge(EDGE_INTERNAL, key_name, pair[0].clone());
+                }
+                self.edge(EDGE_INTERNAL, value_name, pair[1].clone());
+            }
+        }
+    }
+}
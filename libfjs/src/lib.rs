@@ -42,6 +42,7 @@
 #![warn(clippy::undocumented_unsafe_blocks)]
 
 pub mod api;
+mod bytecode_bundle;
 mod bytecode_support;
 #[allow(
     missing_docs,
@@ -49,7 +50,9 @@ mod bytecode_support;
     clippy::undocumented_unsafe_blocks
 )]
 mod frb_generated;
+mod heap_snapshot;
 mod runtime;
+mod test_runner;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,142 @@
+//! Async stack capture across `await` boundaries.
+//!
+//! QuickJS stack traces stop at the innermost synchronous frames, so an error
+//! thrown after an `await` loses the callers that were waiting on it. When
+//! enabled, every Promise records the frames that created it, linked to the
+//! chain that was current at the time. A rejection annotates its reason with
+//! the chain, ending at the foreground operation (eval, call, or module
+//! import) that started the async work, and `JsError` appends it to the stack.
+
+use rquickjs::promise::PromiseState;
+use rquickjs::runtime::PromiseHookType;
+use rquickjs::{CatchResultExt, Ctx, Function, Object, Value};
+
+/// Global holding the hook and entry points used from Rust.
+const ASYNC_STACK_GLOBAL: &str = "__fjs_async_stack__";
+
+/// Non-enumerable property holding the rendered chain on a rejection reason.
+pub(crate) const ASYNC_STACK_PROPERTY: &str = "__fjs_async_stack__";
+
+const INSTALL_ASYNC_STACK: &str = r#"
+(() => {
+  const MAX_LINKS = 16;
+  const KEY = "__fjs_async_stack__";
+  const chains = new WeakMap();
+  const saved = [];
+  let current;
+  // Frames of the caller, without the capture and hook frames.
+  const capture = () => {
+    const frames = String(new Error().stack ?? "")
+      .split("\n")
+      .filter((line) => /^\s+at /.test(line));
+    return frames.slice(2).join("\n");
+  };
+  const site = (frames) => frames.split("\n").pop().replace(/:\d+(:\d+)?\)?$/, "");
+  // A continuation that awaits again replaces its previous await point
+  // instead of stacking on top of it.
+  const link = (frames) => {
+    if (frames === "") return current;
+    const parent = current ?? [];
+    const rest = parent.length > 0 && site(parent[0]) === site(frames) ? parent.slice(1) : parent;
+    return [frames, ...rest].slice(0, MAX_LINKS);
+  };
+  Object.defineProperty(globalThis, "__fjs_async_stack__", {
+    value: Object.freeze({
+      hook(type, promise, reason) {
+        if (type === "init") {
+          const chain = link(capture());
+          if (chain !== undefined) chains.set(promise, chain);
+        } else if (type === "before") {
+          saved.push(current);
+          current = chains.get(promise);
+        } else if (type === "after") {
+          current = saved.pop();
+        } else if (type === "reject") {
+          const chain = current ?? chains.get(promise);
+          if (chain === undefined || reason === null ||
+              (typeof reason !== "object" && typeof reason !== "function") ||
+              Object.prototype.hasOwnProperty.call(reason, KEY)) {
+            return;
+          }
+          try {
+            Object.defineProperty(reason, KEY, {
+              value: chain.map((frames) => `    --- await ---\n${frames}`).join("\n"),
+              configurable: true,
+            });
+          } catch {}
+        }
+      },
+      enter(label) {
+        const previous = current;
+        current = [`    at <${label}>`];
+        return previous;
+      },
+      exit(previous) {
+        current = previous;
+      },
+    }),
+  });
+})();
+"#;
+
+/// Installs async stack capture in `ctx`.
+pub(crate) fn install(ctx: &Ctx<'_>) -> Result<(), crate::api::error::JsError> {
+    ctx.eval::<(), _>(INSTALL_ASYNC_STACK)
+        .catch(ctx)
+        .map_err(|e| {
+            crate::api::error::JsError::runtime(format!("Failed to install async stacks: {e}"))
+        })
+}
+
+/// Forwards a promise event to the context's async stack hook, if installed.
+pub(crate) fn on_promise<'js>(ctx: &Ctx<'js>, kind: PromiseHookType, promise: &Value<'js>) {
+    let Ok(controls) = ctx.globals().get::<_, Object>(ASYNC_STACK_GLOBAL) else {
+        return;
+    };
+    let Ok(hook) = controls.get::<_, Function>("hook") else {
+        return;
+    };
+    let (kind, reason) = match kind {
+        PromiseHookType::Init => ("init", None),
+        PromiseHookType::Before => ("before", None),
+        PromiseHookType::After => ("after", None),
+        PromiseHookType::Resolve => {
+            let Some(promise) = promise.as_promise() else {
+                return;
+            };
+            if promise.state() != PromiseState::Rejected {
+                return;
+            }
+            // Reading a rejected result rethrows the reason; take it back.
+            let _ = promise.result::<Value>();
+            ("reject", Some(ctx.catch()))
+        }
+    };
+    let _ = hook
+        .call::<_, ()>((kind, promise.clone(), reason))
+        .catch(ctx);
+}
+
+/// Makes `label` the root of new async chains and returns the previous root
+/// for [`exit`], or `None` when async stacks are disabled.
+pub(crate) fn enter<'js>(ctx: &Ctx<'js>, label: impl FnOnce() -> String) -> Option<Value<'js>> {
+    ctx.globals()
+        .get::<_, Object>(ASYNC_STACK_GLOBAL)
+        .ok()?
+        .get::<_, Function>("enter")
+        .ok()?
+        .call((label(),))
+        .ok()
+}
+
+/// Restores the root that was current before [`enter`].
+pub(crate) fn exit<'js>(ctx: &Ctx<'js>, previous: Option<Value<'js>>) {
+    let Some(previous) = previous else {
+        return;
+    };
+    if let Ok(controls) = ctx.globals().get::<_, Object>(ASYNC_STACK_GLOBAL)
+        && let Ok(exit) = controls.get::<_, Function>("exit")
+    {
+        let _ = exit.call::<_, ()>((previous,)).catch(ctx);
+    }
+}
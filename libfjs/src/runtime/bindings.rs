@@ -0,0 +1,157 @@
+use crate::api::error::JsError;
+use crate::api::value::JsValue;
+use crate::runtime::coverage::has_use_strict_directive;
+use rquickjs::{CatchResultExt, Ctx, Object};
+use std::collections::HashMap;
+
+/// Global the prelude reads binding values from. The prelude deletes it
+/// before the script's own code runs; [`unbind`] covers scripts that fail to
+/// parse.
+const BINDINGS_GLOBAL: &str = "__fjs_bindings__";
+
+/// Words that cannot name a `const` binding, including those reserved only
+/// in strict mode or inside modules and async functions.
+const RESERVED_WORDS: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Returns whether `name` can be spliced into source as a binding name.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first == '$' || first.is_alphabetic())
+        && chars.all(|c| c == '_' || c == '$' || c.is_alphanumeric())
+        && !RESERVED_WORDS.contains(&name)
+}
+
+/// Makes `bindings` available to `source` as block-scoped constants.
+///
+/// Values are converted to JavaScript and handed over through a temporary
+/// global, never through source text; only the validated names are
+/// spliced in. The script is wrapped in a block whose prelude sits on the
+/// first line, so line numbers in stack traces are unchanged and the block's
+/// completion value is still the script's result. A leading `"use strict"`
+/// directive is repeated ahead of the block, where it still applies.
+///
+/// `let`, `const`, and `class` declarations stay local to the block, but
+/// `var` declarations, and function declarations outside strict mode, still
+/// reach the global object.
+pub(crate) fn bind_source(
+    ctx: &Ctx<'_>,
+    source: Vec<u8>,
+    bindings: Option<HashMap<String, JsValue>>,
+) -> Result<Vec<u8>, JsError> {
+    let Some(bindings) = bindings.filter(|bindings| !bindings.is_empty()) else {
+        return Ok(source);
+    };
+    let mut names: Vec<&String> = bindings.keys().collect();
+    names.sort();
+    if let Some(invalid) = names.iter().find(|name| !is_identifier(name)) {
+        return Err(JsError::context(format!(
+            "Binding name '{invalid}' is not a valid identifier"
+        )));
+    }
+    let strict = std::str::from_utf8(&source).is_ok_and(has_use_strict_directive);
+    let prelude = format!(
+        "{}{{ const {{ {} }} = globalThis.{BINDINGS_GLOBAL}; delete globalThis.{BINDINGS_GLOBAL}; ",
+        if strict { "\"use strict\"; " } else { "" },
+        names
+            .iter()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let values = Object::new(ctx.clone())
+        .catch(ctx)
+        .map_err(|e| JsError::context(format!("Failed to create bindings: {e}")))?;
+    for (name, value) in bindings {
+        values
+            .set(name.as_str(), value)
+            .catch(ctx)
+            .map_err(|e| JsError::context(format!("Failed to convert binding '{name}': {e}")))?;
+    }
+    ctx.globals()
+        .set(BINDINGS_GLOBAL, values)
+        .catch(ctx)
+        .map_err(|e| JsError::context(format!("Failed to install bindings: {e}")))?;
+
+    let mut wrapped = prelude.into_bytes();
+    wrapped.extend_from_slice(&source);
+    wrapped.extend_from_slice(b"\n}");
+    Ok(wrapped)
+}
+
+/// Removes binding values left behind by a script that never ran.
+pub(crate) fn unbind(ctx: &Ctx<'_>) {
+    let _ = ctx.globals().remove(BINDINGS_GLOBAL);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_identifier;
+
+    #[test]
+    fn accepts_only_identifier_names() {
+        assert!(is_identifier("input"));
+        assert!(is_identifier("$el"));
+        assert!(is_identifier("_x1"));
+        assert!(is_identifier("données"));
+        assert!(!is_identifier(""));
+        assert!(!is_identifier("1x"));
+        assert!(!is_identifier("a-b"));
+        assert!(!is_identifier("a; globalThis.x = 1"));
+        assert!(!is_identifier("if"));
+        assert!(!is_identifier("class"));
+        assert!(!is_identifier("await"));
+        assert!(!is_identifier("eval"));
+    }
+}
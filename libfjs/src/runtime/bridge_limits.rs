@@ -0,0 +1,58 @@
+use crate::api::engine::{JsBridgeOptions, JsBridgeOverflowPolicy};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Timeout and concurrency limits applied to `fjs.bridge_call()`.
+#[derive(Clone, Default)]
+pub(crate) struct BridgeLimits {
+    timeout: Option<Duration>,
+    slots: Option<Arc<Semaphore>>,
+    max_in_flight: usize,
+    queue: bool,
+}
+
+impl BridgeLimits {
+    pub(crate) fn new(options: &JsBridgeOptions) -> Self {
+        let max_in_flight = options.max_in_flight.map(|limit| limit.max(1) as usize);
+        Self {
+            timeout: options.timeout_ms.map(Duration::from_millis),
+            slots: max_in_flight.map(|limit| Arc::new(Semaphore::new(limit))),
+            max_in_flight: max_in_flight.unwrap_or(0),
+            queue: options.on_overflow.unwrap_or_default() == JsBridgeOverflowPolicy::Queue,
+        }
+    }
+
+    /// Default timeout for calls that do not set their own.
+    pub(crate) fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Takes an in-flight slot, waiting for one when the overflow policy
+    /// queues. The slot is released when the permit drops.
+    pub(crate) async fn acquire(&self) -> Result<Option<OwnedSemaphorePermit>, String> {
+        let Some(slots) = &self.slots else {
+            return Ok(None);
+        };
+        let full = || {
+            format!(
+                "Too many bridge calls in flight (limit {})",
+                self.max_in_flight
+            )
+        };
+        if self.queue {
+            slots
+                .clone()
+                .acquire_owned()
+                .await
+                .map(Some)
+                .map_err(|_| full())
+        } else {
+            slots
+                .clone()
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(|_| full())
+        }
+    }
+}
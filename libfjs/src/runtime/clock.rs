@@ -0,0 +1,295 @@
+//! Virtual clock and seeded randomness for deterministic execution.
+//!
+//! The clock replaces `Date`, `performance.now`, `Math.random`, and the
+//! timers of a context, both the globals and the `timers` builtin module.
+//! Virtual time only moves when the host advances it, and timers fire in
+//! deadline order as it passes them, so a run depends on nothing but the seed
+//! and the sequence of host calls. `setImmediate` callbacks are due at the
+//! current virtual time, so they also wait for the host. The wall clock
+//! (`Date`) and the monotonic clock (`performance.now`) are tracked
+//! separately: setting the wall clock never fires or reorders timers.
+//!
+//! The controls live in context userdata, out of reach of guest code.
+
+use crate::api::error::{JsError, JsUnhandledErrorOrigin};
+use crate::runtime::error_sink::RuntimeErrorSink;
+use rquickjs::{CatchResultExt, Ctx, Function, JsLifetime, Module, Object, qjs};
+use std::marker::PhantomData;
+
+/// Name of the builtin module served from the virtual timers.
+const TIMERS_MODULE: &str = "timers";
+
+/// Property of `import.meta` holding the virtual timers of the `timers` module.
+const TIMERS_HOOK: &str = "__fjs_timers__";
+
+/// Source of the `timers` module under the virtual clock.
+const TIMERS_MODULE_SOURCE: &str = r#"const timers = import.meta.__fjs_timers__;
+delete import.meta.__fjs_timers__;
+export const { setTimeout, clearTimeout, setInterval, clearInterval, setImmediate, clearImmediate } = timers;
+export default timers;
+"#;
+
+/// Controls of the virtual clock installed in a context.
+struct VirtualClock<'js> {
+    controls: Object<'js>,
+    _marker: PhantomData<&'js ()>,
+}
+
+// SAFETY: The only context-bound field is an `Object` with the same `'js`
+// lifetime, and `Changed` rebinds it to `'to`.
+unsafe impl<'js> JsLifetime<'js> for VirtualClock<'js> {
+    type Changed<'to> = VirtualClock<'to>;
+}
+
+/// Installs the clock. Evaluates to a function taking the timer error
+/// reporter, the job drain, the two halves of the seed, and the initial
+/// wall-clock time, and returning the clock controls.
+const INSTALL_CLOCK: &str = r#"
+(reporter, drain, seedLow, seedHigh, start) => {
+  // sfc32, warmed up so that nearby seeds diverge immediately.
+  let a = seedLow >>> 0, b = seedHigh >>> 0, c = 0x9e3779b9, d = 0x243f6a88;
+  const random = () => {
+    a >>>= 0; b >>>= 0; c >>>= 0; d >>>= 0;
+    const t = (a + b | 0) + d | 0;
+    d = d + 1 | 0;
+    a = b ^ b >>> 9;
+    b = c + (c << 3) | 0;
+    c = c << 21 | c >>> 11;
+    c = c + t | 0;
+    return (t >>> 0) / 4294967296;
+  };
+  for (let i = 0; i < 12; i++) random();
+  Math.random = random;
+
+  let wall = start;
+  let elapsed = 0;
+
+  const NativeDate = Date;
+  const VirtualDate = function Date(...args) {
+    if (new.target === undefined) return new NativeDate(wall).toString();
+    return Reflect.construct(NativeDate, args.length === 0 ? [wall] : args, new.target);
+  };
+  Object.setPrototypeOf(VirtualDate, NativeDate);
+  Object.defineProperty(VirtualDate, "prototype", { value: NativeDate.prototype });
+  VirtualDate.now = () => wall;
+  Object.defineProperty(NativeDate.prototype, "constructor", {
+    value: VirtualDate,
+    writable: true,
+    configurable: true,
+  });
+  globalThis.Date = VirtualDate;
+
+  if (typeof globalThis.performance === "object" && globalThis.performance !== null) {
+    try {
+      Object.defineProperty(globalThis.performance, "now", {
+        value: () => elapsed,
+        writable: true,
+        configurable: true,
+      });
+    } catch {}
+  }
+
+  // Virtual timers ordered by deadline, then by the order they were armed.
+  const timers = new Map();
+  let nextId = 1;
+  let nextOrder = 0;
+  const schedule = (name, callback, args, delay, wait, period) => {
+    if (typeof callback !== "function") {
+      throw new TypeError(`${name} callback must be a function`);
+    }
+    const id = nextId++;
+    timers.set(id, {
+      id, name, callback, args, delay, period,
+      due: elapsed + wait,
+      order: nextOrder++,
+    });
+    return id;
+  };
+  const clear = (id) => { timers.delete(id); };
+  const virtualTimers = Object.freeze({
+    setTimeout(callback, delay, ...args) {
+      const wait = Math.max(0, Number(delay) || 0);
+      return schedule("setTimeout", callback, args, Number(delay) || 0, wait, undefined);
+    },
+    clearTimeout: clear,
+    setInterval(callback, delay, ...args) {
+      const period = Math.max(1, Number(delay) || 0);
+      return schedule("setInterval", callback, args, Number(delay) || 0, period, period);
+    },
+    clearInterval: clear,
+    setImmediate(callback, ...args) {
+      return schedule("setImmediate", callback, args, undefined, 0, undefined);
+    },
+    clearImmediate: clear,
+  });
+  for (const [name, timer] of Object.entries(virtualTimers)) {
+    if (typeof globalThis[name] === "function") {
+      globalThis[name] = timer;
+    }
+  }
+
+  const earliest = (limit) => {
+    let found;
+    for (const timer of timers.values()) {
+      if (timer.due > limit) continue;
+      if (found === undefined || timer.due < found.due ||
+          (timer.due === found.due && timer.order < found.order)) {
+        found = timer;
+      }
+    }
+    return found;
+  };
+
+  return Object.freeze({
+    timers: virtualTimers,
+    advance(ms) {
+      const target = elapsed + ms;
+      let failed = false;
+      let failure;
+      for (let timer = earliest(target); timer !== undefined; timer = earliest(target)) {
+        wall += timer.due - elapsed;
+        elapsed = timer.due;
+        if (timer.period === undefined) {
+          timers.delete(timer.id);
+        } else {
+          timer.due += timer.period;
+          timer.order = nextOrder++;
+        }
+        try {
+          timer.callback.apply(undefined, timer.args);
+        } catch (error) {
+          if (!reporter(error, timer.name, timer.delay, timer.id) && !failed) {
+            failed = true;
+            failure = error;
+          }
+        }
+        drain();
+      }
+      wall += target - elapsed;
+      elapsed = target;
+      if (failed) throw failure;
+    },
+    setTime(ms) {
+      wall = ms;
+    },
+    next() {
+      const timer = earliest(Infinity);
+      return timer === undefined ? undefined : timer.due - elapsed;
+    },
+  });
+}
+"#;
+
+/// Installs the virtual clock and seeded `Math.random` in `ctx`.
+pub(crate) fn install(ctx: &Ctx<'_>, seed: u64, start_time_ms: i64) -> Result<(), JsError> {
+    let reporter = Function::new(ctx.clone(), crate::api::module::record_timer_callback_error)
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to create timer reporter: {e}")))?;
+    let drain = Function::new(ctx.clone(), drain_jobs)
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to create job drain: {e}")))?;
+    let controls = ctx
+        .eval::<Function, _>(INSTALL_CLOCK)
+        .and_then(|install| {
+            install.call::<_, Object>((
+                reporter,
+                drain,
+                seed as u32,
+                (seed >> 32) as u32,
+                start_time_ms as f64,
+            ))
+        })
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to install virtual clock: {e}")))?;
+    ctx.store_userdata(VirtualClock {
+        controls,
+        _marker: PhantomData,
+    })
+    .map_err(|e| JsError::storage(format!("Failed to store virtual clock: {e:?}")))?;
+    Ok(())
+}
+
+/// Runs every queued Promise job, so the jobs a timer callback queues settle
+/// before the next timer fires. A job that throws is reported like the
+/// driver reports failing jobs.
+fn drain_jobs(ctx: Ctx<'_>) {
+    // SAFETY: `ctx` is live and this runs from JavaScript, so its runtime is
+    // locked by the caller.
+    let rt = unsafe { qjs::JS_GetRuntime(ctx.as_raw().as_ptr()) };
+    loop {
+        let mut job_ctx = std::ptr::null_mut();
+        // SAFETY: `rt` is the locked runtime above; the context written to
+        // `job_ctx` is borrowed from the job, not owned by this caller.
+        match unsafe { qjs::JS_ExecutePendingJob(rt, &mut job_ctx) } {
+            0 => break,
+            1.. => {}
+            _ => {
+                let error = ctx.catch();
+                if let Some(sink) = ctx.userdata::<RuntimeErrorSink>() {
+                    sink.push_value(&ctx, JsUnhandledErrorOrigin::Job, error);
+                }
+            }
+        }
+    }
+}
+
+fn controls<'js>(ctx: &Ctx<'js>) -> Option<Object<'js>> {
+    ctx.userdata::<VirtualClock<'js>>()
+        .map(|clock| clock.controls.clone())
+}
+
+fn call<'js, R: rquickjs::FromJs<'js>>(
+    ctx: &Ctx<'js>,
+    method: &str,
+    args: impl rquickjs::function::IntoArgs<'js>,
+) -> Result<R, JsError> {
+    let controls =
+        controls(ctx).ok_or_else(|| JsError::engine("Deterministic mode is not enabled"))?;
+    controls
+        .get::<_, Function>(method)
+        .and_then(|function| function.call(args))
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Virtual clock failed: {e}")))
+}
+
+/// Advances the monotonic and wall clocks by `ms` milliseconds in a single
+/// call into JavaScript, firing every timer due on the way in deadline order
+/// and running the Promise jobs each one queues before the next.
+///
+/// When callbacks fail outside the unhandled-error policy, the remaining
+/// timers still fire and the clock still reaches the target before the first
+/// failure is returned.
+pub(crate) fn advance(ctx: &Ctx<'_>, ms: u64) -> Result<(), JsError> {
+    call(ctx, "advance", (ms as f64,))
+}
+
+/// Sets the virtual wall-clock time without moving timer deadlines.
+pub(crate) fn set_time(ctx: &Ctx<'_>, time_ms: i64) -> Result<(), JsError> {
+    call(ctx, "setTime", (time_ms as f64,))
+}
+
+/// Returns the virtual milliseconds until the next timer is due, or `None`
+/// when deterministic mode is off (`Some(None)` when no timer is armed).
+pub(crate) fn next_delay(ctx: &Ctx<'_>) -> Option<Option<f64>> {
+    controls(ctx)?;
+    call::<Option<f64>>(ctx, "next", ()).ok()
+}
+
+/// Declares the `timers` builtin over the virtual timers when `ctx` runs on
+/// the virtual clock, so importing it does not bypass the clock. Returns
+/// `None` for every other module, and when deterministic mode is off.
+pub(crate) fn declare_timers_module<'js>(
+    ctx: &Ctx<'js>,
+    name: &str,
+) -> Option<rquickjs::Result<Module<'js, rquickjs::module::Declared>>> {
+    if name != TIMERS_MODULE {
+        return None;
+    }
+    let controls = controls(ctx)?;
+    Some(controls.get::<_, Object>("timers").and_then(|timers| {
+        let module = Module::declare(ctx.clone(), name, TIMERS_MODULE_SOURCE)?;
+        let meta: Object = module.meta()?;
+        meta.set(TIMERS_HOOK, timers)?;
+        Ok(module)
+    }))
+}
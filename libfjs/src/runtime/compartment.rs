@@ -0,0 +1,84 @@
+use crate::api::error::JsError;
+use crate::api::value::JsValue;
+use rquickjs::object::Property;
+use rquickjs::{CatchResultExt, Ctx, JsLifetime};
+use std::collections::HashMap;
+
+/// Marks a context created for isolated evaluation. The runtime resolver
+/// refuses every import requested from such a context.
+pub(crate) struct IsolatedCompartment;
+
+// SAFETY: The marker holds no JavaScript handles, so it is valid for every JS
+// lifetime.
+unsafe impl<'js> JsLifetime<'js> for IsolatedCompartment {
+    type Changed<'to> = IsolatedCompartment;
+}
+
+/// Freezes every object reachable from the global object, plus the
+/// intrinsics that are only reachable through syntax (generator, async
+/// function, and iterator prototypes).
+const FREEZE_INTRINSICS: &str = r#"
+(() => {
+  const seen = new WeakSet();
+  const freeze = (value) => {
+    if ((typeof value !== "object" && typeof value !== "function") || value === null) return;
+    if (seen.has(value)) return;
+    seen.add(value);
+    Object.freeze(value);
+    for (const key of Reflect.ownKeys(value)) {
+      const descriptor = Reflect.getOwnPropertyDescriptor(value, key);
+      if (descriptor === undefined) continue;
+      if ("value" in descriptor) freeze(descriptor.value);
+      freeze(descriptor.get);
+      freeze(descriptor.set);
+    }
+    freeze(Object.getPrototypeOf(value));
+  };
+  for (const key of Reflect.ownKeys(globalThis)) {
+    const descriptor = Reflect.getOwnPropertyDescriptor(globalThis, key);
+    if (descriptor !== undefined && "value" in descriptor) freeze(descriptor.value);
+  }
+  freeze(Object.getPrototypeOf(globalThis));
+  freeze(function* () {});
+  freeze(async function () {});
+  freeze(async function* () {});
+  freeze([][Symbol.iterator]());
+  freeze(new Map()[Symbol.iterator]());
+  freeze(new Set()[Symbol.iterator]());
+  freeze(""[Symbol.iterator]());
+  freeze(/x/[Symbol.matchAll](""));
+})();
+"#;
+
+/// Prepares a fresh context for isolated evaluation: refuses imports,
+/// freezes the intrinsics, and defines `bindings` as read-only globals.
+pub(crate) fn prepare(ctx: &Ctx<'_>, bindings: HashMap<String, JsValue>) -> Result<(), JsError> {
+    ctx.store_userdata(IsolatedCompartment)
+        .map_err(|e| JsError::storage(format!("Failed to mark isolated context: {e:?}")))?;
+    ctx.eval::<(), _>(FREEZE_INTRINSICS)
+        .catch(ctx)
+        .map_err(|e| JsError::context(format!("Failed to freeze intrinsics: {e}")))?;
+    let globals = ctx.globals();
+    for (name, value) in bindings {
+        globals
+            .prop(name.as_str(), Property::from(value).enumerable())
+            .catch(ctx)
+            .map_err(|e| JsError::context(format!("Failed to define binding '{name}': {e}")))?;
+    }
+    Ok(())
+}
+
+/// Returns whether `ctx` belongs to an isolated evaluation.
+pub(crate) fn is_isolated(ctx: &Ctx<'_>) -> bool {
+    ctx.userdata::<IsolatedCompartment>().is_some()
+}
+
+/// Returns whether `value` holds a function reference anywhere.
+pub(crate) fn contains_function(value: &JsValue) -> bool {
+    match value {
+        JsValue::Function(_) => true,
+        JsValue::Array(items) => items.iter().any(contains_function),
+        JsValue::Object(entries) => entries.values().any(contains_function),
+        _ => false,
+    }
+}
@@ -0,0 +1,850 @@
+//! Source instrumentation for statement and function coverage.
+//!
+//! QuickJS does not expose per-instruction hooks or its `pc2line` tables
+//! through the public C API, so coverage uses the same approach as Istanbul:
+//! module source is rewritten before it is compiled so that each statement
+//! and function body bumps a counter. The rewriter is a token-level scanner
+//! rather than a full parser. It only inserts probes where a statement is
+//! syntactically certain (at the start of blocks, after `;`, and after closed
+//! control blocks), and leaves ambiguous code uninstrumented. Probes go after
+//! directive prologues such as `"use strict";`, so directives keep their
+//! meaning, and never add lines, so reported positions match the original
+//! source.
+
+use crate::api::coverage::{
+    JsCoverageReport, JsFunctionCoverage, JsModuleCoverage, JsStatementCoverage,
+};
+use crate::api::error::JsError;
+use crate::api::module::js_string_literal;
+use rquickjs::{CatchResultExt, Ctx, JsLifetime, Object};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Global holding one counters object per instrumented module.
+const REGISTRY_GLOBAL: &str = "__fjs_coverage__";
+/// Module-local binding of the module's counters object.
+const COUNTERS: &str = "__fjs_coverage";
+
+/// Keywords after which `/` starts a regular expression.
+const EXPRESSION_KEYWORDS: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+/// Keywords whose parenthesized header is followed by a statement block.
+const CONTROL_KEYWORDS: &[&str] = &["if", "for", "while", "switch", "catch", "with"];
+/// Multi-character punctuators, longest first.
+const PUNCTUATORS: &[&str] = &[
+    ">>>=", "...", "===", "!==", "**=", "<<=", ">>=", ">>>", "&&=", "||=", "??=", "=>", "==", "!=",
+    "<=", ">=", "&&", "||", "??", "?.", "++", "--", "**", "+=", "-=", "*=", "/=", "%=", "&=", "|=",
+    "^=", "<<", ">>",
+];
+
+#[derive(Clone, Copy)]
+struct Position {
+    line: u32,
+    column: u32,
+}
+
+struct FunctionEntry {
+    name: String,
+    position: Position,
+}
+
+/// Statement and function locations of one instrumented module.
+#[derive(Default)]
+struct ModuleMap {
+    statements: Vec<Position>,
+    functions: Vec<FunctionEntry>,
+}
+
+/// Coverage maps of the modules instrumented in one context.
+#[derive(Clone, Default)]
+pub(crate) struct CoverageRecorder {
+    modules: Arc<Mutex<BTreeMap<String, ModuleMap>>>,
+}
+
+// SAFETY: The recorder owns only Rust-side maps and contains no context-bound
+// JavaScript handles, so it is valid for every JS lifetime.
+unsafe impl<'js> JsLifetime<'js> for CoverageRecorder {
+    type Changed<'to> = CoverageRecorder;
+}
+
+/// Enables coverage for modules declared in `ctx` from now on.
+pub(crate) fn install(ctx: &Ctx<'_>) -> Result<(), JsError> {
+    ctx.eval::<(), _>(format!(
+        "Object.defineProperty(globalThis, {}, {{ value: Object.create(null) }});",
+        js_string_literal(REGISTRY_GLOBAL)
+    ))
+    .catch(ctx)
+    .map_err(|e| JsError::runtime(format!("Failed to install coverage registry: {e}")))?;
+    ctx.store_userdata(CoverageRecorder::default())
+        .map(|_| ())
+        .map_err(|e| JsError::storage(format!("Failed to store coverage recorder: {e:?}")))
+}
+
+/// Returns whether modules declared in `ctx` are instrumented.
+pub(crate) fn coverage_enabled(ctx: &Ctx<'_>) -> bool {
+    ctx.userdata::<CoverageRecorder>().is_some()
+}
+
+/// Returns whether `source` opens with a `"use strict"` directive.
+pub(crate) fn has_use_strict_directive(source: &str) -> bool {
+    Cursor {
+        text: source,
+        pos: 0,
+        line: 1,
+        column: 0,
+    }
+    .skip_directives()
+}
+
+/// Returns `source` instrumented for coverage when coverage is enabled.
+///
+/// Sources that are not UTF-8, or whose counters cannot be registered, are
+/// returned unchanged.
+pub(crate) fn instrument_module_source(
+    ctx: &Ctx<'_>,
+    module_name: &str,
+    source: Vec<u8>,
+) -> Vec<u8> {
+    let Some(recorder) = ctx.userdata::<CoverageRecorder>() else {
+        return source;
+    };
+    let Ok(text) = std::str::from_utf8(&source) else {
+        return source;
+    };
+    let (instrumented, map) = instrument(text, module_name);
+    let registered = ctx
+        .globals()
+        .get::<_, Object>(REGISTRY_GLOBAL)
+        .and_then(|registry| {
+            registry.set(
+                module_name,
+                new_counters(ctx, map.statements.len(), map.functions.len())?,
+            )
+        });
+    if registered.is_err() {
+        return source;
+    }
+    recorder
+        .modules
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(module_name.to_string(), map);
+    instrumented.into_bytes()
+}
+
+fn new_counters<'js>(
+    ctx: &Ctx<'js>,
+    statements: usize,
+    functions: usize,
+) -> rquickjs::Result<Object<'js>> {
+    let counters = Object::new(ctx.clone())?;
+    counters.set("s", vec![0u32; statements])?;
+    counters.set("f", vec![0u32; functions])?;
+    Ok(counters)
+}
+
+/// Reads the counters of every instrumented module.
+pub(crate) fn collect(ctx: &Ctx<'_>) -> rquickjs::Result<Option<JsCoverageReport>> {
+    let Some(recorder) = ctx.userdata::<CoverageRecorder>() else {
+        return Ok(None);
+    };
+    let registry: Object = ctx.globals().get(REGISTRY_GLOBAL)?;
+    let modules = recorder
+        .modules
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let mut report = Vec::with_capacity(modules.len());
+    for (name, map) in modules.iter() {
+        let Some(counters) = registry.get::<_, Option<Object>>(name.as_str())? else {
+            continue;
+        };
+        let statement_hits: Vec<f64> = counters.get("s")?;
+        let function_hits: Vec<f64> = counters.get("f")?;
+        let hits = |counts: &[f64], index: usize| counts.get(index).map_or(0, |n| *n as u64);
+        report.push(JsModuleCoverage {
+            module: name.clone(),
+            statements: map
+                .statements
+                .iter()
+                .enumerate()
+                .map(|(index, position)| JsStatementCoverage {
+                    line: position.line,
+                    column: position.column,
+                    hits: hits(&statement_hits, index),
+                })
+                .collect(),
+            functions: map
+                .functions
+                .iter()
+                .enumerate()
+                .map(|(index, function)| JsFunctionCoverage {
+                    name: function.name.clone(),
+                    line: function.position.line,
+                    column: function.position.column,
+                    hits: hits(&function_hits, index),
+                })
+                .collect(),
+        });
+    }
+    Ok(Some(JsCoverageReport { modules: report }))
+}
+
+/// Zeroes the counters of every instrumented module; returns `false` when
+/// coverage is disabled.
+pub(crate) fn reset(ctx: &Ctx<'_>) -> rquickjs::Result<bool> {
+    let Some(recorder) = ctx.userdata::<CoverageRecorder>() else {
+        return Ok(false);
+    };
+    let registry: Object = ctx.globals().get(REGISTRY_GLOBAL)?;
+    let modules = recorder
+        .modules
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    for (name, map) in modules.iter() {
+        if let Some(counters) = registry.get::<_, Option<Object>>(name.as_str())? {
+            counters.set("s", vec![0u32; map.statements.len()])?;
+            counters.set("f", vec![0u32; map.functions.len()])?;
+        }
+    }
+    Ok(true)
+}
+
+/// Lexical scope opened by a bracket.
+#[derive(Clone, Copy, PartialEq)]
+enum Scope<'a> {
+    /// Statement block; `resume` probes the statement after it closes.
+    Block {
+        function: bool,
+        resume: bool,
+    },
+    /// Object literal, class body, or a brace that could not be classified.
+    Braces,
+    Switch,
+    Paren(Opener<'a>),
+    Bracket,
+    /// `${ ... }` inside a template literal.
+    Template,
+}
+
+/// What a parenthesized group belongs to.
+#[derive(Clone, Copy, PartialEq)]
+enum Opener<'a> {
+    Control(&'a str),
+    /// A call or a parameter list, with the identifier before it.
+    Call(Option<&'a str>),
+}
+
+/// The previous significant token.
+#[derive(Clone, Copy, PartialEq)]
+enum Prev<'a> {
+    Start,
+    Punct(&'a str),
+    Word(&'a str),
+    Value,
+    CloseParen(Opener<'a>),
+    CloseBrace { block: bool },
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+    line: u32,
+    column: u32,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.text[self.pos..].chars().nth(1)
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.text[self.pos..].starts_with(prefix)
+    }
+
+    fn position(&self) -> Position {
+        Position {
+            line: self.line,
+            column: self.column,
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn bump_str(&mut self, s: &str) {
+        for _ in s.chars() {
+            self.bump();
+        }
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() || c == '\u{feff}' => {
+                    self.bump();
+                }
+                Some('/') if self.starts_with("//") => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                Some('/') if self.starts_with("/*") => {
+                    self.bump_str("/*");
+                    while self.peek().is_some() && !self.starts_with("*/") {
+                        self.bump();
+                    }
+                    self.bump_str("*/");
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn scan_string(&mut self, quote: char) {
+        self.bump();
+        while let Some(c) = self.bump() {
+            match c {
+                '\\' => {
+                    self.bump();
+                }
+                '\n' => return,
+                c if c == quote => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Scans template characters; returns `true` when stopped at `${`.
+    fn scan_template(&mut self) -> bool {
+        while let Some(c) = self.bump() {
+            match c {
+                '\\' => {
+                    self.bump();
+                }
+                '`' => return false,
+                '$' if self.peek() == Some('{') => {
+                    self.bump();
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Moves past a directive prologue, such as `"use strict";`, so probes
+    /// inserted at the new position do not end it early. Returns whether the
+    /// prologue contains a `"use strict"` directive.
+    fn skip_directives(&mut self) -> bool {
+        let mut strict = false;
+        loop {
+            let start = (self.pos, self.line, self.column);
+            self.skip_trivia();
+            let Some(quote @ ('"' | '\'')) = self.peek() else {
+                (self.pos, self.line, self.column) = start;
+                return strict;
+            };
+            let literal = self.pos;
+            self.scan_string(quote);
+            let directive = &self.text[literal..self.pos];
+            let line = self.line;
+            self.skip_trivia();
+            match self.peek() {
+                Some(';') => {
+                    self.bump();
+                }
+                None | Some('}') => {}
+                // Without a semicolon, the string is a directive only if the
+                // next line starts a new statement.
+                Some(c) if self.line > line && !self.continues_expression(c) => {}
+                _ => {
+                    (self.pos, self.line, self.column) = start;
+                    return strict;
+                }
+            }
+            strict |= matches!(directive, "\"use strict\"" | "'use strict'");
+        }
+    }
+
+    fn continues_expression(&self, c: char) -> bool {
+        if is_word_char(c) {
+            let word = self.text[self.pos..]
+                .split(|c: char| !is_word_char(c))
+                .next()
+                .unwrap_or_default();
+            return matches!(word, "in" | "instanceof");
+        }
+        matches!(
+            c,
+            '.' | '('
+                | '['
+                | '`'
+                | '+'
+                | '-'
+                | '*'
+                | '/'
+                | '%'
+                | '='
+                | '!'
+                | '?'
+                | ','
+                | '<'
+                | '>'
+                | '&'
+                | '|'
+                | '^'
+        )
+    }
+
+    fn scan_regex(&mut self) {
+        self.bump();
+        let mut in_class = false;
+        while let Some(c) = self.peek() {
+            if c == '\n' {
+                return;
+            }
+            self.bump();
+            match c {
+                '\\' => {
+                    self.bump();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                _ => {}
+            }
+        }
+        self.scan_word();
+    }
+
+    fn scan_word(&mut self) -> &'a str {
+        let start = self.pos;
+        if self.peek() == Some('#') {
+            self.bump();
+        }
+        while self.peek().is_some_and(is_word_char) {
+            self.bump();
+        }
+        &self.text[start..self.pos]
+    }
+
+    fn scan_number(&mut self) {
+        while self.peek().is_some_and(|c| is_word_char(c) || c == '.') {
+            self.bump();
+        }
+    }
+
+    fn scan_punct(&mut self) -> &'a str {
+        let start = self.pos;
+        match PUNCTUATORS.iter().find(|p| self.starts_with(p)) {
+            Some(p) => self.bump_str(p),
+            None => {
+                self.bump();
+            }
+        }
+        &self.text[start..self.pos]
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || c == '\\' || !c.is_ascii()
+}
+
+fn is_keyword(word: &str) -> bool {
+    EXPRESSION_KEYWORDS.contains(&word)
+        || CONTROL_KEYWORDS.contains(&word)
+        || matches!(
+            word,
+            "function" | "async" | "class" | "const" | "let" | "var" | "import" | "export"
+        )
+}
+
+fn regex_allowed(prev: Prev<'_>) -> bool {
+    match prev {
+        Prev::Start => true,
+        Prev::Punct(p) => !matches!(p, "]" | "++" | "--"),
+        Prev::Word(word) => EXPRESSION_KEYWORDS.contains(&word),
+        // `if (x) /re/.test(s)`: a control header is followed by a statement.
+        Prev::CloseParen(Opener::Control(_)) => true,
+        Prev::Value | Prev::CloseParen(Opener::Call(_)) => false,
+        Prev::CloseBrace { block } => block,
+    }
+}
+
+/// Whether a statement may start in the innermost scope.
+fn in_statement_context(scopes: &[Scope<'_>]) -> bool {
+    matches!(
+        scopes.last(),
+        None | Some(Scope::Block { .. }) | Some(Scope::Switch)
+    )
+}
+
+/// Rewrites `text` with coverage probes and returns it with its map.
+fn instrument(text: &str, module_name: &str) -> (String, ModuleMap) {
+    let mut map = ModuleMap::default();
+    let mut inserts: Vec<(usize, String)> = Vec::new();
+    let mut cursor = Cursor {
+        text,
+        pos: 0,
+        line: 1,
+        column: 0,
+    };
+
+    // Keep the hashbang first; the header shares the next line so that no
+    // line numbers shift.
+    if text.starts_with("#!") {
+        while cursor.bump().is_some_and(|c| c != '\n') {}
+    }
+    cursor.skip_directives();
+    inserts.push((
+        cursor.pos,
+        format!(
+            "const {COUNTERS} = globalThis.{REGISTRY_GLOBAL}[{}];",
+            js_string_literal(module_name)
+        ),
+    ));
+
+    let mut scopes: Vec<Scope<'_>> = Vec::new();
+    let mut prev = Prev::Start;
+    let mut pending_statement = true;
+    let mut binding: Option<&str> = None;
+    let mut class_head: Option<usize> = None;
+    let mut braceless_do: Vec<usize> = Vec::new();
+
+    loop {
+        cursor.skip_trivia();
+        let Some(c) = cursor.peek() else {
+            break;
+        };
+
+        if std::mem::take(&mut pending_statement) {
+            let next_word = cursor.text[cursor.pos..]
+                .split(|c: char| !is_word_char(c))
+                .next()
+                .unwrap_or_default();
+            if c != '}' && !matches!(next_word, "else" | "catch" | "finally" | "case" | "default") {
+                inserts.push((
+                    cursor.pos,
+                    format!("{COUNTERS}.s[{}]++;", map.statements.len()),
+                ));
+                map.statements.push(cursor.position());
+            }
+        }
+
+        match c {
+            '{' => {
+                let position = cursor.position();
+                cursor.bump();
+                if prev == Prev::Word("do") {
+                    braceless_do.pop();
+                }
+                let scope = if class_head == Some(scopes.len()) {
+                    class_head = None;
+                    Scope::Braces
+                } else {
+                    match prev {
+                        Prev::CloseParen(Opener::Control("switch")) => Scope::Switch,
+                        Prev::CloseParen(Opener::Control(_)) => Scope::Block {
+                            function: false,
+                            resume: true,
+                        },
+                        Prev::CloseParen(Opener::Call(_)) | Prev::Punct("=>") => Scope::Block {
+                            function: true,
+                            resume: false,
+                        },
+                        Prev::Word("else" | "try" | "finally" | "catch" | "static") => {
+                            Scope::Block {
+                                function: false,
+                                resume: true,
+                            }
+                        }
+                        Prev::Word("do") => Scope::Block {
+                            function: false,
+                            resume: false,
+                        },
+                        Prev::Start | Prev::Punct(";") | Prev::CloseBrace { block: true }
+                            if in_statement_context(&scopes) =>
+                        {
+                            Scope::Block {
+                                function: false,
+                                resume: true,
+                            }
+                        }
+                        Prev::Punct("{") if matches!(scopes.last(), Some(Scope::Block { .. })) => {
+                            Scope::Block {
+                                function: false,
+                                resume: true,
+                            }
+                        }
+                        Prev::Punct(":") if scopes.last() == Some(&Scope::Switch) => Scope::Block {
+                            function: false,
+                            resume: true,
+                        },
+                        _ => Scope::Braces,
+                    }
+                };
+                if let Scope::Block { function, .. } = scope {
+                    if function {
+                        let name = match prev {
+                            Prev::CloseParen(Opener::Call(Some(name))) => Some(name),
+                            _ => binding,
+                        };
+                        cursor.skip_directives();
+                        let index = map.functions.len();
+                        inserts.push((cursor.pos, format!("{COUNTERS}.f[{index}]++;")));
+                        map.functions.push(FunctionEntry {
+                            name: name
+                                .map_or_else(|| format!("(anonymous_{index})"), str::to_string),
+                            position,
+                        });
+                    }
+                    pending_statement = true;
+                }
+                scopes.push(scope);
+                binding = None;
+                prev = Prev::Punct("{");
+            }
+            '}' => {
+                cursor.bump();
+                if scopes.last() == Some(&Scope::Template) {
+                    scopes.pop();
+                    if cursor.scan_template() {
+                        scopes.push(Scope::Template);
+                        prev = Prev::Punct("${");
+                    } else {
+                        prev = Prev::Value;
+                    }
+                    continue;
+                }
+                let closed = scopes.pop();
+                if matches!(
+                    closed,
+                    Some(Scope::Block { resume: true, .. }) | Some(Scope::Switch)
+                ) && in_statement_context(&scopes)
+                {
+                    pending_statement = true;
+                }
+                binding = None;
+                prev = Prev::CloseBrace {
+                    block: matches!(closed, Some(Scope::Block { .. }) | Some(Scope::Switch)),
+                };
+            }
+            '(' => {
+                cursor.bump();
+                let opener = match prev {
+                    Prev::Word(word) if CONTROL_KEYWORDS.contains(&word) => Opener::Control(word),
+                    Prev::Word(word) if !is_keyword(word) => Opener::Call(Some(word)),
+                    _ => Opener::Call(None),
+                };
+                scopes.push(Scope::Paren(opener));
+                prev = Prev::Punct("(");
+            }
+            ')' => {
+                cursor.bump();
+                prev = match scopes.last() {
+                    Some(Scope::Paren(opener)) => {
+                        let opener = *opener;
+                        scopes.pop();
+                        Prev::CloseParen(opener)
+                    }
+                    _ => Prev::Value,
+                };
+            }
+            '[' => {
+                cursor.bump();
+                scopes.push(Scope::Bracket);
+                prev = Prev::Punct("[");
+            }
+            ']' => {
+                cursor.bump();
+                if scopes.last() == Some(&Scope::Bracket) {
+                    scopes.pop();
+                }
+                prev = Prev::Punct("]");
+            }
+            ';' => {
+                cursor.bump();
+                if braceless_do.last() == Some(&scopes.len()) {
+                    braceless_do.pop();
+                } else if in_statement_context(&scopes) {
+                    pending_statement = true;
+                }
+                binding = None;
+                prev = Prev::Punct(";");
+            }
+            '"' | '\'' => {
+                cursor.scan_string(c);
+                prev = Prev::Value;
+            }
+            '`' => {
+                cursor.bump();
+                if cursor.scan_template() {
+                    scopes.push(Scope::Template);
+                    prev = Prev::Punct("${");
+                } else {
+                    prev = Prev::Value;
+                }
+            }
+            '/' if regex_allowed(prev) => {
+                cursor.scan_regex();
+                prev = Prev::Value;
+            }
+            c if c.is_ascii_digit()
+                || (c == '.' && cursor.peek_second().is_some_and(|c| c.is_ascii_digit())) =>
+            {
+                cursor.scan_number();
+                prev = Prev::Value;
+            }
+            c if is_word_char(c) || c == '#' => {
+                let word = cursor.scan_word();
+                let member = matches!(prev, Prev::Punct("." | "?."));
+                if word == "class" && !member {
+                    class_head = Some(scopes.len());
+                }
+                if word == "do" && !member {
+                    braceless_do.push(scopes.len());
+                }
+                prev = if member {
+                    Prev::Value
+                } else {
+                    Prev::Word(word)
+                };
+            }
+            _ => {
+                let punct = cursor.scan_punct();
+                match punct {
+                    "=" | ":" => {
+                        if let Prev::Word(word) = prev
+                            && !is_keyword(word)
+                        {
+                            binding = Some(word);
+                        }
+                    }
+                    "," => binding = None,
+                    _ => {}
+                }
+                prev = Prev::Punct(punct);
+            }
+        }
+    }
+
+    let mut output =
+        String::with_capacity(text.len() + inserts.iter().map(|(_, s)| s.len()).sum::<usize>());
+    let mut copied = 0;
+    for (offset, insert) in inserts {
+        output.push_str(&text[copied..offset]);
+        output.push_str(&insert);
+        copied = offset;
+    }
+    output.push_str(&text[copied..]);
+    (output, map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probes_statements_and_function_bodies_but_not_expressions() {
+        let source = "import x from 'x';\nconst o = { a: 1, b() { return /}/.test(`${{ c: 2 }.c}`); } };\nif (o.a) {\n  o.b();\n} else o.a = 2;\nswitch (o.a) { case 1: break; }\ndo o.a--; while (o.a > 0);\nexport const f = (n) => { return n / 2; };\n";
+        let (output, map) = instrument(source, "m");
+
+        assert_eq!(output.lines().count(), source.lines().count());
+        assert!(output.starts_with(
+            "const __fjs_coverage = globalThis.__fjs_coverage__[\"m\"];__fjs_coverage.s[0]++;import x"
+        ));
+        assert!(output.contains("b() {__fjs_coverage.f[0]++; __fjs_coverage.s[2]++;return /}/"));
+        assert!(output.contains("${{ c: 2 }.c}"));
+        assert!(output.contains("} else o.a = 2;"));
+        assert!(output.contains("switch (o.a) { case 1: break;"));
+        assert!(output.contains("do o.a--; while (o.a > 0);"));
+        assert!(output.contains("(n) => {__fjs_coverage.f[1]++;"));
+
+        let names: Vec<_> = map.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["b", "f"]);
+        let lines: Vec<_> = map.statements.iter().map(|s| s.line).collect();
+        assert_eq!(lines, [1, 2, 2, 3, 4, 6, 7, 8, 8]);
+    }
+
+    /// Each case lists fragments that must survive instrumentation intact:
+    /// a probe inside any of them would change what the code means.
+    #[test]
+    fn leaves_literals_and_directives_intact() {
+        let cases: &[(&str, &[&str])] = &[
+            ("if (x) /;{/.test(s);", &["/;{/.test(s)"]),
+            ("while (i--) /}/g.exec(s);", &["/}/g.exec(s)"]),
+            ("for (;;) /;/.test(s) && f();", &["/;/.test(s)"]),
+            ("a = f() / 2; b = /;/;", &["f() / 2;", "/;/;"]),
+            ("a = (b) / 2 / c;", &["(b) / 2 / c;"]),
+            ("a = x[0] / 2; b = /;}/;", &["x[0] / 2;", "/;}/;"]),
+            ("{ } /;/.test(s);", &["/;/.test(s)"]),
+            ("if (a) b(); else /;/.test(s);", &["else /;/.test(s)"]),
+            ("a = /[/;]{1}/;", &["/[/;]{1}/"]),
+            ("a = /\\/;/;", &["/\\/;/"]),
+            (
+                "a = `${ { b: '}' }.b }; ${`;{`}`;",
+                &["`${ { b: '}' }.b }; ${`;{`}`"],
+            ),
+            ("a = '{;}' + \"};\";", &["'{;}' + \"};\""]),
+            ("/* { ; */ a = 1; // } ;\n", &["/* { ; */", "// } ;"]),
+            ("a = this / 2; b = null / 2;", &["this / 2;", "null / 2;"]),
+            ("a = b\n/c/i;", &["b\n/c/i;"]),
+        ];
+        for (source, fragments) in cases {
+            let (output, _) = instrument(source, "m");
+            assert_eq!(output.lines().count(), source.lines().count(), "{source}");
+            for fragment in *fragments {
+                assert!(output.contains(fragment), "{source}\n=> {output}");
+            }
+        }
+    }
+
+    #[test]
+    fn probes_go_after_directive_prologues() {
+        let (output, map) = instrument("'use strict';\n\"use asm\"\nexport const a = 1;", "m");
+        assert!(
+            output.starts_with("'use strict';\n\"use asm\"\nconst __fjs_coverage"),
+            "{output}"
+        );
+        assert_eq!(map.statements.len(), 1);
+
+        let (output, _) = instrument("function f() {\n  'use strict';\n  return 1;\n}", "m");
+        assert!(
+            output.contains("'use strict';__fjs_coverage.f[0]++;\n  __fjs_coverage.s[1]++;return"),
+            "{output}"
+        );
+
+        // Strings that only start an expression statement are not directives.
+        for source in ["'use strict'.length;", "'a'\n+ b;", "'a' in b;"] {
+            let (output, _) = instrument(source, "m");
+            assert!(output.starts_with("const __fjs_coverage"), "{output}");
+        }
+    }
+}
@@ -1,11 +1,18 @@
+use crate::api::error::{
+    JsError, JsUnhandledError, JsUnhandledErrorOrigin, JsUnhandledErrorPolicy,
+};
 use crate::runtime::executor;
+use crate::runtime::memory::MemoryMonitor;
+use crate::runtime::metrics::{RuntimeMetrics, SpanKind, instrument};
+use crate::runtime::profiler::CpuProfiler;
+use crate::runtime::recorder::Recorder;
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Notify;
 
 const MAX_ERRORS: usize = 32;
@@ -14,6 +21,9 @@ const MAX_ERRORS: usize = 32;
 /// driver's schedular waker without a follow-up notification).
 const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
+/// Receives unhandled background errors; returns `false` once it is closed.
+pub(crate) type UnhandledErrorListener = dyn Fn(JsUnhandledError) -> bool + Send + Sync;
+
 #[derive(Clone, Default)]
 pub(crate) struct DriverController {
     inner: Arc<DriverState>,
@@ -25,14 +35,25 @@ struct DriverState {
     next_error_id: AtomicU64,
     lifecycle: Mutex<DriverLifecycle>,
     errors: Mutex<VecDeque<DriverError>>,
+    policy: Mutex<JsUnhandledErrorPolicy>,
+    listeners: Mutex<Vec<Arc<UnhandledErrorListener>>>,
+    metrics: RuntimeMetrics,
+    memory: MemoryMonitor,
+    profiler: CpuProfiler,
+    recorder: Recorder,
     stop_finished: Notify,
     work_added: Notify,
+    paused: AtomicBool,
+    manual: AtomicBool,
 }
 
 struct DriverError {
     id: u64,
     source: DriverErrorSource,
     message: String,
+    error: JsError,
+    origin: JsUnhandledErrorOrigin,
+    delivered: bool,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -45,6 +66,15 @@ impl DriverErrorSource {
     pub(crate) fn promise(value: &rquickjs::Value<'_>) -> Self {
         Self::Promise(JsValueIdentity::from_value(value))
     }
+
+    pub(crate) fn origin(&self) -> JsUnhandledErrorOrigin {
+        match self {
+            Self::Unattributed => JsUnhandledErrorOrigin::Job,
+            Self::Promise(identity) => JsUnhandledErrorOrigin::PromiseRejection {
+                promise_id: identity.bits,
+            },
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -180,11 +210,28 @@ impl DriverController {
         )
     }
 
+    #[cfg(test)]
     pub(crate) fn push_error(&self, error: String) {
         self.push_error_from(DriverErrorSource::Unattributed, error);
     }
 
+    #[cfg(test)]
     pub(crate) fn push_error_from(&self, source: DriverErrorSource, error: String) {
+        let structured = JsError::runtime(error.clone());
+        self.push_unhandled(source, source.origin(), structured, error);
+    }
+
+    /// Queues an unhandled background error together with its origin.
+    ///
+    /// `message` is the flattened text used when the error poisons a later
+    /// call; `error` is the structured form delivered to listeners.
+    pub(crate) fn push_unhandled(
+        &self,
+        source: DriverErrorSource,
+        origin: JsUnhandledErrorOrigin,
+        error: JsError,
+        message: String,
+    ) {
         let id = self.inner.next_error_id.fetch_add(1, Ordering::AcqRel);
         let mut errors = self
             .inner
@@ -197,10 +244,171 @@ impl DriverController {
         errors.push_back(DriverError {
             id,
             source,
-            message: error,
+            message,
+            error,
+            origin,
+            delivered: false,
         });
     }
 
+    pub(crate) fn metrics(&self) -> RuntimeMetrics {
+        self.inner.metrics.clone()
+    }
+
+    pub(crate) fn memory(&self) -> MemoryMonitor {
+        self.inner.memory.clone()
+    }
+
+    pub(crate) fn profiler(&self) -> CpuProfiler {
+        self.inner.profiler.clone()
+    }
+
+    pub(crate) fn recorder(&self) -> Recorder {
+        self.inner.recorder.clone()
+    }
+
+    /// Marks the runtime as paused by its owner; returns the previous value.
+    pub(crate) fn set_paused(&self, paused: bool) -> bool {
+        self.inner.paused.swap(paused, Ordering::AcqRel)
+    }
+
+    pub(crate) fn paused(&self) -> bool {
+        self.inner.paused.load(Ordering::Acquire)
+    }
+
+    /// Marks the runtime as pumped by its host instead of the background loop.
+    pub(crate) fn set_manual(&self, manual: bool) {
+        self.inner.manual.store(manual, Ordering::Release);
+    }
+
+    pub(crate) fn manual(&self) -> bool {
+        self.inner.manual.load(Ordering::Acquire)
+    }
+
+    /// Runs one host-driven turn of the event loop in place of the
+    /// background driver. Stops after `max_jobs` jobs, once `deadline`
+    /// passes, or when nothing is runnable, and returns the number of jobs
+    /// run. Job errors and memory checks are handled as in the driver loop.
+    pub(crate) async fn pump(
+        &self,
+        runtime: rquickjs::AsyncRuntime,
+        max_jobs: u64,
+        deadline: Option<Instant>,
+    ) -> u64 {
+        let driver = self.clone();
+        executor::run_js(async move {
+            let mut jobs = 0;
+            while jobs < max_jobs && deadline.is_none_or(|deadline| Instant::now() < deadline) {
+                match instrument(SpanKind::DriverStep, runtime.execute_pending_job()).await {
+                    Ok(true) => jobs += 1,
+                    Ok(false) => break,
+                    Err(error) => {
+                        jobs += 1;
+                        let error = crate::runtime::job_error::async_job_context(error.0).await;
+                        let message = error.to_string();
+                        driver.push_unhandled(
+                            DriverErrorSource::Unattributed,
+                            JsUnhandledErrorOrigin::Job,
+                            error,
+                            message,
+                        );
+                    }
+                }
+            }
+            driver.inner.metrics.record_driver_iteration(jobs);
+            driver.deliver_errors();
+            driver
+                .inner
+                .memory
+                .check(&runtime, &driver.inner.metrics)
+                .await;
+            jobs
+        })
+        .await
+    }
+
+    pub(crate) fn policy(&self) -> JsUnhandledErrorPolicy {
+        *self
+            .inner
+            .policy
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    pub(crate) fn set_policy(&self, policy: JsUnhandledErrorPolicy) {
+        *self
+            .inner
+            .policy
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = policy;
+    }
+
+    pub(crate) fn add_listener(&self, listener: Arc<UnhandledErrorListener>) {
+        self.inner
+            .listeners
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(listener);
+    }
+
+    /// Hands every not-yet-delivered error to the registered listeners.
+    ///
+    /// Under [`JsUnhandledErrorPolicy::Report`] delivered errors leave the
+    /// queue so they never fail a later call; under
+    /// [`JsUnhandledErrorPolicy::Poison`] they stay queued until drained.
+    pub(crate) fn deliver_errors(&self) {
+        let poison = self.policy() == JsUnhandledErrorPolicy::Poison;
+        let reports = {
+            let mut errors = self
+                .inner
+                .errors
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let reports = errors
+                .iter_mut()
+                .filter(|queued| !queued.delivered)
+                .map(|queued| {
+                    queued.delivered = true;
+                    JsUnhandledError {
+                        error: queued.error.clone(),
+                        origin: queued.origin.clone(),
+                        poisons_engine: poison,
+                    }
+                })
+                .collect::<Vec<_>>();
+            if !poison {
+                errors.clear();
+            }
+            reports
+        };
+        if reports.is_empty() {
+            return;
+        }
+
+        let listeners = self
+            .inner
+            .listeners
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        let mut closed = Vec::new();
+        for listener in &listeners {
+            for report in &reports {
+                if !listener(report.clone()) {
+                    closed.push(Arc::as_ptr(listener));
+                    break;
+                }
+            }
+        }
+        if !closed.is_empty() {
+            self.inner
+                .listeners
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .retain(|listener| !closed.contains(&Arc::as_ptr(listener)));
+        }
+    }
+
     pub(crate) fn error_checkpoint(&self) -> u64 {
         self.inner.next_error_id.load(Ordering::Acquire)
     }
@@ -234,7 +442,21 @@ impl DriverController {
             .retain(|queued| !matches_error(queued));
     }
 
+    /// Returns the queued errors without taking them, so whoever owns the
+    /// queue still reports them.
+    pub(crate) fn peek_errors(&self) -> Vec<String> {
+        self.deliver_errors();
+        self.inner
+            .errors
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .map(|error| error.message.clone())
+            .collect()
+    }
+
     pub(crate) fn drain_errors(&self) -> Vec<String> {
+        self.deliver_errors();
         self.inner
             .errors
             .lock()
@@ -273,10 +495,23 @@ impl Future for WokenAgain {
 }
 
 async fn drive_runtime(runtime: rquickjs::AsyncRuntime, driver: DriverController) {
+    let mut jobs = 0;
     loop {
-        match runtime.execute_pending_job().await {
-            Ok(true) => continue,
+        match instrument(SpanKind::DriverStep, runtime.execute_pending_job()).await {
+            Ok(true) => jobs += 1,
             Ok(false) => {
+                driver
+                    .inner
+                    .metrics
+                    .record_driver_iteration(std::mem::take(&mut jobs));
+                // Pending jobs have settled, so rejections still queued now
+                // were not handled within the turn that produced them.
+                driver.deliver_errors();
+                driver
+                    .inner
+                    .memory
+                    .check(&runtime, &driver.inner.metrics)
+                    .await;
                 // Nothing runnable right now. Park until something can change
                 // that: an explicit work notification from a foreground call,
                 // a schedular wake forwarded from a timer/IO/spawned future,
@@ -289,8 +524,15 @@ async fn drive_runtime(runtime: rquickjs::AsyncRuntime, driver: DriverController
                 }
             }
             Err(error) => {
+                jobs += 1;
                 let error = crate::runtime::job_error::async_job_context(error.0).await;
-                driver.push_error(error.to_string());
+                let message = error.to_string();
+                driver.push_unhandled(
+                    DriverErrorSource::Unattributed,
+                    JsUnhandledErrorOrigin::Job,
+                    error,
+                    message,
+                );
             }
         }
     }
@@ -336,6 +578,7 @@ impl DriverState {
 #[cfg(test)]
 mod tests {
     use super::{DriverController, DriverErrorSource, JsValueIdentity};
+    use crate::api::error::JsUnhandledErrorPolicy;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::time::Duration;
@@ -397,6 +640,31 @@ mod tests {
         assert_eq!(driver.drain_errors(), vec!["stale promise error"]);
     }
 
+    #[test]
+    fn report_policy_delivers_each_error_once_and_clears_queue() {
+        let driver = DriverController::default();
+        let delivered = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let listener_delivered = delivered.clone();
+        driver.add_listener(Arc::new(move |report| {
+            listener_delivered
+                .lock()
+                .unwrap()
+                .push(report.poisons_engine);
+            true
+        }));
+
+        driver.push_error("poisoning".to_string());
+        driver.deliver_errors();
+        driver.deliver_errors();
+        assert_eq!(*delivered.lock().unwrap(), vec![true]);
+
+        driver.set_policy(JsUnhandledErrorPolicy::Report);
+        driver.push_error("reported".to_string());
+        driver.deliver_errors();
+        assert_eq!(*delivered.lock().unwrap(), vec![true, false]);
+        assert!(driver.drain_errors().is_empty());
+    }
+
     #[tokio::test]
     async fn stop_waits_for_driver_task_to_finish_before_restart() {
         let driver = DriverController::default();
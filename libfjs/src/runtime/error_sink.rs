@@ -1,4 +1,5 @@
-use crate::runtime::driver::DriverController;
+use crate::api::error::{JsError, JsUnhandledErrorOrigin};
+use crate::runtime::driver::{DriverController, DriverErrorSource};
 use rquickjs::{CatchResultExt, CaughtError, Ctx, Exception, FromJs, JsLifetime, Value};
 use std::sync::Once;
 
@@ -12,8 +13,41 @@ impl RuntimeErrorSink {
         Self { driver }
     }
 
-    pub(crate) fn push(&self, error: String) {
-        self.driver.push_error(error);
+    /// Reports a thrown JavaScript value from a background origin.
+    pub(crate) fn push_value<'js>(
+        &self,
+        ctx: &Ctx<'js>,
+        origin: JsUnhandledErrorOrigin,
+        value: Value<'js>,
+    ) {
+        let message = format_value(ctx, value.clone());
+        let error = JsError::from_thrown_value(ctx, value);
+        self.driver
+            .push_unhandled(DriverErrorSource::Unattributed, origin, error, message);
+    }
+
+    /// Reports a caught QuickJS error from a background origin.
+    pub(crate) fn push_caught<'js>(
+        &self,
+        ctx: &Ctx<'js>,
+        origin: JsUnhandledErrorOrigin,
+        error: CaughtError<'js>,
+    ) {
+        match error {
+            CaughtError::Exception(exception) => {
+                self.push_value(ctx, origin, exception.into_object().into_value())
+            }
+            CaughtError::Value(value) => self.push_value(ctx, origin, value),
+            CaughtError::Error(error) => {
+                let message = error.to_string();
+                self.driver.push_unhandled(
+                    DriverErrorSource::Unattributed,
+                    origin,
+                    error.into(),
+                    message,
+                );
+            }
+        }
     }
 }
 
@@ -29,20 +63,12 @@ pub(crate) fn install_llrt_spawn_error_handler() {
     INSTALL_SPAWN_ERROR_HANDLER.call_once(|| {
         llrt_context::set_spawn_error_handler(|ctx, error| {
             if let Some(sink) = ctx.userdata::<RuntimeErrorSink>() {
-                sink.push(format_caught_error(ctx, error));
+                sink.push_caught(ctx, JsUnhandledErrorOrigin::Task, error);
             }
         });
     });
 }
 
-pub(crate) fn format_caught_error<'js>(ctx: &Ctx<'js>, error: CaughtError<'js>) -> String {
-    match error {
-        CaughtError::Exception(exception) => exception.to_string(),
-        CaughtError::Value(value) => format_value(ctx, value),
-        CaughtError::Error(error) => error.to_string(),
-    }
-}
-
 pub(crate) fn format_value<'js>(ctx: &Ctx<'js>, value: Value<'js>) -> String {
     if let Some(exception) = value.clone().into_object().and_then(Exception::from_object) {
         return exception.to_string();
@@ -0,0 +1,206 @@
+use crate::api::runtime::{JsMemoryEvent, JsMemoryEventKind};
+use crate::runtime::metrics::RuntimeMetrics;
+use rquickjs::qjs;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Minimum time between two soft-limit checks. Measuring usage walks the
+/// whole heap, so usage is sampled instead of measured on every poll.
+const MIN_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Receives memory events; returns `false` once it is closed.
+pub(crate) type MemoryEventListener = dyn Fn(JsMemoryEvent) -> bool + Send + Sync;
+
+#[derive(Default)]
+struct MonitorState {
+    /// Soft limit in bytes; `0` disables the check.
+    soft_limit: AtomicUsize,
+    above_soft_limit: AtomicBool,
+    last_check: Mutex<Option<Instant>>,
+    listeners: Mutex<Vec<Arc<MemoryEventListener>>>,
+    /// Runtime measured from the interrupt handler; null until a context exists.
+    runtime: AtomicPtr<qjs::JSRuntime>,
+}
+
+/// Tracks the soft memory limit of one runtime and fans out memory events.
+#[derive(Clone, Default)]
+pub(crate) struct MemoryMonitor {
+    inner: Arc<MonitorState>,
+}
+
+impl MemoryMonitor {
+    pub(crate) fn soft_limit(&self) -> Option<usize> {
+        match self.inner.soft_limit.load(Ordering::Acquire) {
+            0 => None,
+            limit => Some(limit),
+        }
+    }
+
+    pub(crate) fn set_soft_limit(&self, limit: Option<usize>) {
+        self.inner
+            .soft_limit
+            .store(limit.unwrap_or(0), Ordering::Release);
+        self.inner.above_soft_limit.store(false, Ordering::Release);
+    }
+
+    pub(crate) fn add_listener(&self, listener: Arc<MemoryEventListener>) {
+        self.inner
+            .listeners
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(listener);
+    }
+
+    /// Delivers `event` to every listener and drops the ones that closed.
+    ///
+    /// Listeners are called without holding the listener lock, so a listener
+    /// may register another one or emit in turn.
+    pub(crate) fn emit(&self, event: JsMemoryEvent) {
+        let listeners = self
+            .inner
+            .listeners
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        let closed: Vec<_> = listeners
+            .into_iter()
+            .filter(|listener| !listener(event.clone()))
+            .collect();
+        if !closed.is_empty() {
+            self.inner
+                .listeners
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .retain(|listener| !closed.iter().any(|closed| Arc::ptr_eq(closed, listener)));
+        }
+    }
+
+    /// Records the runtime that [`MemoryMonitor::poll`] measures.
+    pub(crate) fn attach(&self, runtime: NonNull<qjs::JSRuntime>) {
+        self.inner
+            .runtime
+            .store(runtime.as_ptr(), Ordering::Release);
+    }
+
+    /// Builds an event for `kind` with the current soft limit filled in.
+    pub(crate) fn event(
+        &self,
+        kind: JsMemoryEventKind,
+        usage_before: i64,
+        usage_after: i64,
+    ) -> JsMemoryEvent {
+        JsMemoryEvent {
+            kind,
+            usage_before,
+            usage_after,
+            soft_limit: self.soft_limit().map(|limit| limit as u64),
+        }
+    }
+
+    /// Claims the next check, or returns `false` if one ran too recently.
+    fn claim_check(&self) -> bool {
+        // `try_lock` keeps the interrupt handler from ever waiting on the
+        // driver; a contended check is simply skipped.
+        let Ok(mut last_check) = self.inner.last_check.try_lock() else {
+            return false;
+        };
+        if last_check.is_some_and(|checked| checked.elapsed() < MIN_CHECK_INTERVAL) {
+            return false;
+        }
+        *last_check = Some(Instant::now());
+        true
+    }
+
+    /// Compares allocator usage against the soft limit.
+    ///
+    /// Crossing the limit runs a GC pass and emits
+    /// [`JsMemoryEventKind::SoftLimitExceeded`] once; dropping back below it
+    /// emits [`JsMemoryEventKind::SoftLimitRecovered`]. The driver calls this
+    /// when it goes idle; [`MemoryMonitor::poll`] covers running code.
+    pub(crate) async fn check(&self, runtime: &rquickjs::AsyncRuntime, metrics: &RuntimeMetrics) {
+        let Some(limit) = self.soft_limit() else {
+            return;
+        };
+        if !self.claim_check() {
+            return;
+        }
+        let before = runtime.memory_usage().await.malloc_size;
+        if !self.crossed(limit as i64, before) {
+            return;
+        }
+        let started = Instant::now();
+        runtime.run_gc().await;
+        metrics.record_gc(started.elapsed());
+        let after = runtime.memory_usage().await.malloc_size;
+        self.exceeded(limit as i64, before, after);
+    }
+
+    /// Checks the soft limit from the interrupt handler while JavaScript runs.
+    ///
+    /// QuickJS polls the interrupt handler every few thousand bytecode
+    /// operations, so a long-running script or allocation loop crosses the
+    /// limit without waiting for the driver to go idle.
+    pub(crate) fn poll(&self, metrics: &RuntimeMetrics) {
+        let Some(limit) = self.soft_limit() else {
+            return;
+        };
+        let Some(runtime) = NonNull::new(self.inner.runtime.load(Ordering::Acquire)) else {
+            return;
+        };
+        if !self.claim_check() {
+            return;
+        }
+        // SAFETY: the interrupt handler runs on the JavaScript thread with the
+        // runtime lock held, and `runtime` was recorded from a context of the
+        // runtime this handler is installed on, so it is live.
+        let before = unsafe { malloc_size(runtime) };
+        if !self.crossed(limit as i64, before) {
+            return;
+        }
+        let started = Instant::now();
+        // SAFETY: as above. QuickJS itself collects cycles from inside running
+        // code when an allocation crosses its GC threshold, so a collection
+        // between two bytecode operations is safe.
+        unsafe { qjs::JS_RunGC(runtime.as_ptr()) };
+        metrics.record_gc(started.elapsed());
+        // SAFETY: as above.
+        let after = unsafe { malloc_size(runtime) };
+        self.exceeded(limit as i64, before, after);
+    }
+
+    /// Handles usage below the limit and returns whether `usage` is a new
+    /// crossing that needs a GC pass.
+    fn crossed(&self, limit: i64, usage: i64) -> bool {
+        if usage <= limit {
+            if self.inner.above_soft_limit.swap(false, Ordering::AcqRel) {
+                self.emit(self.event(JsMemoryEventKind::SoftLimitRecovered, usage, usage));
+            }
+            return false;
+        }
+        !self.inner.above_soft_limit.load(Ordering::Acquire)
+    }
+
+    fn exceeded(&self, limit: i64, before: i64, after: i64) {
+        self.inner
+            .above_soft_limit
+            .store(after > limit, Ordering::Release);
+        self.emit(self.event(JsMemoryEventKind::SoftLimitExceeded, before, after));
+    }
+}
+
+/// Reads the allocator usage of `runtime`.
+///
+/// # Safety
+///
+/// `runtime` must be live and locked by the calling thread.
+unsafe fn malloc_size(runtime: NonNull<qjs::JSRuntime>) -> i64 {
+    let mut usage = std::mem::MaybeUninit::<qjs::JSMemoryUsage>::uninit();
+    // SAFETY: the caller guarantees `runtime` is live and locked; QuickJS
+    // fills every field of `usage`.
+    unsafe {
+        qjs::JS_ComputeMemoryUsage(runtime.as_ptr(), usage.as_mut_ptr());
+        usage.assume_init().malloc_size
+    }
+}
@@ -0,0 +1,213 @@
+use crate::api::engine::{JsEngineMetrics, JsHistogram, JsHistogramBucket};
+use rquickjs::JsLifetime;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Upper bounds, in microseconds, of the latency histogram buckets.
+const LATENCY_BOUNDS_US: &[u64] = &[
+    50,
+    100,
+    250,
+    500,
+    1_000,
+    2_500,
+    5_000,
+    10_000,
+    25_000,
+    50_000,
+    100_000,
+    250_000,
+    1_000_000,
+    u64::MAX,
+];
+/// Upper bounds of the job-burst histogram buckets.
+const JOB_BURST_BOUNDS: &[u64] = &[1, 2, 4, 8, 16, 32, 64, 128, 256, 1_024, u64::MAX];
+
+struct Histogram {
+    bounds: &'static [u64],
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [u64]) -> Self {
+        Self {
+            bounds,
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, value: u64) {
+        let index = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len() - 1);
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.max.fetch_max(value, Ordering::Relaxed);
+    }
+
+    fn record_duration(&self, elapsed: Duration) {
+        self.record(u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX));
+    }
+
+    fn snapshot(&self) -> JsHistogram {
+        JsHistogram {
+            count: self.count.load(Ordering::Relaxed),
+            sum: self.sum.load(Ordering::Relaxed),
+            max: self.max.load(Ordering::Relaxed),
+            buckets: self
+                .bounds
+                .iter()
+                .zip(&self.buckets)
+                .map(|(bound, count)| JsHistogramBucket {
+                    upper_bound: *bound,
+                    count: count.load(Ordering::Relaxed),
+                })
+                .collect(),
+        }
+    }
+}
+
+struct MetricsState {
+    created: Instant,
+    eval: Histogram,
+    call: Histogram,
+    bridge_call: Histogram,
+    module_load: Histogram,
+    gc: Histogram,
+    job_burst: Histogram,
+    driver_iterations: AtomicU64,
+    jobs_executed: AtomicU64,
+}
+
+/// Counters and histograms collected for one runtime.
+///
+/// Cloning is cheap; every clone records into the same counters.
+#[derive(Clone)]
+pub(crate) struct RuntimeMetrics {
+    inner: Arc<MetricsState>,
+}
+
+impl Default for RuntimeMetrics {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(MetricsState {
+                created: Instant::now(),
+                eval: Histogram::new(LATENCY_BOUNDS_US),
+                call: Histogram::new(LATENCY_BOUNDS_US),
+                bridge_call: Histogram::new(LATENCY_BOUNDS_US),
+                module_load: Histogram::new(LATENCY_BOUNDS_US),
+                gc: Histogram::new(LATENCY_BOUNDS_US),
+                job_burst: Histogram::new(JOB_BURST_BOUNDS),
+                driver_iterations: AtomicU64::new(0),
+                jobs_executed: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+// SAFETY: `RuntimeMetrics` owns only Rust-side atomics and contains no
+// context-bound JavaScript handles, so it is valid for every JS lifetime.
+unsafe impl<'js> JsLifetime<'js> for RuntimeMetrics {
+    type Changed<'to> = RuntimeMetrics;
+}
+
+impl RuntimeMetrics {
+    pub(crate) fn record_eval(&self, elapsed: Duration) {
+        self.inner.eval.record_duration(elapsed);
+    }
+
+    pub(crate) fn record_call(&self, elapsed: Duration) {
+        self.inner.call.record_duration(elapsed);
+    }
+
+    pub(crate) fn record_bridge_call(&self, elapsed: Duration) {
+        self.inner.bridge_call.record_duration(elapsed);
+    }
+
+    pub(crate) fn record_module_load(&self, elapsed: Duration) {
+        self.inner.module_load.record_duration(elapsed);
+    }
+
+    pub(crate) fn record_gc(&self, elapsed: Duration) {
+        self.inner.gc.record_duration(elapsed);
+    }
+
+    /// Records one driver wake that ran `jobs` jobs before the queue emptied.
+    pub(crate) fn record_driver_iteration(&self, jobs: u64) {
+        self.inner.driver_iterations.fetch_add(1, Ordering::Relaxed);
+        if jobs > 0 {
+            self.inner.jobs_executed.fetch_add(jobs, Ordering::Relaxed);
+            self.inner.job_burst.record(jobs);
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> JsEngineMetrics {
+        let inner = &self.inner;
+        JsEngineMetrics {
+            uptime_ms: u64::try_from(inner.created.elapsed().as_millis()).unwrap_or(u64::MAX),
+            eval_latency_us: inner.eval.snapshot(),
+            call_latency_us: inner.call.snapshot(),
+            bridge_call_latency_us: inner.bridge_call.snapshot(),
+            module_load_latency_us: inner.module_load.snapshot(),
+            gc_time_us: inner.gc.snapshot(),
+            driver_iterations: inner.driver_iterations.load(Ordering::Relaxed),
+            jobs_executed: inner.jobs_executed.load(Ordering::Relaxed),
+            jobs_per_drain: inner.job_burst.snapshot(),
+        }
+    }
+}
+
+/// Operations that emit a `tracing` span when the `tracing` feature is on.
+#[derive(Clone, Copy)]
+pub(crate) enum SpanKind {
+    Foreground,
+    DriverStep,
+    ModuleLoad,
+}
+
+#[cfg(feature = "tracing")]
+fn span(kind: SpanKind, name: &str) -> tracing::Span {
+    match kind {
+        SpanKind::Foreground => tracing::debug_span!("fjs.foreground"),
+        SpanKind::DriverStep => tracing::trace_span!("fjs.driver_step"),
+        SpanKind::ModuleLoad => tracing::debug_span!("fjs.module_load", module = name),
+    }
+}
+
+/// Runs `future` inside a span of `kind`.
+pub(crate) async fn instrument<F: Future>(kind: SpanKind, future: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument;
+        future.instrument(span(kind, "")).await
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = kind;
+        future.await
+    }
+}
+
+/// Runs `f` inside a span of `kind` labelled with `name`.
+pub(crate) fn in_span<R>(kind: SpanKind, name: &str, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "tracing")]
+    {
+        span(kind, name).in_scope(f)
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (kind, name);
+        f()
+    }
+}
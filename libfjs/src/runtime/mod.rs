@@ -1,7 +1,20 @@
+pub(crate) mod async_stack;
+pub(crate) mod bindings;
+pub(crate) mod bridge_limits;
+pub(crate) mod clock;
+pub(crate) mod compartment;
+pub(crate) mod coverage;
 pub(crate) mod driver;
 pub(crate) mod error_sink;
 pub(crate) mod executor;
 pub(crate) mod job_error;
+pub(crate) mod memory;
+pub(crate) mod metrics;
+pub(crate) mod module_cache;
+pub(crate) mod profiler;
+pub(crate) mod promise_hook;
+pub(crate) mod recorder;
 pub(crate) mod shutdown;
 pub(crate) mod stack;
 pub(crate) mod teardown;
+pub(crate) mod trace;
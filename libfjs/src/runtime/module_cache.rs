@@ -0,0 +1,230 @@
+use crate::api::engine::{JsModuleCache, JsModuleCacheStore};
+use crate::api::source::{JsBytecodeEndianness, JsModuleBytecodeOptions};
+use crate::bytecode_bundle::quickjs_version;
+use crate::bytecode_support::{bytecode_format_version, load_module_bytecode_checked};
+use crate::runtime::coverage::{coverage_enabled, instrument_module_source};
+use rquickjs::{CatchResultExt, Ctx, JsLifetime, Module};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+const HASH_LEN: usize = 32;
+const FILE_EXTENSION: &str = "qjsc";
+
+/// Revision of rquickjs the engine is built against; keep in sync with Cargo.toml.
+///
+/// rquickjs patches the QuickJS sources it bundles, so bytecode written by one
+/// revision is not guaranteed to load in another even when the QuickJS
+/// version string matches.
+const RQUICKJS_REV: &str = "04e2734";
+
+/// Compiled-module cache shared by every context of an engine.
+///
+/// Entries are stored per module name as `key ‖ SHA-256(payload) ‖ payload`.
+/// The key covers the module name, its original source, whether coverage
+/// probes were compiled in, the QuickJS version, the rquickjs revision, and
+/// the bytecode format version. A lookup whose key
+/// differs finds a stale entry, and a payload whose hash does not match is
+/// corrupted; either way the entry is dropped and the lookup reports a miss,
+/// so stale or damaged bytecode never loads.
+#[derive(Clone)]
+pub(crate) struct ModuleBytecodeCache {
+    store: Arc<dyn JsModuleCacheStore>,
+}
+
+// SAFETY: The cache owns only a thread-safe store of Rust-side buffers and
+// contains no context-bound JavaScript handles, so it is valid for every JS lifetime.
+unsafe impl<'js> JsLifetime<'js> for ModuleBytecodeCache {
+    type Changed<'to> = ModuleBytecodeCache;
+}
+
+fn sha256(parts: &[&[u8]]) -> [u8; HASH_LEN] {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    for part in parts {
+        context.update(&(part.len() as u64).to_le_bytes());
+        context.update(part);
+    }
+    let mut hash = [0; HASH_LEN];
+    hash.copy_from_slice(context.finish().as_ref());
+    hash
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Keeps compiled modules in memory for the lifetime of the engine.
+#[derive(Default)]
+struct MemoryStore(Mutex<HashMap<String, Vec<u8>>>);
+
+impl JsModuleCacheStore for MemoryStore {
+    fn get(&self, id: &str) -> Option<Vec<u8>> {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(id)
+            .cloned()
+    }
+
+    fn put(&self, id: &str, entry: Vec<u8>) {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(id.to_string(), entry);
+    }
+
+    fn remove(&self, id: &str) {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(id);
+    }
+
+    fn trim(&self) {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// Persists compiled modules as one file per module in a directory.
+struct DirectoryStore(PathBuf);
+
+impl DirectoryStore {
+    fn entry_path(&self, id: &str) -> PathBuf {
+        self.0.join(format!("{id}.{FILE_EXTENSION}"))
+    }
+}
+
+impl JsModuleCacheStore for DirectoryStore {
+    fn get(&self, id: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.entry_path(id)).ok()
+    }
+
+    fn put(&self, id: &str, entry: Vec<u8>) {
+        // Cache writes are best effort: a failure only costs a recompile.
+        if std::fs::create_dir_all(&self.0).is_err() {
+            return;
+        }
+        let path = self.entry_path(id);
+        let temp = path.with_extension(format!("{FILE_EXTENSION}.{}", std::process::id()));
+        if std::fs::write(&temp, entry).is_ok() && std::fs::rename(&temp, &path).is_err() {
+            let _ = std::fs::remove_file(&temp);
+        }
+    }
+
+    fn remove(&self, id: &str) {
+        let _ = std::fs::remove_file(self.entry_path(id));
+    }
+}
+
+impl ModuleBytecodeCache {
+    pub(crate) fn new(config: JsModuleCache) -> Self {
+        match config {
+            JsModuleCache::Memory => Self::from_store(Arc::new(MemoryStore::default())),
+            JsModuleCache::Directory(path) => {
+                Self::from_store(Arc::new(DirectoryStore(PathBuf::from(path))))
+            }
+        }
+    }
+
+    pub(crate) fn from_store(store: Arc<dyn JsModuleCacheStore>) -> Self {
+        Self { store }
+    }
+
+    fn key(ctx: &Ctx<'_>, module_name: &str, source: &[u8]) -> [u8; HASH_LEN] {
+        let format_version = bytecode_format_version(ctx).map_or(Vec::new(), |v| vec![v]);
+        sha256(&[
+            quickjs_version().as_bytes(),
+            RQUICKJS_REV.as_bytes(),
+            &format_version,
+            &[u8::from(coverage_enabled(ctx))],
+            module_name.as_bytes(),
+            source,
+        ])
+    }
+
+    fn entry_id(module_name: &str) -> String {
+        hex(&sha256(&[module_name.as_bytes()]))
+    }
+
+    fn get(&self, module_name: &str, key: &[u8; HASH_LEN]) -> Option<Vec<u8>> {
+        let id = Self::entry_id(module_name);
+        let entry = self.store.get(&id)?;
+        let valid = entry.len() > 2 * HASH_LEN
+            && entry[..HASH_LEN] == key[..]
+            && entry[HASH_LEN..2 * HASH_LEN] == sha256(&[&entry[2 * HASH_LEN..]])[..];
+        if valid {
+            Some(entry[2 * HASH_LEN..].to_vec())
+        } else {
+            self.store.remove(&id);
+            None
+        }
+    }
+
+    fn put(&self, module_name: &str, key: [u8; HASH_LEN], bytes: Vec<u8>) {
+        let mut entry = Vec::with_capacity(2 * HASH_LEN + bytes.len());
+        entry.extend_from_slice(&key);
+        entry.extend_from_slice(&sha256(&[&bytes]));
+        entry.extend_from_slice(&bytes);
+        self.store.put(&Self::entry_id(module_name), entry);
+    }
+
+    /// Asks the store to release what it holds in memory.
+    pub(crate) fn trim(&self) {
+        self.store.trim();
+    }
+
+    fn remove(&self, module_name: &str) {
+        self.store.remove(&Self::entry_id(module_name));
+    }
+}
+
+/// Declares a source module, reusing cached bytecode when it is still valid.
+///
+/// On a miss the module is parsed from source and its bytecode is written to
+/// the cache for the next run. Cached bytecode that fails to load is dropped
+/// and the module is compiled from source instead.
+///
+/// Coverage instrumentation happens here, after the key is computed from the
+/// original source. It also runs on a hit: cached instrumented bytecode
+/// reads counters that only instrumenting registers. `trailer` is appended
+/// after instrumentation, so it is never counted, and is part of the key.
+pub(crate) fn declare_cached_module<'js>(
+    ctx: &Ctx<'js>,
+    cache: &ModuleBytecodeCache,
+    module_name: &str,
+    mut source: Vec<u8>,
+    trailer: &[u8],
+) -> rquickjs::Result<Module<'js, rquickjs::module::Declared>> {
+    let original = source.len();
+    source.extend_from_slice(trailer);
+    let key = ModuleBytecodeCache::key(ctx, module_name, &source);
+    source.truncate(original);
+    let mut source = instrument_module_source(ctx, module_name, source);
+    source.extend_from_slice(trailer);
+    if let Some(bytes) = cache.get(module_name, &key) {
+        match load_module_bytecode_checked(ctx.clone(), module_name, &bytes).catch(ctx) {
+            Ok(module)
+                if module
+                    .name::<String>()
+                    .is_ok_and(|name| name == module_name) =>
+            {
+                return Ok(module);
+            }
+            _ => cache.remove(module_name),
+        }
+    }
+
+    let module = Module::declare(ctx.clone(), module_name, source)?;
+    let options = JsModuleBytecodeOptions {
+        endianness: Some(JsBytecodeEndianness::Native),
+        strip_source: Some(false),
+        strip_debug: Some(false),
+    };
+    if let Ok(bytes) = module.write(options.into()).catch(ctx) {
+        cache.put(module_name, key, bytes);
+    }
+    Ok(module)
+}
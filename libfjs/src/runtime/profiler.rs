@@ -0,0 +1,297 @@
+use crate::api::module::dynamic_module_public_name;
+use crate::api::profiler::{JsCpuProfile, JsProfileNode};
+use rquickjs::qjs;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Stops recording new samples once this many have been collected.
+const MAX_SAMPLES: usize = 1_000_000;
+/// Deepest stack level inspected per sample.
+const MAX_FRAMES: i32 = 128;
+
+/// One frame of a sampled stack.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Frame {
+    function: String,
+    url: String,
+    line: i32,
+    column: i32,
+}
+
+struct Sample {
+    at: Duration,
+    /// Outermost frame first.
+    frames: Vec<Frame>,
+}
+
+struct Session {
+    context: NonNull<qjs::JSContext>,
+    interval: Duration,
+    started: Instant,
+    started_at: SystemTime,
+    last_sample: Instant,
+    samples: Vec<Sample>,
+}
+
+// SAFETY: `context` is only dereferenced by `CpuProfiler::sample`, which runs
+// on the JavaScript thread from the interrupt handler while the runtime lock
+// is held. Other threads only move or drop the pointer.
+unsafe impl Send for Session {}
+
+#[derive(Default)]
+struct ProfilerState {
+    active: AtomicBool,
+    session: Mutex<Option<Session>>,
+}
+
+/// Sampling CPU profiler driven by the runtime's interrupt handler.
+///
+/// QuickJS does not poll the interrupt handler on a timer: it counts down a
+/// budget of executed operations (about ten thousand) and polls when the
+/// budget runs out. A sample is taken at the first poll after the sampling
+/// interval has elapsed, so the effective granularity is the larger of the
+/// interval and the time the interpreter needs for one budget. Time spent
+/// outside JavaScript (awaiting, in native code) is not sampled.
+///
+/// The capture runs inside the interrupt handler, so it must not throw,
+/// evaluate JavaScript, or create objects. QuickJS only exposes the script or
+/// module name of each bytecode frame without allocating
+/// (`JS_GetScriptOrModuleName`), so frames are attributed to the module that
+/// defines the running function rather than to the function itself, and the
+/// walk stops at the first native frame.
+#[derive(Clone, Default)]
+pub(crate) struct CpuProfiler {
+    inner: Arc<ProfilerState>,
+}
+
+impl CpuProfiler {
+    /// Starts a session sampling `context`; returns `false` if one is already
+    /// running.
+    pub(crate) fn start(&self, context: NonNull<qjs::JSContext>, interval: Duration) -> bool {
+        let mut session = self
+            .inner
+            .session
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if session.is_some() {
+            return false;
+        }
+        let now = Instant::now();
+        *session = Some(Session {
+            context,
+            interval,
+            started: now,
+            started_at: SystemTime::now(),
+            last_sample: now,
+            samples: Vec::new(),
+        });
+        self.inner.active.store(true, Ordering::Release);
+        true
+    }
+
+    /// Ends the running session and builds its profile.
+    pub(crate) fn stop(&self) -> Option<JsCpuProfile> {
+        let session = self.take_session()?;
+        Some(build_profile(session))
+    }
+
+    /// Discards the running session if it samples `context`, which is about
+    /// to be freed.
+    pub(crate) fn detach(&self, context: NonNull<qjs::JSContext>) {
+        let mut session = self
+            .inner
+            .session
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if session
+            .as_ref()
+            .is_some_and(|session| session.context == context)
+        {
+            self.inner.active.store(false, Ordering::Release);
+            *session = None;
+        }
+    }
+
+    /// Discards the running session, if any.
+    pub(crate) fn discard(&self) {
+        let _ = self.take_session();
+    }
+
+    fn take_session(&self) -> Option<Session> {
+        let mut session = self
+            .inner
+            .session
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        self.inner.active.store(false, Ordering::Release);
+        session.take()
+    }
+
+    /// Records a sample if the interval has elapsed. Called from the
+    /// interrupt handler.
+    pub(crate) fn sample(&self) {
+        if !self.inner.active.load(Ordering::Acquire) {
+            return;
+        }
+        let mut session = self
+            .inner
+            .session
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(session) = session.as_mut()
+            && session.samples.len() < MAX_SAMPLES
+            && session.last_sample.elapsed() >= session.interval
+        {
+            // SAFETY: the interrupt handler runs on the JavaScript thread
+            // with the runtime lock held, and `detach` removes the session
+            // before its context is freed, so `session.context` is live.
+            if let Some(frames) = unsafe { capture_frames(session.context) } {
+                let now = Instant::now();
+                session.last_sample = now;
+                session.samples.push(Sample {
+                    at: now - session.started,
+                    frames,
+                });
+            }
+        }
+    }
+}
+
+/// Captures the script or module of each running bytecode frame, outermost
+/// first. Consecutive frames from the same module are merged.
+///
+/// # Safety
+///
+/// `context` must be a live context of the runtime whose lock is held by the
+/// calling thread.
+unsafe fn capture_frames(context: NonNull<qjs::JSContext>) -> Option<Vec<Frame>> {
+    let ctx = context.as_ptr();
+    let mut frames: Vec<Frame> = Vec::new();
+    // SAFETY: the caller guarantees `ctx` is live and locked. Stack levels are
+    // only read, never thrown through; every atom and C string obtained here
+    // is released before the next level is inspected.
+    unsafe {
+        if qjs::JS_HasException(ctx) {
+            return None;
+        }
+        for level in 0..MAX_FRAMES {
+            let atom = qjs::JS_GetScriptOrModuleName(ctx, level);
+            if atom == qjs::JS_ATOM_NULL as qjs::JSAtom {
+                break;
+            }
+            let text = qjs::JS_AtomToCString(ctx, atom);
+            qjs::JS_FreeAtom(ctx, atom);
+            if text.is_null() {
+                qjs::JS_FreeValue(ctx, qjs::JS_GetException(ctx));
+                break;
+            }
+            let name = CStr::from_ptr(text).to_string_lossy();
+            let name = dynamic_module_public_name(&name);
+            if frames.last().is_none_or(|frame| frame.url != name) {
+                frames.push(module_frame(name));
+            }
+            qjs::JS_FreeCString(ctx, text);
+        }
+    }
+    frames.reverse();
+    Some(frames)
+}
+
+/// Builds the frame recorded for code running in module `name`.
+fn module_frame(name: &str) -> Frame {
+    Frame {
+        function: match name {
+            "" => "(anonymous)".to_string(),
+            name => name.to_string(),
+        },
+        url: name.to_string(),
+        line: -1,
+        column: -1,
+    }
+}
+
+fn build_profile(session: Session) -> JsCpuProfile {
+    let mut nodes = vec![JsProfileNode {
+        id: 1,
+        parent_id: None,
+        function_name: "(root)".to_string(),
+        url: String::new(),
+        line_number: -1,
+        column_number: -1,
+        hit_count: 0,
+    }];
+    let mut children: HashMap<(u32, Frame), u32> = HashMap::new();
+    let program = Frame {
+        function: "(program)".to_string(),
+        url: String::new(),
+        line: -1,
+        column: -1,
+    };
+
+    let mut samples = Vec::with_capacity(session.samples.len());
+    let mut time_deltas_us = Vec::with_capacity(session.samples.len());
+    let mut previous = Duration::ZERO;
+    for sample in &session.samples {
+        let frames = if sample.frames.is_empty() {
+            std::slice::from_ref(&program)
+        } else {
+            sample.frames.as_slice()
+        };
+        let mut parent = 1;
+        for frame in frames {
+            parent = *children.entry((parent, frame.clone())).or_insert_with(|| {
+                let id = nodes.len() as u32 + 1;
+                nodes.push(JsProfileNode {
+                    id,
+                    parent_id: Some(parent),
+                    function_name: frame.function.clone(),
+                    url: frame.url.clone(),
+                    line_number: frame.line,
+                    column_number: frame.column,
+                    hit_count: 0,
+                });
+                id
+            });
+        }
+        nodes[parent as usize - 1].hit_count += 1;
+        samples.push(parent);
+        time_deltas_us.push(micros(sample.at - previous));
+        previous = sample.at;
+    }
+
+    let start_time_us = micros(
+        session
+            .started_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default(),
+    );
+    JsCpuProfile {
+        nodes,
+        samples,
+        time_deltas_us,
+        start_time_us,
+        end_time_us: start_time_us.saturating_add(micros(session.started.elapsed())),
+    }
+}
+
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_frames_use_public_module_names() {
+        let frame = module_frame(dynamic_module_public_name("plugin.js?fjs-reload=2"));
+        assert_eq!(frame.function, "plugin.js");
+        assert_eq!(frame.url, "plugin.js");
+        assert_eq!((frame.line, frame.column), (-1, -1));
+        assert_eq!(module_frame("").function, "(anonymous)");
+    }
+}
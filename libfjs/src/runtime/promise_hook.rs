@@ -0,0 +1,12 @@
+use rquickjs::runtime::PromiseHook;
+
+/// Runtime-wide promise hook shared by trace contexts and async stacks.
+///
+/// A runtime has a single hook slot, so each feature checks whether it is
+/// installed in the event's context and ignores the event otherwise.
+pub(crate) fn promise_hook() -> PromiseHook {
+    Box::new(|ctx, kind, promise, _parent| {
+        crate::runtime::trace::on_promise(&ctx, kind, &promise);
+        crate::runtime::async_stack::on_promise(&ctx, kind, &promise);
+    })
+}
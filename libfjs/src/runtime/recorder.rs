@@ -0,0 +1,196 @@
+use crate::api::engine::BridgeCallback;
+use crate::api::error::{JsError, JsResult};
+use crate::api::recording::{JsRecordedEvent, JsRecordedOutcome, JsRecording};
+use crate::api::source::JsCode;
+use crate::api::value::JsValue;
+use flutter_rust_bridge::DartFnFuture;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Captures engine inputs and bridge traffic while a recording is active.
+#[derive(Clone, Default)]
+pub(crate) struct Recorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    generation: u64,
+    active: Option<ActiveRecording>,
+}
+
+/// A running recording. Events get their slot when the operation starts, so
+/// the recording is in start order even though results arrive out of order.
+struct ActiveRecording {
+    started: Instant,
+    slots: Vec<Option<JsRecordedEvent>>,
+}
+
+/// Place reserved for an operation that started during a recording.
+pub(crate) struct RecordingSlot {
+    generation: u64,
+    index: usize,
+    /// Milliseconds from the start of the recording.
+    pub(crate) at_ms: u64,
+}
+
+impl Recorder {
+    fn lock(&self) -> std::sync::MutexGuard<'_, RecorderState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Starts a recording; returns `false` if one is already running.
+    pub(crate) fn start(&self) -> bool {
+        let mut state = self.lock();
+        if state.active.is_some() {
+            return false;
+        }
+        state.generation += 1;
+        state.active = Some(ActiveRecording {
+            started: Instant::now(),
+            slots: Vec::new(),
+        });
+        true
+    }
+
+    /// Ends the running recording, if any. Operations still in flight are
+    /// left out.
+    pub(crate) fn stop(&self) -> Option<JsRecording> {
+        self.lock().active.take().map(|recording| JsRecording {
+            events: recording.slots.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Reserves a slot for an operation starting now, or returns `None` when
+    /// nothing is being recorded.
+    pub(crate) fn reserve(&self) -> Option<RecordingSlot> {
+        let mut state = self.lock();
+        let generation = state.generation;
+        let recording = state.active.as_mut()?;
+        recording.slots.push(None);
+        Some(RecordingSlot {
+            generation,
+            index: recording.slots.len() - 1,
+            at_ms: recording.started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Fills `slot` with the finished operation, if its recording is still
+    /// running.
+    pub(crate) fn fill(&self, slot: RecordingSlot, event: JsRecordedEvent) {
+        let mut state = self.lock();
+        if state.generation != slot.generation {
+            return;
+        }
+        if let Some(recording) = state.active.as_mut() {
+            recording.slots[slot.index] = Some(event);
+        }
+    }
+
+    /// Wraps `bridge` so its traffic is captured while recording.
+    ///
+    /// Trace contexts are not recorded: they differ between runs, and replay
+    /// matches calls by argument alone.
+    pub(crate) fn wrap(&self, bridge: Arc<BridgeCallback>) -> Arc<BridgeCallback> {
+        let recorder = self.clone();
+        Arc::new(
+            move |argument: JsValue, trace: Option<JsValue>| -> DartFnFuture<JsResult> {
+                let bridge = bridge.clone();
+                let recorder = recorder.clone();
+                Box::pin(async move {
+                    let Some(slot) = recorder.reserve() else {
+                        return bridge(argument, trace).await;
+                    };
+                    let started = Instant::now();
+                    let result = bridge(argument.clone(), trace).await;
+                    let at_ms = slot.at_ms;
+                    recorder.fill(
+                        slot,
+                        JsRecordedEvent::BridgeCall {
+                            at_ms,
+                            duration_us: started.elapsed().as_micros() as u64,
+                            argument,
+                            outcome: outcome_of_result(&result),
+                        },
+                    );
+                    result
+                })
+            },
+        )
+    }
+}
+
+/// Converts an operation result to its recorded form.
+pub(crate) fn outcome_of(result: &Result<JsValue, JsError>) -> JsRecordedOutcome {
+    match result {
+        Ok(value) => JsRecordedOutcome::Ok(value.clone()),
+        Err(error) => JsRecordedOutcome::Err(error.clone()),
+    }
+}
+
+/// Converts a `callBatch()` result to its recorded form.
+pub(crate) fn outcome_of_batch(result: &Result<Vec<JsResult>, JsError>) -> JsRecordedOutcome {
+    match result {
+        Ok(results) => JsRecordedOutcome::Batch(results.iter().map(outcome_of_result).collect()),
+        Err(error) => JsRecordedOutcome::Err(error.clone()),
+    }
+}
+
+fn outcome_of_result(result: &JsResult) -> JsRecordedOutcome {
+    match result {
+        JsResult::Ok(value) => JsRecordedOutcome::Ok(value.clone()),
+        JsResult::Err(error) => JsRecordedOutcome::Err(error.clone()),
+    }
+}
+
+/// Builds a bridge that answers from the bridge calls in `recording`.
+///
+/// Each call consumes the earliest unused recorded call with an equal
+/// argument, so replies still match when concurrent calls start in a
+/// different order. Recorded errors are returned as the same `JsError`.
+pub(crate) fn replay_bridge(recording: &JsRecording) -> Arc<BridgeCallback> {
+    let responses: Vec<(JsValue, JsRecordedOutcome)> = recording
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            JsRecordedEvent::BridgeCall {
+                argument, outcome, ..
+            } => Some((argument.clone(), outcome.clone())),
+            _ => None,
+        })
+        .collect();
+    let responses = Arc::new(Mutex::new(responses));
+    Arc::new(
+        move |argument: JsValue, _: Option<JsValue>| -> DartFnFuture<JsResult> {
+            let mut responses = responses
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let result = match responses
+                .iter()
+                .position(|(recorded, _)| *recorded == argument)
+            {
+                Some(index) => match responses.remove(index).1 {
+                    JsRecordedOutcome::Ok(value) => JsResult::Ok(value),
+                    JsRecordedOutcome::Err(error) => JsResult::Err(error),
+                    JsRecordedOutcome::Batch(_) => JsResult::Err(JsError::bridge(
+                        "Recorded bridge response is a batch outcome",
+                    )),
+                },
+                None => JsResult::Err(JsError::bridge(format!(
+                    "No recorded bridge response for argument {argument:?}"
+                ))),
+            };
+            Box::pin(async move { result })
+        },
+    )
+}
+
+/// Keeps source read for a recorded operation, as text when it is UTF-8.
+pub(crate) fn recorded_source(source: &[u8]) -> JsCode {
+    match std::str::from_utf8(source) {
+        Ok(code) => JsCode::Code(code.to_string()),
+        Err(_) => JsCode::Bytes(source.to_vec()),
+    }
+}
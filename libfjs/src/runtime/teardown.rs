@@ -4,6 +4,7 @@ pub(crate) async fn cleanup_async_engine_gracefully(
     context: &JsAsyncContext,
     runtime: &JsAsyncRuntime,
 ) {
+    runtime.driver.profiler().discard();
     runtime.stop_driver().await;
 
     let _ = context
@@ -25,6 +26,7 @@ pub(crate) async fn cleanup_async_engine_immediately(
     runtime: &JsAsyncRuntime,
 ) {
     runtime.request_shutdown();
+    runtime.driver.profiler().discard();
     runtime.stop_driver().await;
 
     let _ = context
@@ -0,0 +1,158 @@
+//! Trace context carried through a JavaScript async chain.
+//!
+//! A trace context is an opaque map from Dart. It is the "current" context
+//! while the code it was passed with runs, and a QuickJS promise hook copies
+//! it to every promise created meanwhile, so reactions and `await`
+//! continuations run with it too. Timer and microtask callbacks capture the
+//! context they were scheduled under. JavaScript reads it with
+//! `traceContext.getStore()` and can switch it with `traceContext.run()`,
+//! mirroring Node's `AsyncLocalStorage`.
+//!
+//! This does not build on `llrt_async_hooks`. As far as we know, that crate
+//! provides `createHook()`, `executionAsyncId()`, and `triggerAsyncId()`
+//! but no `AsyncLocalStorage`. Its async IDs are only tracked when its own
+//! promise hook is installed on the runtime. QuickJS has a single promise
+//! hook per runtime, and the engine's hook already dispatches to the trace
+//! and async-stack trackers.
+
+use crate::api::error::JsError;
+use crate::api::value::JsValue;
+use rquickjs::runtime::PromiseHookType;
+use rquickjs::{CatchResultExt, Ctx, FromJs, Function, IntoJs, Object, Value};
+use std::collections::HashMap;
+
+/// Global holding the hook and entry points used from Rust.
+const TRACE_GLOBAL: &str = "__fjs_trace__";
+
+const INSTALL_TRACE: &str = r#"
+(() => {
+  let current;
+  const traces = new WeakMap();
+  const saved = [];
+  const run = (store, fn, thisArg, args) => {
+    const previous = current;
+    current = store;
+    try {
+      return fn.apply(thisArg, args);
+    } finally {
+      current = previous;
+    }
+  };
+  const bind = (fn) => {
+    if (typeof fn !== "function" || current === undefined) return fn;
+    const store = current;
+    return function(...args) {
+      return run(store, fn, this, args);
+    };
+  };
+  for (const name of ["setTimeout", "setInterval", "setImmediate", "queueMicrotask"]) {
+    const native = globalThis[name];
+    if (typeof native === "function") {
+      globalThis[name] = function(callback, ...rest) {
+        return native.call(this, bind(callback), ...rest);
+      };
+    }
+  }
+
+  Object.defineProperty(globalThis, "traceContext", {
+    value: Object.freeze({
+      getStore: () => current,
+      run: (store, fn, ...args) => run(store, fn, undefined, args),
+    }),
+    writable: true,
+    configurable: true,
+  });
+  Object.defineProperty(globalThis, "__fjs_trace__", {
+    value: Object.freeze({
+      hook(type, promise) {
+        if (type === "init") {
+          if (current !== undefined) traces.set(promise, current);
+        } else if (type === "before") {
+          saved.push(current);
+          current = traces.get(promise);
+        } else if (type === "after") {
+          current = saved.pop();
+        }
+      },
+      enter(store) {
+        const previous = current;
+        current = Object.freeze(store);
+        return previous;
+      },
+      exit(previous) {
+        current = previous;
+      },
+      current: () => current,
+    }),
+  });
+})();
+"#;
+
+/// Installs `traceContext` and the trace entry points in `ctx`.
+pub(crate) fn install(ctx: &Ctx<'_>) -> Result<(), JsError> {
+    ctx.eval::<(), _>(INSTALL_TRACE)
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to install trace context: {e}")))
+}
+
+/// Forwards a promise event to the context's trace hook, if installed.
+pub(crate) fn on_promise<'js>(ctx: &Ctx<'js>, kind: PromiseHookType, promise: &Value<'js>) {
+    let kind = match kind {
+        PromiseHookType::Init => "init",
+        PromiseHookType::Before => "before",
+        PromiseHookType::After => "after",
+        PromiseHookType::Resolve => return,
+    };
+    let Ok(controls) = ctx.globals().get::<_, Object>(TRACE_GLOBAL) else {
+        return;
+    };
+    if let Ok(hook) = controls.get::<_, Function>("hook") {
+        let _ = hook.call::<_, ()>((kind, promise.clone())).catch(ctx);
+    }
+}
+
+fn controls<'js>(ctx: &Ctx<'js>) -> Result<Object<'js>, JsError> {
+    ctx.globals()
+        .get::<_, Object>(TRACE_GLOBAL)
+        .map_err(|_| JsError::engine("Trace context is not enabled"))
+}
+
+/// Makes `context` current and returns the previous context for [`exit`].
+pub(crate) fn enter<'js>(
+    ctx: &Ctx<'js>,
+    context: HashMap<String, JsValue>,
+) -> Result<Value<'js>, JsError> {
+    let store = JsValue::Object(context)
+        .into_js(ctx)
+        .catch(ctx)
+        .map_err(|e| JsError::context(format!("Failed to convert trace context: {e}")))?;
+    controls(ctx)?
+        .get::<_, Function>("enter")
+        .and_then(|enter| enter.call((store,)))
+        .catch(ctx)
+        .map_err(|e| JsError::runtime(format!("Failed to enter trace context: {e}")))
+}
+
+/// Restores the context that was current before [`enter`].
+pub(crate) fn exit<'js>(ctx: &Ctx<'js>, previous: Value<'js>) {
+    if let Ok(controls) = controls(ctx)
+        && let Ok(exit) = controls.get::<_, Function>("exit")
+    {
+        let _ = exit.call::<_, ()>((previous,)).catch(ctx);
+    }
+}
+
+/// Returns the current trace context, if tracing is installed and a context
+/// is active.
+pub(crate) fn current(ctx: &Ctx<'_>) -> Option<JsValue> {
+    let current = controls(ctx)
+        .ok()?
+        .get::<_, Function>("current")
+        .ok()?
+        .call::<_, Value>(())
+        .ok()?;
+    if current.is_undefined() {
+        return None;
+    }
+    JsValue::from_js(ctx, current).ok()
+}
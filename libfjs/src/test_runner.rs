@@ -0,0 +1,353 @@
+//! `node:test`-compatible test harness.
+//!
+//! The harness is plain JavaScript evaluated once per context when the
+//! `test` builtin module is first imported. Top-level `test()`/`describe()`
+//! calls register into a root suite; the internal `__fjsRun` export runs
+//! everything registered since the previous run and returns plain result
+//! objects, which [`parse_results`] converts for Dart.
+
+use crate::api::error::JsError;
+use crate::api::testing::{JsTestFailure, JsTestResult, JsTestStatus};
+use crate::api::value::JsValue;
+use rquickjs::module::{Declarations, Exports, ModuleDef};
+use rquickjs::{Ctx, Object, Value};
+use std::collections::HashMap;
+
+/// Name the harness module is registered under; `node:test` resolves to it.
+pub(crate) const TEST_MODULE_NAME: &str = "test";
+/// Export that runs the registered tests.
+pub(crate) const RUN_EXPORT: &str = "__fjsRun";
+
+const EXPORTS: &[&str] = &[
+    "test",
+    "it",
+    "describe",
+    "suite",
+    "before",
+    "after",
+    "beforeEach",
+    "afterEach",
+    RUN_EXPORT,
+];
+
+const HARNESS: &str = r#"
+(() => {
+  const now = () =>
+    typeof performance === "object" && typeof performance.now === "function"
+      ? performance.now()
+      : Date.now();
+  const hasTimers = typeof setTimeout === "function" && typeof clearTimeout === "function";
+
+  const newSuite = (name, options) => ({
+    kind: "suite", name, options, children: [],
+    before: [], after: [], beforeEach: [], afterEach: [], pending: null,
+  });
+  let root = newSuite("", {});
+  let current = root;
+
+  const parseArgs = (args) => {
+    let name;
+    let options = {};
+    let fn;
+    for (const arg of args) {
+      if (typeof arg === "string") name = arg;
+      else if (typeof arg === "function") fn = arg;
+      else if (arg !== null && typeof arg === "object") options = { ...options, ...arg };
+    }
+    if (name === undefined) name = fn && fn.name ? fn.name : "<anonymous>";
+    return { name, options, fn };
+  };
+
+  const withOptions = (register, extra) => (...args) => register(...args, extra);
+  const addModifiers = (register) => {
+    register.skip = withOptions(register, { skip: true });
+    register.todo = withOptions(register, { todo: true });
+    register.only = withOptions(register, { only: true });
+    return register;
+  };
+
+  const test = addModifiers((...args) => {
+    const { name, options, fn } = parseArgs(args);
+    current.children.push({ kind: "test", name, options, fn });
+  });
+  const describe = addModifiers((...args) => {
+    const { name, options, fn } = parseArgs(args);
+    const suite = newSuite(name, options);
+    current.children.push(suite);
+    if (!fn) return;
+    const parent = current;
+    current = suite;
+    try {
+      const result = fn();
+      if (result !== null && typeof result === "object" && typeof result.then === "function") {
+        suite.pending = result;
+      }
+    } catch (error) {
+      suite.pending = Promise.reject(error);
+    } finally {
+      current = parent;
+    }
+  });
+  const hook = (kind) => (fn) => {
+    current[kind].push(fn);
+  };
+
+  const errorInfo = (error) => {
+    if (error !== null && typeof error === "object") {
+      return {
+        name: String(error.name ?? "Error"),
+        message: String(error.message ?? ""),
+        stack: error.stack === undefined ? null : String(error.stack),
+      };
+    }
+    return { name: "Error", message: String(error), stack: null };
+  };
+
+  const invoke = (fn, context, timeout) => new Promise((resolve, reject) => {
+    let settled = false;
+    let timer;
+    const settle = (finish) => (value) => {
+      if (settled) return;
+      settled = true;
+      if (timer !== undefined) clearTimeout(timer);
+      finish(value);
+    };
+    const pass = settle(resolve);
+    const fail = settle(reject);
+    if (hasTimers && typeof timeout === "number" && Number.isFinite(timeout)) {
+      timer = setTimeout(() => fail(new Error(`test timed out after ${timeout}ms`)), timeout);
+    }
+    try {
+      if (fn.length >= 2) {
+        fn(context, (error) => (error == null ? pass() : fail(error)));
+      } else {
+        Promise.resolve(fn(context)).then(() => pass(), fail);
+      }
+    } catch (error) {
+      fail(error);
+    }
+  });
+
+  const runHooks = async (hooks, context) => {
+    for (const fn of hooks) await invoke(fn, context);
+  };
+
+  const report = (results, name, path, status, durationMs, error) => {
+    const result = { name, path, status, durationMs, error: error === undefined ? null : errorInfo(error) };
+    results.push(result);
+    return result;
+  };
+
+  // A test marked `todo`, or inside a `todo` suite, still runs, but its
+  // failure is reported as `todo` rather than `fail`.
+  const runTest = async (entry, path, chain, results) => {
+    const { options } = entry;
+    if (options.skip || !entry.fn) {
+      return report(results, entry.name, path, options.skip ? "skip" : "todo", 0);
+    }
+    const todo = options.todo || chain.some((suite) => suite.options.todo);
+
+    const started = now();
+    const subtests = [];
+    let marked = null;
+    const context = {
+      name: entry.name,
+      skip() { marked = "skip"; },
+      todo() { marked = "todo"; },
+      diagnostic() {},
+      plan() {},
+      test: (...args) => {
+        const { name, options, fn } = parseArgs(args);
+        const inherited = todo ? { todo: true, ...options } : options;
+        const run = runTest({ name, options: inherited, fn }, [...path, entry.name], chain, results);
+        subtests.push(run);
+        return run;
+      },
+    };
+
+    let failure;
+    let failed = false;
+    const attempt = async (step) => {
+      if (failed) return;
+      try {
+        await step();
+      } catch (error) {
+        failed = true;
+        failure = error;
+      }
+    };
+    await attempt(() => runHooks(chain.flatMap((suite) => suite.beforeEach), context));
+    await attempt(() => invoke(entry.fn, context, options.timeout));
+    const outcomes = await Promise.all(subtests);
+    await attempt(async () => {
+      const count = outcomes.filter((outcome) => outcome.status === "fail").length;
+      if (count > 0) throw new Error(`${count} subtest${count === 1 ? "" : "s"} failed`);
+    });
+    const afterEach = chain.flatMap((suite) => suite.afterEach).reverse();
+    try {
+      await runHooks(afterEach, context);
+    } catch (error) {
+      if (!failed) {
+        failed = true;
+        failure = error;
+      }
+    }
+
+    const status = todo || marked === "todo" ? "todo" : failed ? "fail" : marked ?? "pass";
+    return report(results, entry.name, path, status, now() - started, failed ? failure : undefined);
+  };
+
+  // Whether any test or suite below `suite` is marked `only`.
+  const containsOnly = (suite) =>
+    suite.children.some((child) =>
+      child.options.only || (child.kind === "suite" && containsOnly(child)));
+
+  const reportAll = (suite, path, status, error, results) => {
+    for (const child of suite.children) {
+      if (child.kind === "suite") reportAll(child, [...path, child.name], status, error, results);
+      else report(results, child.name, path, status, 0, error);
+    }
+  };
+
+  // When a suite contains `only` tests, its other children are skipped; an
+  // `only` suite runs all of its children unless some of them are `only`.
+  const runSuite = async (suite, path, chain, results) => {
+    if (suite.options.skip) {
+      reportAll(suite, path, "skip", undefined, results);
+      return;
+    }
+    const context = { name: suite.name };
+    const inner = [...chain, suite];
+    const failed = inner.some((parent) => parent.options.todo) ? "todo" : "fail";
+    try {
+      if (suite.pending) await suite.pending;
+      await runHooks(suite.before, context);
+    } catch (error) {
+      reportAll(suite, path, failed, error, results);
+      return;
+    }
+    const only = containsOnly(suite);
+    for (const child of suite.children) {
+      const selected = !only || child.options.only ||
+        (child.kind === "suite" && containsOnly(child));
+      if (!selected) {
+        if (child.kind === "suite") reportAll(child, [...path, child.name], "skip", undefined, results);
+        else report(results, child.name, path, "skip", 0);
+      } else if (child.kind === "suite") {
+        await runSuite(child, [...path, child.name], inner, results);
+      } else {
+        await runTest(child, path, inner, results);
+      }
+    }
+    try {
+      await runHooks(suite.after, context);
+    } catch (error) {
+      report(results, suite.name || "<root>", path, failed, 0, error);
+    }
+  };
+
+  const run = async () => {
+    const suite = root;
+    root = newSuite("", {});
+    current = root;
+    const results = [];
+    await runSuite(suite, [], [], results);
+    return results;
+  };
+
+  return {
+    test, it: test, describe, suite: describe,
+    before: hook("before"), after: hook("after"),
+    beforeEach: hook("beforeEach"), afterEach: hook("afterEach"),
+    __fjsRun: run,
+  };
+})()
+"#;
+
+/// The `test` builtin module.
+pub(crate) struct TestModule;
+
+impl ModuleDef for TestModule {
+    fn declare(declare: &Declarations) -> rquickjs::Result<()> {
+        for name in EXPORTS {
+            declare.declare(*name)?;
+        }
+        declare.declare("default")
+    }
+
+    fn evaluate<'js>(ctx: &Ctx<'js>, exports: &Exports<'js>) -> rquickjs::Result<()> {
+        let harness: Object = ctx.eval(HARNESS)?;
+        for name in EXPORTS {
+            exports.export(*name, harness.get::<_, Value>(*name)?)?;
+        }
+        exports.export("default", harness.get::<_, Value>("test")?)?;
+        Ok(())
+    }
+}
+
+fn field<'a>(object: &'a HashMap<String, JsValue>, name: &str) -> Option<&'a JsValue> {
+    object.get(name)
+}
+
+fn string_field(object: &HashMap<String, JsValue>, name: &str) -> Option<String> {
+    match field(object, name) {
+        Some(JsValue::String(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn invalid_result() -> JsError {
+    JsError::runtime("Test harness returned a malformed result")
+}
+
+/// Converts the value returned by `__fjsRun` into test results.
+pub(crate) fn parse_results(value: JsValue) -> Result<Vec<JsTestResult>, JsError> {
+    let JsValue::Array(items) = value else {
+        return Err(invalid_result());
+    };
+    items
+        .into_iter()
+        .map(|item| {
+            let JsValue::Object(object) = item else {
+                return Err(invalid_result());
+            };
+            let status = match string_field(&object, "status").as_deref() {
+                Some("pass") => JsTestStatus::Pass,
+                Some("fail") => JsTestStatus::Fail,
+                Some("skip") => JsTestStatus::Skip,
+                Some("todo") => JsTestStatus::Todo,
+                _ => return Err(invalid_result()),
+            };
+            let path = match field(&object, "path") {
+                Some(JsValue::Array(names)) => names
+                    .iter()
+                    .map(|name| match name {
+                        JsValue::String(name) => name.clone(),
+                        other => format!("{other:?}"),
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let duration_ms = match field(&object, "durationMs") {
+                Some(JsValue::Float(ms)) => *ms,
+                Some(JsValue::Integer(ms)) => *ms as f64,
+                _ => 0.0,
+            };
+            let error = match field(&object, "error") {
+                Some(JsValue::Object(error)) => Some(JsTestFailure {
+                    name: string_field(error, "name").unwrap_or_else(|| "Error".to_string()),
+                    message: string_field(error, "message").unwrap_or_default(),
+                    stack: string_field(error, "stack"),
+                }),
+                _ => None,
+            };
+            Ok(JsTestResult {
+                name: string_field(&object, "name").ok_or_else(invalid_result)?,
+                path,
+                status,
+                duration_ms,
+                error,
+            })
+        })
+        .collect()
+}
@@ -145,7 +145,7 @@ fn assert_memory_usage_is_readable(usage: &MemoryUsage) {
     assert!(usage.summary().contains("Memory:"));
 }
 
-fn builtin_flags(options: &JsBuiltinOptions) -> [Option<bool>; 30] {
+fn builtin_flags(options: &JsBuiltinOptions) -> [Option<bool>; 31] {
     [
         options.abort,
         options.assert,
@@ -171,6 +171,7 @@ fn builtin_flags(options: &JsBuiltinOptions) -> [Option<bool>; 30] {
         options.stream_web,
         options.string_decoder,
         options.temporal,
+        options.test,
         options.timers,
         options.tty,
         options.url,
@@ -1035,6 +1036,15 @@ async fn engine_public_apis_interoperate_across_sources_bytecode_bridge_and_shut
                 gc_threshold: Some(512 * 1024),
                 max_stack_size: Some(512 * 1024),
                 info: Some("engine-surface".to_string()),
+                module_cache: None,
+                unhandled_error_policy: None,
+                soft_memory_limit: None,
+                coverage: None,
+                manual_event_loop: None,
+                deterministic: None,
+                bridge: None,
+                trace_context: None,
+                async_stack_traces: None,
             }),
         )
         .await
@@ -1213,10 +1223,13 @@ async fn engine_public_apis_interoperate_across_sources_bytecode_bridge_and_shut
     assert!(available.contains(&"timers".to_string()));
 
     let evaluated = engine
-        .evaluate_module(JsModule::code(
-            "engine/evaluated.js".to_string(),
-            "globalThis.__evaluatedModule = 42; export const loaded = true;".to_string(),
-        ))
+        .evaluate_module(
+            JsModule::code(
+                "engine/evaluated.js".to_string(),
+                "globalThis.__evaluatedModule = 42; export const loaded = true;".to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
     assert!(evaluated.is_none());
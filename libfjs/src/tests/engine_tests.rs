@@ -218,6 +218,15 @@ async fn test_engine_create_applies_runtime_options_before_init() {
             gc_threshold: Some(256 * 1024),
             max_stack_size: Some(128 * 1024),
             info: Some("engine-runtime".to_string()),
+            module_cache: None,
+            unhandled_error_policy: None,
+            soft_memory_limit: None,
+            coverage: None,
+            manual_event_loop: None,
+            deterministic: None,
+            bridge: None,
+            trace_context: None,
+            async_stack_traces: None,
         }),
     )
     .await
@@ -228,202 +237,210 @@ async fn test_engine_create_applies_runtime_options_before_init() {
 }
 
 #[tokio::test]
-async fn test_engine_initial_state() {
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-
-    assert!(!engine.closed());
-    assert!(!engine.running());
-}
-
-#[tokio::test]
-async fn test_engine_runtime_proxy_methods_work_before_init() {
+async fn test_engine_metrics_record_evals_calls_bridge_and_gc() {
     let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine
+        .init(|value| Box::pin(async move { JsResult::Ok(value) }))
+        .await
+        .unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "metrics/math".to_string(),
+            "export const double = (value) => value * 2;".to_string(),
+        ))
+        .await
+        .unwrap();
 
-    let pending = engine.is_job_pending().await.unwrap();
-    let usage = engine.memory_usage().await.unwrap();
-    engine.set_memory_limit(1024 * 1024).await.unwrap();
-    engine.set_gc_threshold(256 * 1024).await.unwrap();
-    engine.set_max_stack_size(128 * 1024).await.unwrap();
-    engine.set_info("before-init".to_string()).await.unwrap();
+    engine
+        .eval(
+            JsCode::Code("await fjs.bridge_call(1); await fjs.bridge_call(2);".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    let _ = engine
+        .eval(JsCode::Code("throw new Error('counted')".to_string()), None)
+        .await;
+    engine
+        .call(
+            "metrics/math".to_string(),
+            "double".to_string(),
+            Some(vec![JsValue::Integer(2)]),
+        )
+        .await
+        .unwrap();
     engine.run_gc().await.unwrap();
-    engine.idle().await.unwrap();
-    let progressed = engine.execute_pending_job().await.unwrap();
-
-    assert!(!pending);
-    assert!(usage.total_memory() >= 0);
-    assert!(!progressed);
-}
-
-#[tokio::test]
-async fn test_engine_init_without_bridge() {
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-
-    let result = engine.init_without_bridge().await;
-    assert!(result.is_ok());
-    assert!(engine.running());
-    assert!(!engine.closed());
-}
-
-#[tokio::test]
-async fn test_engine_init_failure_rolls_back_state() {
-    let runtime = JsAsyncRuntime {
-        rt: rquickjs::AsyncRuntime::new().unwrap(),
-        global_attachment: Some(
-            GlobalAttachment::default().add_function(failing_global_attachment),
-        ),
-        driver: crate::runtime::driver::DriverController::default(),
-        shutdown: RuntimeShutdown::default(),
-        cleaned: Arc::new(AtomicBool::new(false)),
-        runtime_lifetime: Some(Arc::new(())),
-    };
-    let context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::new_for_test(runtime, context);
-
-    let first_error = engine.init_without_bridge().await.unwrap_err();
-    assert!(
-        first_error
-            .to_string()
-            .contains("Failed to attach global context")
-    );
-    assert!(!engine.running());
 
-    let second_error = engine.init_without_bridge().await.unwrap_err();
-    assert!(
-        second_error
-            .to_string()
-            .contains("Failed to attach global context")
+    let metrics = engine.metrics().await.unwrap();
+    assert_eq!(metrics.eval_latency_us.count, 2);
+    assert_eq!(metrics.call_latency_us.count, 1);
+    assert_eq!(metrics.bridge_call_latency_us.count, 2);
+    assert_eq!(metrics.gc_time_us.count, 1);
+    assert!(metrics.module_load_latency_us.count >= 1);
+    assert_eq!(
+        metrics
+            .eval_latency_us
+            .buckets
+            .iter()
+            .map(|bucket| bucket.count)
+            .sum::<u64>(),
+        2
     );
-    assert!(!engine.running());
+    assert!(metrics.eval_latency_us.max <= metrics.eval_latency_us.sum);
+    engine.close().await.unwrap();
 }
 
-#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn test_engine_runtime_proxy_methods_fail_while_initializing() {
-    prepare_blocking_init_attachment();
-
-    let runtime = JsAsyncRuntime {
-        rt: rquickjs::AsyncRuntime::new().unwrap(),
-        global_attachment: Some(
-            GlobalAttachment::default().add_function(blocking_global_attachment),
-        ),
-        driver: crate::runtime::driver::DriverController::default(),
-        shutdown: RuntimeShutdown::default(),
-        cleaned: Arc::new(AtomicBool::new(false)),
-        runtime_lifetime: Some(Arc::new(())),
-    };
-    let context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = std::sync::Arc::new(JsEngine::new_for_test(runtime, context));
+#[tokio::test]
+async fn test_engine_soft_memory_limit_and_pressure_events() {
+    use crate::api::engine::JsEngineRuntimeOptions;
+    use crate::api::runtime::{JsMemoryEventKind, JsMemoryPressureLevel};
 
-    let init_engine = engine.clone();
-    let init_task = tokio::spawn(async move { init_engine.init_without_bridge().await });
+    let engine = JsEngine::create(
+        None,
+        None,
+        Some(JsEngineRuntimeOptions {
+            soft_memory_limit: Some(1),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let listener_events = events.clone();
+    engine
+        .runtime_for_test()
+        .driver
+        .memory()
+        .add_listener(Arc::new(move |event| {
+            listener_events.lock().unwrap().push(event.kind);
+            true
+        }));
 
-    tokio::task::spawn_blocking(wait_for_blocking_init_start)
+    engine
+        .eval(
+            JsCode::Code("setTimeout(() => {}, 0); 'scheduled'".to_string()),
+            None,
+        )
         .await
         .unwrap();
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+    while !events
+        .lock()
+        .unwrap()
+        .contains(&JsMemoryEventKind::SoftLimitExceeded)
+    {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "soft limit event was not emitted"
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
 
-    let usage_error = engine.memory_usage().await;
-    assert!(matches!(usage_error, Err(ref error) if error.to_string().contains("initializing")));
-
-    let limit_error = engine.set_memory_limit(1024).await.unwrap_err();
-    assert!(limit_error.to_string().contains("initializing"));
-
-    // close() always wins, even while init is still in flight; the blocked
-    // init observes the CLOSED state when it tries to commit and fails.
-    let close_engine = engine.clone();
-    let close_task = tokio::spawn(async move { close_engine.close().await });
-
-    release_blocking_init_attachment();
-
-    close_task.await.unwrap().unwrap();
-    let init_error = init_task.await.unwrap().unwrap_err();
-    assert!(
-        init_error
-            .to_string()
-            .contains("Failed to finalize engine initialization"),
-        "{init_error}"
+    let event = engine
+        .notify_memory_pressure(JsMemoryPressureLevel::Critical)
+        .await
+        .unwrap();
+    assert_eq!(
+        event.kind,
+        JsMemoryEventKind::MemoryPressure(JsMemoryPressureLevel::Critical)
     );
-    assert!(engine.closed());
-}
-
-#[tokio::test]
-async fn test_engine_init_with_bridge() {
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-
-    let result = engine
-        .init(|value| {
-            Box::pin(async move {
-                // Echo back the value
-                JsResult::Ok(value)
-            })
-        })
-        .await;
-
-    assert!(result.is_ok());
-    assert!(engine.running());
+    assert_eq!(event.soft_limit, Some(1));
+    assert!(events.lock().unwrap().contains(&event.kind));
     engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_double_init_fails() {
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-
-    let result1 = engine.init_without_bridge().await;
-    assert!(result1.is_ok());
-
-    let result2 = engine.init_without_bridge().await;
-    assert!(result2.is_err());
-}
-
-#[tokio::test]
-async fn test_engine_close() {
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_soft_memory_limit_is_checked_while_code_runs() {
+    use crate::api::engine::JsEngineRuntimeOptions;
+    use crate::api::runtime::JsMemoryEventKind;
 
+    // With a host-pumped loop the driver never goes idle, so only the
+    // interrupt-handler check can notice the crossing.
+    let engine = JsEngine::create(
+        None,
+        None,
+        Some(JsEngineRuntimeOptions {
+            soft_memory_limit: Some(1),
+            manual_event_loop: Some(true),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
     engine.init_without_bridge().await.unwrap();
-    let result = engine.close().await;
-
-    assert!(result.is_ok());
-    assert!(engine.closed());
-    assert!(!engine.running());
-}
-
-#[tokio::test]
-async fn test_engine_double_close_is_idempotent() {
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let listener_events = events.clone();
+    let memory = engine.runtime_for_test().driver.memory();
+    let nested = memory.clone();
+    memory.add_listener(Arc::new(move |event| {
+        // Registering from inside a listener must not deadlock.
+        nested.add_listener(Arc::new(|_| false));
+        listener_events.lock().unwrap().push(event.kind);
+        true
+    }));
 
-    engine.init_without_bridge().await.unwrap();
+    engine
+        .eval(
+            JsCode::Code(
+                "const end = Date.now() + 200; const keep = []; \
+                 while (Date.now() < end) keep.push({}); keep.length > 0"
+                    .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(
+        events
+            .lock()
+            .unwrap()
+            .contains(&JsMemoryEventKind::SoftLimitExceeded)
+    );
     engine.close().await.unwrap();
-
-    let result = engine.close().await;
-    assert!(result.is_ok());
-    assert!(engine.closed());
-    assert!(!engine.running());
 }
 
 #[tokio::test]
-async fn test_engine_close_marks_engine_closed() {
+async fn test_engine_heap_snapshot_diff_and_export() {
     let engine = JsEngine::create(None, None, None).await.unwrap();
-
     engine.init_without_bridge().await.unwrap();
-    engine.close().await.unwrap();
-
-    assert!(engine.closed());
-    assert!(!engine.running());
-}
+    engine
+        .eval(
+            JsCode::Code(
+                "class Leaky {}; globalThis.Leaky = Leaky; globalThis.leak = [];".to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
 
-#[tokio::test]
-async fn test_engine_close_drains_pending_runtime_work() {
-    let engine = JsEngine::create(Some(JsBuiltinOptions::essential()), None, None)
+    let before = engine.heap_snapshot().await.unwrap();
+    engine
+        .eval(
+            JsCode::Code("for (let i = 0; i < 100; i++) leak.push(new Leaky());".to_string()),
+            None,
+        )
         .await
         .unwrap();
-    engine.init_without_bridge().await.unwrap();
+    let after = engine.heap_snapshot().await.unwrap();
 
-    let scheduled = engine
+    assert!(after.node_count > before.node_count);
+    let leaky = before
+        .diff(&after)
+        .into_iter()
+        .find(|delta| delta.name == "Leaky")
+        .expect("Leaky instances should appear in the diff");
+    assert_eq!(leaky.count_delta, 100);
+    assert!(leaky.self_size_delta > 0);
+
+    engine
         .eval(
             JsCode::Code(
                 r#"
-                    setTimeout(() => {
-                        globalThis.__close_timer_fired = true;
-                    }, 10);
-                    'scheduled'
+                class Cached {}
+                globalThis.reads = 0;
+                globalThis.cache = new Map([["a", new Cached()], ["b", new Cached()]]);
+                Object.defineProperty(globalThis, "watched", { get() { reads++; return 1; } });
                 "#
                 .to_string(),
             ),
@@ -431,24 +448,832 @@ async fn test_engine_close_drains_pending_runtime_work() {
         )
         .await
         .unwrap();
-    assert!(matches!(scheduled, JsValue::String(ref value) if value == "scheduled"));
-    assert!(engine.is_job_pending().await.unwrap());
-
-    let runtime = engine.runtime_for_test();
-    engine.close_gracefully().await.unwrap();
+    let snapshot = engine.heap_snapshot().await.unwrap();
+    let cached = snapshot
+        .classes
+        .iter()
+        .find(|stats| stats.name == "Cached")
+        .expect("Map entries should be walked");
+    assert_eq!(cached.count, 2);
+    let reads = engine
+        .eval(JsCode::Code("reads".to_string()), None)
+        .await
+        .unwrap();
+    assert!(matches!(reads, JsValue::Integer(0)));
 
-    assert!(!runtime.is_job_pending().await);
+    let exported = engine.export_heap_snapshot().await.unwrap();
+    assert!(exported.starts_with("{\"snapshot\":"));
+    assert!(exported.contains("\"node_fields\""));
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_runtime_proxy_methods_fail_after_close() {
+async fn test_engine_cpu_profile_samples_hot_function() {
     let engine = JsEngine::create(None, None, None).await.unwrap();
-    engine.close().await.unwrap();
+    engine.init_without_bridge().await.unwrap();
 
-    assert!(engine.memory_usage().await.is_err());
-    assert!(engine.set_memory_limit(1024).await.is_err());
-    assert!(engine.run_gc().await.is_err());
-}
+    engine.start_profiling(Some(100)).await.unwrap();
+    assert!(engine.start_profiling(None).await.is_err());
+    engine
+        .evaluate_module(
+            JsModule::code(
+                "hot".to_string(),
+                "function hot() { const end = Date.now() + 50; let n = 0; while (Date.now() < end) n++; return n; } hot();"
+                    .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    let profile = engine.stop_profiling().await.unwrap();
+    assert!(engine.stop_profiling().await.is_err());
+
+    assert!(!profile.samples.is_empty());
+    assert_eq!(profile.samples.len(), profile.time_deltas_us.len());
+    assert_eq!(profile.nodes[0].function_name, "(root)");
+    assert!(profile.nodes.iter().any(|node| node.url == "hot"));
+    assert!(
+        profile
+            .to_cpuprofile_json()
+            .starts_with("{\"nodes\":[{\"id\":1,")
+    );
+    assert!(profile.to_folded_stacks().contains("hot"));
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_coverage_counts_statements_and_functions() {
+    use crate::api::engine::JsEngineRuntimeOptions;
+
+    let engine = JsEngine::create(
+        None,
+        None,
+        Some(JsEngineRuntimeOptions {
+            coverage: Some(true),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "covered".to_string(),
+            "export function used(n) {\n  if (n > 1) {\n    return n * 2;\n  }\n  return n;\n}\nexport function unused() {\n  return 0;\n}\n"
+                .to_string(),
+        ))
+        .await
+        .unwrap();
+    for n in [1, 2, 3] {
+        engine
+            .call(
+                "covered".to_string(),
+                "used".to_string(),
+                Some(vec![JsValue::Integer(n)]),
+            )
+            .await
+            .unwrap();
+    }
+
+    let report = engine.coverage().await.unwrap();
+    let module = report
+        .modules
+        .iter()
+        .find(|module| module.module == "covered")
+        .expect("declared module should be instrumented");
+    let hits: Vec<_> = module
+        .functions
+        .iter()
+        .map(|function| (function.name.as_str(), function.hits))
+        .collect();
+    assert_eq!(hits, [("used", 3), ("unused", 0)]);
+    assert_eq!(module.line_hits(), [(1, 1), (2, 3), (3, 2), (5, 1), (8, 0)]);
+    let lcov = report.to_lcov();
+    assert!(lcov.contains("SF:covered\nFN:1,used\nFN:7,unused\nFNDA:3,used\nFNDA:0,unused\n"));
+    assert!(lcov.contains("DA:3,2\n"));
+    assert!(
+        report
+            .to_istanbul_json()
+            .starts_with("{\"covered\":{\"path\":\"covered\"")
+    );
+
+    engine.reset_coverage().await.unwrap();
+    let report = engine.coverage().await.unwrap();
+    assert!(
+        report.modules[0]
+            .statements
+            .iter()
+            .all(|statement| statement.hits == 0)
+    );
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_coverage_keeps_tricky_modules_working() {
+    use crate::api::engine::JsEngineRuntimeOptions;
+
+    let cases = [
+        (
+            "let hit = 0;\nif (true) /;{/.test(';{') && hit++;\nexport const value = hit;",
+            JsValue::Integer(1),
+        ),
+        (
+            "const f = () => 8;\nexport const value = f() / 2 / 2;",
+            JsValue::Integer(2),
+        ),
+        (
+            "const a = [8];\nlet r = 0; { r = 1 } /a/.test('a') && r++;\nexport const value = a[0] / 2 / 2 + r;",
+            JsValue::Integer(4),
+        ),
+        (
+            "export const value = /[/;]{2}/.test('/;') + `${ { a: '}' }.a }${`;{`}`;",
+            JsValue::String("true};{".to_string()),
+        ),
+        (
+            "'use strict';\nfunction f() {\n  'use strict';\n  return this;\n}\nexport const value = f() === undefined;",
+            JsValue::Boolean(true),
+        ),
+        (
+            "let r;\nif (false) r = 0; else /x/.test('x') && (r = 3);\nexport const value = r;",
+            JsValue::Integer(3),
+        ),
+        (
+            "class C { static #n = 1; static { C.m = C.#n + 1; } get v() { return C.m; } }\nexport const value = new C().v;",
+            JsValue::Integer(2),
+        ),
+        (
+            "/* { */ // }\nouter: for (const x of [1, 2]) for (const y of [1]) { if (x === 2) break outer; }\nexport const value = ({ a: 1 }).a;",
+            JsValue::Integer(1),
+        ),
+    ];
+
+    let engine = JsEngine::create(
+        None,
+        None,
+        Some(JsEngineRuntimeOptions {
+            coverage: Some(true),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    for (index, (source, expected)) in cases.into_iter().enumerate() {
+        let name = format!("case{index}");
+        engine
+            .declare_new_module(JsModule::code(name.clone(), source.to_string()))
+            .await
+            .unwrap();
+        let value = engine
+            .eval(
+                JsCode::Code(format!("(await import('{name}')).value")),
+                None,
+            )
+            .await
+            .unwrap_or_else(|e| panic!("{name} failed: {e}\n{source}"));
+        assert_eq!(value, expected, "{source}");
+    }
+    let report = engine.coverage().await.unwrap();
+    assert_eq!(report.modules.len(), 8);
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_run_tests_reports_each_test() {
+    use crate::api::testing::JsTestStatus;
+
+    let engine = JsEngine::create(
+        Some(JsBuiltinOptions {
+            test: Some(true),
+            ..Default::default()
+        }),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "math.test".to_string(),
+            r#"
+            import { describe, it, test, beforeEach } from "node:test";
+            import assert from "node:assert";
+            let setups = 0;
+            describe("math", () => {
+              beforeEach(() => { setups++; });
+              it("adds", () => assert.strictEqual(1 + 1, 2));
+              it("fails", async () => assert.strictEqual(1 + 1, 3));
+              it.skip("skipped", () => { throw new Error("not run"); });
+              it.todo("later");
+            });
+            test("hooks ran", () => assert.strictEqual(setups, 2));
+            "#
+            .to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let report = engine.run_tests("math.test".to_string()).await.unwrap();
+    assert_eq!(
+        (report.passed, report.failed, report.skipped, report.todo),
+        (2, 1, 1, 1)
+    );
+    let failed = report
+        .results
+        .iter()
+        .find(|result| result.status == JsTestStatus::Fail)
+        .unwrap();
+    assert_eq!(failed.name, "fails");
+    assert_eq!(failed.path, ["math"]);
+    assert_eq!(failed.error.as_ref().unwrap().name, "AssertionError");
+
+    let rerun = engine.run_tests("math.test".to_string()).await.unwrap();
+    assert!(rerun.results.is_empty());
+
+    engine
+        .declare_new_module(JsModule::code(
+            "focus.test".to_string(),
+            r#"
+            import { describe, it, test } from "node:test";
+            globalThis.ran = [];
+            test("plain", () => { ran.push("plain"); });
+            describe("focus", () => {
+              it.only("focused", () => { ran.push("focused"); });
+              it("unfocused", () => { ran.push("unfocused"); });
+            });
+            test.only("pending", { todo: true }, () => {
+              ran.push("pending");
+              throw new Error("not yet");
+            });
+            "#
+            .to_string(),
+        ))
+        .await
+        .unwrap();
+    let report = engine.run_tests("focus.test".to_string()).await.unwrap();
+    assert_eq!(
+        (report.passed, report.failed, report.skipped, report.todo),
+        (1, 0, 2, 1)
+    );
+    let pending = report
+        .results
+        .iter()
+        .find(|result| result.status == JsTestStatus::Todo)
+        .unwrap();
+    assert_eq!(pending.error.as_ref().unwrap().message, "not yet");
+    let ran = engine
+        .eval(JsCode::Code("ran.join(',')".to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(ran, JsValue::String("focused,pending".to_string()));
+    engine.close().await.unwrap();
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+    assert!(engine.run_tests("missing".to_string()).await.is_err());
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_realms_isolate_globals_and_share_selected_modules() {
+    use crate::api::realm::JsRealmOptions;
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "counter".to_string(),
+            "let count = 0;\nexport function increment() { return ++count; }\n\
+             export function wrap(value) { return { value, at: [count], fail() { throw new TypeError('no'); } }; }\n"
+                .to_string(),
+        ))
+        .await
+        .unwrap();
+    engine
+        .eval(
+            JsCode::Code("globalThis.engineSecret = 'hidden'".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let realm = engine
+        .create_realm(Some(JsRealmOptions {
+            shared_modules: Some(vec!["counter".to_string()]),
+        }))
+        .await
+        .unwrap();
+    let other = engine.create_realm(None).await.unwrap();
+
+    realm
+        .eval(JsCode::Code("globalThis.tenant = 'acme'".to_string()), None)
+        .await
+        .unwrap();
+    let tenant = engine
+        .eval(JsCode::Code("typeof globalThis.tenant".to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(tenant, JsValue::String("undefined".to_string()));
+
+    realm
+        .declare_new_module(JsModule::code(
+            "plugin".to_string(),
+            "import { increment } from 'counter';\nexport function run() { return increment(); }\n"
+                .to_string(),
+        ))
+        .await
+        .unwrap();
+    let from_realm = realm
+        .call("plugin".to_string(), "run".to_string(), None)
+        .await
+        .unwrap();
+    let from_engine = engine
+        .call("counter".to_string(), "increment".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(from_realm, JsValue::Integer(1));
+    assert_eq!(from_engine, JsValue::Integer(2));
+
+    // Nothing handed to the realm leads back to the engine's global object.
+    realm
+        .declare_new_module(JsModule::code(
+            "probe".to_string(),
+            r#"
+            import { increment, wrap } from 'counter';
+            export function run() {
+              const wrapped = wrap({ n: 1 });
+              const reach = (value) => value.constructor.constructor('return globalThis')();
+              let thrown;
+              try { wrapped.fail(); } catch (e) { thrown = e; }
+              return [
+                reach(increment) === globalThis,
+                reach(wrapped) === globalThis,
+                reach(wrapped.at) === globalThis,
+                reach(thrown) === globalThis,
+                thrown instanceof Error,
+                wrapped.value.n,
+                typeof globalThis.engineSecret,
+              ].join(',');
+            }
+            "#
+            .to_string(),
+        ))
+        .await
+        .unwrap();
+    let escaped = realm
+        .call("probe".to_string(), "run".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        escaped,
+        JsValue::String("true,true,true,true,true,1,undefined".to_string())
+    );
+    assert!(
+        !engine
+            .is_module_declared("plugin".to_string())
+            .await
+            .unwrap()
+    );
+    assert!(
+        other
+            .call("plugin".to_string(), "run".to_string(), None)
+            .await
+            .is_err()
+    );
+
+    realm.close().await.unwrap();
+    assert!(realm.closed());
+    assert!(
+        realm
+            .eval(JsCode::Code("1".to_string()), None)
+            .await
+            .is_err()
+    );
+    other
+        .eval(JsCode::Code("1".to_string()), None)
+        .await
+        .unwrap();
+
+    engine.close().await.unwrap();
+    assert!(
+        other
+            .eval(JsCode::Code("1".to_string()), None)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_engine_eval_isolated_cannot_touch_engine_state() {
+    use std::collections::HashMap;
+
+    let engine = JsEngine::create(
+        Some(JsBuiltinOptions {
+            console: Some(true),
+            ..Default::default()
+        }),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "secret".to_string(),
+            "export const token = 'abc';".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let mut bindings = HashMap::new();
+    bindings.insert("price".to_string(), JsValue::Integer(4));
+    bindings.insert("quantity".to_string(), JsValue::Integer(3));
+    let total = engine
+        .eval_isolated("price * quantity".to_string(), Some(bindings))
+        .await
+        .unwrap();
+    assert_eq!(total, JsValue::Integer(12));
+
+    let visible = engine
+        .eval_isolated(
+            "globalThis.leaked = 1; [typeof console, Object.isFrozen(Array.prototype)]".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        visible,
+        JsValue::Array(vec![
+            JsValue::String("undefined".to_string()),
+            JsValue::Boolean(true),
+        ])
+    );
+    assert!(
+        engine
+            .eval_isolated("Array.prototype.evil = 1".to_string(), None)
+            .await
+            .is_err()
+    );
+    assert!(
+        engine
+            .eval_isolated("(await import('secret')).token".to_string(), None)
+            .await
+            .is_err()
+    );
+    assert!(
+        engine
+            .eval_isolated("() => 1".to_string(), None)
+            .await
+            .is_err()
+    );
+
+    let leaked = engine
+        .eval(
+            JsCode::Code("[typeof globalThis.leaked, typeof Array.prototype.evil]".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        leaked,
+        JsValue::Array(vec![
+            JsValue::String("undefined".to_string()),
+            JsValue::String("undefined".to_string()),
+        ])
+    );
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_eval_with_bindings() {
+    use crate::api::source::JsEvalOptions;
+    use std::collections::HashMap;
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let mut input = HashMap::new();
+    input.insert(
+        "name".to_string(),
+        JsValue::String("'; throw 1; '".to_string()),
+    );
+    input.insert("count".to_string(), JsValue::Integer(2));
+    let mut bindings = HashMap::new();
+    bindings.insert("input".to_string(), JsValue::Object(input));
+    bindings.insert("suffix".to_string(), JsValue::String("!".to_string()));
+    let options = JsEvalOptions {
+        bindings: Some(bindings),
+        ..JsEvalOptions::with_promise()
+    };
+    let result = engine
+        .eval(
+            JsCode::Code(
+                "const greeting = input.name.repeat(input.count);\nawait null;\ngreeting + suffix"
+                    .to_string(),
+            ),
+            Some(options.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        JsValue::String("'; throw 1; ''; throw 1; '!".to_string())
+    );
+
+    // Declarations stay local, so the same script can run again.
+    engine
+        .eval(
+            JsCode::Code("const greeting = input.count;".to_string()),
+            Some(options),
+        )
+        .await
+        .unwrap();
+    let leaked = engine
+        .eval(
+            JsCode::Code(
+                "[typeof input, typeof greeting, typeof globalThis.__fjs_bindings__]".to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        leaked,
+        JsValue::Array(vec![JsValue::String("undefined".to_string()); 3])
+    );
+
+    let mut invalid = HashMap::new();
+    invalid.insert("a; globalThis.x = 1".to_string(), JsValue::Integer(1));
+    let error = engine
+        .eval(
+            JsCode::Code("1".to_string()),
+            Some(JsEvalOptions {
+                bindings: Some(invalid),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not a valid identifier"));
+
+    let mut reserved = HashMap::new();
+    reserved.insert("if".to_string(), JsValue::Integer(1));
+    let error = engine
+        .eval(
+            JsCode::Code("1".to_string()),
+            Some(JsEvalOptions {
+                bindings: Some(reserved),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("not a valid identifier"));
+
+    // A leading directive still applies inside the wrapper.
+    let mut flag = HashMap::new();
+    flag.insert("flag".to_string(), JsValue::Boolean(true));
+    let strict = engine
+        .eval(
+            JsCode::Code(
+                "'use strict';\n(function () { return flag && this === undefined; })()".to_string(),
+            ),
+            Some(JsEvalOptions {
+                strict: Some(false),
+                bindings: Some(flag),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(strict, JsValue::Boolean(true));
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_initial_state() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    assert!(!engine.closed());
+    assert!(!engine.running());
+}
+
+#[tokio::test]
+async fn test_engine_runtime_proxy_methods_work_before_init() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    let pending = engine.is_job_pending().await.unwrap();
+    let usage = engine.memory_usage().await.unwrap();
+    engine.set_memory_limit(1024 * 1024).await.unwrap();
+    engine.set_gc_threshold(256 * 1024).await.unwrap();
+    engine.set_max_stack_size(128 * 1024).await.unwrap();
+    engine.set_info("before-init".to_string()).await.unwrap();
+    engine.run_gc().await.unwrap();
+    engine.idle().await.unwrap();
+    let progressed = engine.execute_pending_job().await.unwrap();
+
+    assert!(!pending);
+    assert!(usage.total_memory() >= 0);
+    assert!(!progressed);
+}
+
+#[tokio::test]
+async fn test_engine_init_without_bridge() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    let result = engine.init_without_bridge().await;
+    assert!(result.is_ok());
+    assert!(engine.running());
+    assert!(!engine.closed());
+}
+
+#[tokio::test]
+async fn test_engine_init_failure_rolls_back_state() {
+    let runtime = JsAsyncRuntime {
+        rt: rquickjs::AsyncRuntime::new().unwrap(),
+        global_attachment: Some(
+            GlobalAttachment::default().add_function(failing_global_attachment),
+        ),
+        driver: crate::runtime::driver::DriverController::default(),
+        shutdown: RuntimeShutdown::default(),
+        cleaned: Arc::new(AtomicBool::new(false)),
+        runtime_lifetime: Some(Arc::new(())),
+    };
+    let context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::new_for_test(runtime, context);
+
+    let first_error = engine.init_without_bridge().await.unwrap_err();
+    assert!(
+        first_error
+            .to_string()
+            .contains("Failed to attach global context")
+    );
+    assert!(!engine.running());
+
+    let second_error = engine.init_without_bridge().await.unwrap_err();
+    assert!(
+        second_error
+            .to_string()
+            .contains("Failed to attach global context")
+    );
+    assert!(!engine.running());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_engine_runtime_proxy_methods_fail_while_initializing() {
+    prepare_blocking_init_attachment();
+
+    let runtime = JsAsyncRuntime {
+        rt: rquickjs::AsyncRuntime::new().unwrap(),
+        global_attachment: Some(
+            GlobalAttachment::default().add_function(blocking_global_attachment),
+        ),
+        driver: crate::runtime::driver::DriverController::default(),
+        shutdown: RuntimeShutdown::default(),
+        cleaned: Arc::new(AtomicBool::new(false)),
+        runtime_lifetime: Some(Arc::new(())),
+    };
+    let context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = std::sync::Arc::new(JsEngine::new_for_test(runtime, context));
+
+    let init_engine = engine.clone();
+    let init_task = tokio::spawn(async move { init_engine.init_without_bridge().await });
+
+    tokio::task::spawn_blocking(wait_for_blocking_init_start)
+        .await
+        .unwrap();
+
+    let usage_error = engine.memory_usage().await;
+    assert!(matches!(usage_error, Err(ref error) if error.to_string().contains("initializing")));
+
+    let limit_error = engine.set_memory_limit(1024).await.unwrap_err();
+    assert!(limit_error.to_string().contains("initializing"));
+
+    // close() always wins, even while init is still in flight; the blocked
+    // init observes the CLOSED state when it tries to commit and fails.
+    let close_engine = engine.clone();
+    let close_task = tokio::spawn(async move { close_engine.close().await });
+
+    release_blocking_init_attachment();
+
+    close_task.await.unwrap().unwrap();
+    let init_error = init_task.await.unwrap().unwrap_err();
+    assert!(
+        init_error
+            .to_string()
+            .contains("Failed to finalize engine initialization"),
+        "{init_error}"
+    );
+    assert!(engine.closed());
+}
+
+#[tokio::test]
+async fn test_engine_init_with_bridge() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    let result = engine
+        .init(|value| {
+            Box::pin(async move {
+                // Echo back the value
+                JsResult::Ok(value)
+            })
+        })
+        .await;
+
+    assert!(result.is_ok());
+    assert!(engine.running());
+    engine.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_engine_double_init_fails() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    let result1 = engine.init_without_bridge().await;
+    assert!(result1.is_ok());
+
+    let result2 = engine.init_without_bridge().await;
+    assert!(result2.is_err());
+}
+
+#[tokio::test]
+async fn test_engine_close() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    engine.init_without_bridge().await.unwrap();
+    let result = engine.close().await;
+
+    assert!(result.is_ok());
+    assert!(engine.closed());
+    assert!(!engine.running());
+}
+
+#[tokio::test]
+async fn test_engine_double_close_is_idempotent() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    engine.init_without_bridge().await.unwrap();
+    engine.close().await.unwrap();
+
+    let result = engine.close().await;
+    assert!(result.is_ok());
+    assert!(engine.closed());
+    assert!(!engine.running());
+}
+
+#[tokio::test]
+async fn test_engine_close_marks_engine_closed() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    engine.init_without_bridge().await.unwrap();
+    engine.close().await.unwrap();
+
+    assert!(engine.closed());
+    assert!(!engine.running());
+}
+
+#[tokio::test]
+async fn test_engine_close_drains_pending_runtime_work() {
+    let engine = JsEngine::create(Some(JsBuiltinOptions::essential()), None, None)
+        .await
+        .unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let scheduled = engine
+        .eval(
+            JsCode::Code(
+                r#"
+                    setTimeout(() => {
+                        globalThis.__close_timer_fired = true;
+                    }, 10);
+                    'scheduled'
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(scheduled, JsValue::String(ref value) if value == "scheduled"));
+    assert!(engine.is_job_pending().await.unwrap());
+
+    let runtime = engine.runtime_for_test();
+    engine.close_gracefully().await.unwrap();
+
+    assert!(!runtime.is_job_pending().await);
+}
+
+#[tokio::test]
+async fn test_engine_runtime_proxy_methods_fail_after_close() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.close().await.unwrap();
+
+    assert!(engine.memory_usage().await.is_err());
+    assert!(engine.set_memory_limit(1024).await.is_err());
+    assert!(engine.run_gc().await.is_err());
+}
 
 // ============================================================================
 // Engine Evaluation Tests
@@ -701,10 +1526,473 @@ async fn test_engine_evaluate_module() {
         "export const value = 42; export default value;".to_string(),
     );
 
-    let result = engine.evaluate_module(module).await;
+    let result = engine.evaluate_module(module, None).await;
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_engine_reload_module_reevaluates_dependents_and_disposes() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    engine
+        .declare_new_modules(vec![
+            JsModule::code(
+                "reload/base".to_string(),
+                r#"
+                globalThis.disposed = [];
+                export const value = 1;
+                export function dispose() { globalThis.disposed.push('base:' + value); }
+                "#
+                .to_string(),
+            ),
+            JsModule::code(
+                "reload/app".to_string(),
+                r#"
+                import { value } from './base';
+                export const doubled = value * 2;
+                export function dispose() { globalThis.disposed.push('app:' + doubled); }
+                "#
+                .to_string(),
+            ),
+        ])
+        .await
+        .unwrap();
+    let before = engine
+        .call("reload/app".to_string(), "dispose".to_string(), None)
+        .await;
+    assert!(before.is_ok());
+
+    let reloaded = engine
+        .reload_module(JsModule::code(
+            "reload/base".to_string(),
+            "export const value = 20;".to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(reloaded, vec!["reload/base", "reload/app"]);
+
+    let result = engine
+        .eval(
+            JsCode::Code(
+                r#"
+                const { doubled } = await import('reload/app');
+                `${doubled}|${globalThis.disposed.join(',')}`
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(result, JsValue::String(ref s) if s == "40|app:2,app:2,base:1"));
+
+    // A reload that fails to evaluate leaves the current generation in place
+    // and disposes nothing.
+    let failed = engine
+        .reload_module(JsModule::code(
+            "reload/base".to_string(),
+            "throw new Error('broken reload');".to_string(),
+        ))
+        .await;
+    assert!(failed.is_err());
+    let result = engine
+        .eval(
+            JsCode::Code(
+                r#"
+                const { value } = await import('reload/base');
+                const { doubled } = await import('reload/app');
+                `${value}|${doubled}|${globalThis.disposed.length}`
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(result, JsValue::String(ref s) if s == "20|40|3"));
+
+    // Guest code cannot reach a generation by spelling out the marker.
+    let spoofed = engine
+        .eval(
+            JsCode::Code(
+                "await import('reload/base?fjs-reload=1').then(() => 'loaded', () => 'rejected')"
+                    .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(spoofed, JsValue::String(ref s) if s == "rejected"));
+
+    let missing = engine
+        .reload_module(JsModule::code("reload/missing".to_string(), String::new()))
+        .await;
+    assert!(missing.is_err());
+}
+
+#[tokio::test]
+async fn test_engine_module_graph_reports_imports_exports_and_status() {
+    use crate::api::module::{JsModuleOrigin, JsModuleStatus};
+    use crate::api::source::JsModuleEvalOptions;
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    engine
+        .declare_new_modules(vec![
+            JsModule::code(
+                "graph/util".to_string(),
+                "export const a = 1; export function b() {}".to_string(),
+            ),
+            JsModule::code("graph/lazy".to_string(), "export {};".to_string()),
+            JsModule::code("graph/unused".to_string(), "export {};".to_string()),
+        ])
+        .await
+        .unwrap();
+    engine
+        .evaluate_module(
+            JsModule::code(
+                "graph/main".to_string(),
+                "import { a } from './util'; export default a;".to_string(),
+            ),
+            Some(JsModuleEvalOptions {
+                return_exports: Some(true),
+            }),
+        )
+        .await
+        .unwrap();
+    assert!(
+        engine
+            .evaluate_module(
+                JsModule::code(
+                    "graph/broken".to_string(),
+                    "throw new Error('boom');".to_string(),
+                ),
+                None
+            )
+            .await
+            .is_err()
+    );
+    engine
+        .eval(JsCode::Code("await import('graph/lazy')".to_string()), None)
+        .await
+        .unwrap();
+
+    let graph = engine.module_graph().await.unwrap();
+    let find = |name: &str| graph.iter().find(|m| m.name == name).unwrap().clone();
+
+    let main = find("graph/main");
+    assert_eq!(main.status, JsModuleStatus::Evaluated);
+    assert_eq!(main.origin, JsModuleOrigin::Source);
+    assert_eq!(main.imports, vec!["graph/util"]);
+    assert_eq!(main.exports, vec!["default"]);
+    // Static imports of an evaluated module are evaluated too, but their
+    // exports are only listed once the engine imports them itself.
+    assert_eq!(find("graph/util").status, JsModuleStatus::Evaluated);
+    assert!(find("graph/util").exports.is_empty());
+    assert_eq!(find("graph/broken").status, JsModuleStatus::Errored);
+    // A module only imported from JavaScript reports its own evaluation.
+    assert_eq!(find("graph/lazy").status, JsModuleStatus::Evaluated);
+    assert_eq!(find("graph/unused").status, JsModuleStatus::Declared);
+
+    engine
+        .module_exports("graph/util".to_string())
+        .await
+        .unwrap();
+    let graph = engine.module_graph().await.unwrap();
+    let util = graph.iter().find(|m| m.name == "graph/util").unwrap();
+    assert_eq!(util.exports, vec!["a", "b"]);
+
+    // Building the graph must not leave unhandled rejections behind.
+    assert!(
+        engine
+            .eval(JsCode::Code("1".to_string()), None)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn test_engine_imports_json_text_and_bytes_modules() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    engine
+        .declare_new_modules(vec![
+            JsModule::json("config.json".to_string(), r#"{"port": 8080}"#.to_string()),
+            JsModule::text("greeting.txt".to_string(), "hello".to_string()),
+            JsModule::raw_bytes("blob.bin".to_string(), vec![1, 2, 255]),
+        ])
+        .await
+        .unwrap();
+
+    let result = engine
+        .eval(
+            JsCode::Code(
+                r#"
+                const { default: cfg } = await import('config.json', { with: { type: 'json' } });
+                const { default: text } = await import('greeting.txt', { with: { type: 'text' } });
+                const { default: blob } = await import('blob.bin', { with: { type: 'bytes' } });
+                `${cfg.port}:${text}:${blob instanceof Uint8Array}:${Array.from(blob).join(',')}`
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(result, JsValue::String(ref s) if s == "8080:hello:true:1,2,255"));
+
+    let error = engine
+        .eval(
+            JsCode::Code("await import('config.json', { with: { type: 'css' } })".to_string()),
+            None,
+        )
+        .await;
+    assert!(error.is_err());
+
+    let result = engine
+        .eval(
+            JsCode::Code(
+                r#"
+                const outcome = (specifier, options) =>
+                    import(specifier, options).then(() => 'loaded', () => 'rejected');
+                [
+                    await outcome('config.json'),
+                    await outcome('greeting.txt', { with: { type: 'json' } }),
+                    await outcome('blob.bin', { with: { type: 'text' } }),
+                ].join(',')
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(result, JsValue::String(ref s) if s == "rejected,rejected,rejected"));
+
+    engine
+        .declare_new_modules(vec![JsModule::code(
+            "plain".to_string(),
+            "export default 1;".to_string(),
+        )])
+        .await
+        .unwrap();
+    let result = engine
+        .eval(
+            JsCode::Code(
+                "await import('plain', { with: { type: 'json' } }).then(() => 'loaded', () => 'rejected')"
+                    .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(matches!(result, JsValue::String(ref s) if s == "rejected"));
+
+    let exports = engine
+        .module_exports("config.json".to_string())
+        .await
+        .unwrap();
+    assert_eq!(exports.len(), 1);
+}
+
+#[tokio::test]
+async fn test_engine_imports_json_file_with_type_attribute() {
+    let dir = std::env::temp_dir().join(format!("fjs-json-module-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("data.json");
+    std::fs::write(&path, r#"{"items": [1, 2, 3]}"#).unwrap();
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+    let result = engine
+        .eval(
+            JsCode::Code(format!(
+                "const {{ default: data }} = await import({:?}, {{ with: {{ type: 'json' }} }}); data.items.length",
+                path.to_string_lossy()
+            )),
+            None,
+        )
+        .await;
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(matches!(result.unwrap(), JsValue::Integer(3)));
+}
+
+#[tokio::test]
+async fn test_engine_module_cache_reuses_and_invalidates_directory_entries() {
+    use crate::api::engine::{JsEngineRuntimeOptions, JsModuleCache};
+
+    let dir = std::env::temp_dir().join(format!("fjs-module-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let run = async |source: &str| {
+        let engine = JsEngine::create(
+            None,
+            None,
+            Some(JsEngineRuntimeOptions {
+                module_cache: Some(JsModuleCache::Directory(dir.to_string_lossy().into_owned())),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        engine.init_without_bridge().await.unwrap();
+        engine
+            .evaluate_module(
+                JsModule::code("cached/entry".to_string(), source.to_string()),
+                None,
+            )
+            .await
+            .unwrap();
+        let value = engine
+            .eval(JsCode::Code("globalThis.cached".to_string()), None)
+            .await
+            .unwrap();
+        engine.close().await.unwrap();
+        value
+    };
+    let cache_files = || {
+        std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| std::fs::read(entry.unwrap().path()).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(matches!(
+        run("globalThis.cached = 1;").await,
+        JsValue::Integer(1)
+    ));
+    let first = cache_files();
+    assert_eq!(first.len(), 1);
+
+    assert!(matches!(
+        run("globalThis.cached = 1;").await,
+        JsValue::Integer(1)
+    ));
+    assert_eq!(cache_files(), first);
+
+    assert!(matches!(
+        run("globalThis.cached = 2;").await,
+        JsValue::Integer(2)
+    ));
+    let updated = cache_files();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert_eq!(updated.len(), 1);
+    assert_ne!(updated, first);
+}
+
+#[tokio::test]
+async fn test_engine_module_cache_store_rejects_corrupted_entries() {
+    use crate::api::engine::JsModuleCacheStore;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct SharedStore(Mutex<HashMap<String, Vec<u8>>>);
+
+    impl JsModuleCacheStore for SharedStore {
+        fn get(&self, id: &str) -> Option<Vec<u8>> {
+            self.0.lock().unwrap().get(id).cloned()
+        }
+
+        fn put(&self, id: &str, entry: Vec<u8>) {
+            self.0.lock().unwrap().insert(id.to_string(), entry);
+        }
+
+        fn remove(&self, id: &str) {
+            self.0.lock().unwrap().remove(id);
+        }
+    }
+
+    let store = Arc::new(SharedStore::default());
+    let run = async || {
+        let engine = JsEngine::create(None, None, None).await.unwrap();
+        engine.set_module_cache_store(store.clone()).await.unwrap();
+        engine.init_without_bridge().await.unwrap();
+        engine
+            .evaluate_module(
+                JsModule::code(
+                    "store/entry".to_string(),
+                    "globalThis.stored = 7;".to_string(),
+                ),
+                None,
+            )
+            .await
+            .unwrap();
+        let value = engine
+            .eval(JsCode::Code("globalThis.stored".to_string()), None)
+            .await
+            .unwrap();
+        engine.close().await.unwrap();
+        value
+    };
+
+    assert_eq!(run().await, JsValue::Integer(7));
+    let entry = store.0.lock().unwrap().values().next().cloned().unwrap();
+
+    // Flip a payload byte; the hash check must drop the entry and recompile.
+    for entry in store.0.lock().unwrap().values_mut() {
+        let last = entry.len() - 1;
+        entry[last] ^= 0xff;
+    }
+    assert_eq!(run().await, JsValue::Integer(7));
+    let rewritten = store.0.lock().unwrap().values().next().cloned().unwrap();
+    assert_eq!(rewritten, entry);
+}
+
+#[tokio::test]
+async fn test_engine_evaluate_signed_compressed_bytecode_bundle_file() {
+    use crate::api::source::{JsBytecodeBundleFileOptions, JsBytecodeCompression};
+
+    let signing_key = vec![7u8; 32];
+    let verifying_key = JsBytecode::bundle_verifying_key(signing_key.clone()).unwrap();
+    let file = JsBytecode::compile_module_bundle_file(
+        vec![
+            JsModule::code(
+                "bundle-file/index".to_string(),
+                "import { answer } from './shared'; globalThis.bundleAnswer = answer;".to_string(),
+            ),
+            JsModule::code(
+                "bundle-file/shared".to_string(),
+                "export const answer = 42;".to_string(),
+            ),
+        ],
+        Some("bundle-file/index".to_string()),
+        None,
+        Some(JsBytecodeBundleFileOptions {
+            compression: Some(JsBytecodeCompression::Zstd),
+            signing_key: Some(signing_key),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(file.starts_with(b"FJSB"));
+
+    let unpacked = JsBytecode::unpack_bundle_sync(file.clone(), None).unwrap();
+    assert_eq!(unpacked.entry.as_deref(), Some("bundle-file/index"));
+    assert_eq!(unpacked.modules.len(), 2);
+
+    let mut tampered = file.clone();
+    let middle = tampered.len() / 2;
+    tampered[middle] ^= 0xff;
+    assert!(JsBytecode::unpack_bundle_sync(tampered, Some(verifying_key.clone())).is_err());
+    let other_key = JsBytecode::bundle_verifying_key(vec![8u8; 32]).unwrap();
+    assert!(JsBytecode::unpack_bundle_sync(file.clone(), Some(other_key)).is_err());
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .evaluate_bytecode_bundle_file(file, Some(verifying_key))
+        .await
+        .unwrap();
+    let result = engine
+        .eval(JsCode::Code("globalThis.bundleAnswer".to_string()), None)
+        .await
+        .unwrap();
+    assert!(matches!(result, JsValue::Integer(42)));
+}
+
 #[tokio::test]
 async fn test_engine_compile_module_bytecode_roundtrip_declare_and_import() {
     let bytecode = JsBytecode::compile(
@@ -1420,19 +2708,269 @@ async fn test_engine_declare_new_bytecode_module_rejects_script_bytecode() {
     let engine = JsEngine::create(None, None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
-    let error = engine
-        .declare_new_bytecode_module(JsModuleBytecode::new(
-            "script-in-module-slot.js".to_string(),
-            script.bytes,
-        ))
-        .await
-        .unwrap_err();
+    let error = engine
+        .declare_new_bytecode_module(JsModuleBytecode::new(
+            "script-in-module-slot.js".to_string(),
+            script.bytes,
+        ))
+        .await
+        .unwrap_err();
+
+    assert!(error.to_string().contains("does not contain an ES module"));
+}
+
+#[tokio::test]
+async fn test_engine_dynamic_module_relative_import() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    engine
+        .declare_new_modules(vec![
+            JsModule::code(
+                "pkg/dep.js".to_string(),
+                "export const value = 42;".to_string(),
+            ),
+            JsModule::code(
+                "pkg/main.js".to_string(),
+                "import { value } from './dep.js'; export default value;".to_string(),
+            ),
+        ])
+        .await
+        .unwrap();
+
+    let result = engine
+        .eval(
+            JsCode::Code(
+                "const { default: value } = await import('pkg/main.js'); value".to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(result, JsValue::Integer(42)));
+}
+
+#[tokio::test]
+async fn rooted_relative_source_modules_resolve_from_root() {
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    engine
+        .declare_new_modules(vec![
+            JsModule::code(
+                "/pkg/dep.js".to_string(),
+                "export const value = 42;".to_string(),
+            ),
+            JsModule::code(
+                "/pkg/main.js".to_string(),
+                "import { value } from './dep.js'; export default value;".to_string(),
+            ),
+        ])
+        .await
+        .unwrap();
+
+    let result = engine
+        .eval(
+            JsCode::Code(
+                "const { default: value } = await import('/pkg/main.js'); value".to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(result, JsValue::Integer(42)));
+}
+
+#[tokio::test]
+async fn test_engine_dynamic_module_parent_relative_import() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    engine
+        .declare_new_modules(vec![
+            JsModule::code(
+                "pkg/dep.js".to_string(),
+                "export const value = 7;".to_string(),
+            ),
+            JsModule::code(
+                "pkg/nested/main.js".to_string(),
+                "import { value } from '../dep.js'; export default value;".to_string(),
+            ),
+        ])
+        .await
+        .unwrap();
+
+    let result = engine
+        .eval(
+            JsCode::Code(
+                "const { default: value } = await import('pkg/nested/main.js'); value".to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(matches!(result, JsValue::Integer(7)));
+}
+
+#[tokio::test]
+async fn test_engine_is_module_declared() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let module = JsModule::code(
+        "check-module".to_string(),
+        "export const x = 1;".to_string(),
+    );
+    engine.declare_new_module(module).await.unwrap();
+
+    let exists = engine
+        .is_module_declared("check-module".to_string())
+        .await
+        .unwrap();
+    assert!(exists);
+
+    let not_exists = engine
+        .is_module_declared("non-existent".to_string())
+        .await
+        .unwrap();
+    assert!(!not_exists);
+}
+
+#[tokio::test]
+async fn test_engine_get_declared_modules() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let modules = vec![
+        JsModule::code("mod-a".to_string(), "export const a = 1;".to_string()),
+        JsModule::code("mod-b".to_string(), "export const b = 2;".to_string()),
+    ];
+    engine.declare_new_modules(modules).await.unwrap();
+
+    let declared = engine.get_declared_modules().await.unwrap();
+    assert_eq!(declared.len(), 2);
+    assert_eq!(declared, vec!["mod-a".to_string(), "mod-b".to_string()]);
+    assert!(declared.contains(&"mod-a".to_string()));
+    assert!(declared.contains(&"mod-b".to_string()));
+}
+
+#[tokio::test]
+async fn test_engine_get_available_modules() {
+    let engine = JsEngine::create(
+        Some(JsBuiltinOptions {
+            path: Some(true),
+            https: Some(true),
+            ..Default::default()
+        }),
+        Some(vec![JsModule::code(
+            "static-extra".to_string(),
+            "export const value = 1;".to_string(),
+        )]),
+        None,
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "dynamic-extra".to_string(),
+            "export const value = 2;".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    let modules = engine.get_available_modules().await.unwrap();
+
+    assert!(modules.contains(&"dynamic-extra".to_string()));
+    assert!(modules.contains(&"https".to_string()));
+    assert!(modules.contains(&"path".to_string()));
+    assert!(modules.contains(&"static-extra".to_string()));
+}
+
+#[tokio::test]
+async fn test_engine_is_module_available() {
+    let engine = JsEngine::create(
+        Some(JsBuiltinOptions {
+            dgram: Some(true),
+            ..Default::default()
+        }),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    engine
+        .declare_new_module(JsModule::code(
+            "dynamic-extra".to_string(),
+            "export const value = 2;".to_string(),
+        ))
+        .await
+        .unwrap();
+
+    assert!(
+        engine
+            .is_module_available("dgram".to_string())
+            .await
+            .unwrap()
+    );
+    assert!(
+        engine
+            .is_module_available("dynamic-extra".to_string())
+            .await
+            .unwrap()
+    );
+    assert!(
+        !engine
+            .is_module_available("missing".to_string())
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_engine_clear_pending_modules() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let module = JsModule::code(
+        "clear-module".to_string(),
+        "export const x = 1;".to_string(),
+    );
+    engine.declare_new_module(module).await.unwrap();
+
+    let before = engine.get_declared_modules().await.unwrap();
+    assert!(!before.is_empty());
+
+    engine.clear_pending_modules().await.unwrap();
 
-    assert!(error.to_string().contains("does not contain an ES module"));
+    let after = engine.get_declared_modules().await.unwrap();
+    assert!(after.is_empty());
+
+    let result = engine
+        .eval(
+            JsCode::Code("await import('clear-module')".to_string()),
+            None,
+        )
+        .await;
+    assert!(result.is_err());
 }
 
 #[tokio::test]
-async fn test_engine_dynamic_module_relative_import() {
+async fn test_engine_clear_pending_modules_keeps_loaded_modules() {
     let runtime = JsAsyncRuntime::new().unwrap();
     let _context = JsAsyncContext::from(&runtime).await.unwrap();
     let engine = JsEngine::create(None, None, None).await.unwrap();
@@ -1441,650 +2979,1204 @@ async fn test_engine_dynamic_module_relative_import() {
     engine
         .declare_new_modules(vec![
             JsModule::code(
-                "pkg/dep.js".to_string(),
-                "export const value = 42;".to_string(),
+                "loaded-module".to_string(),
+                "export function value() { return 1; }".to_string(),
             ),
             JsModule::code(
-                "pkg/main.js".to_string(),
-                "import { value } from './dep.js'; export default value;".to_string(),
+                "pending-module".to_string(),
+                "export const value = 2;".to_string(),
             ),
         ])
         .await
         .unwrap();
 
     let result = engine
-        .eval(
-            JsCode::Code(
-                "const { default: value } = await import('pkg/main.js'); value".to_string(),
-            ),
-            None,
-        )
+        .call("loaded-module".to_string(), "value".to_string(), None)
         .await
         .unwrap();
+    assert!(matches!(result, JsValue::Integer(1)));
 
-    assert!(matches!(result, JsValue::Integer(42)));
+    engine.clear_pending_modules().await.unwrap();
+
+    let declared = engine.get_declared_modules().await.unwrap();
+    assert_eq!(declared, vec!["loaded-module".to_string()]);
+
+    let pending_exists = engine
+        .is_module_declared("pending-module".to_string())
+        .await
+        .unwrap();
+    assert!(!pending_exists);
+
+    let loaded_available = engine
+        .is_module_available("loaded-module".to_string())
+        .await
+        .unwrap();
+    assert!(loaded_available);
+
+    let pending_import = engine
+        .eval(
+            JsCode::Code("await import('pending-module')".to_string()),
+            None,
+        )
+        .await;
+    assert!(pending_import.is_err());
 }
 
 #[tokio::test]
-async fn rooted_relative_source_modules_resolve_from_root() {
+async fn test_engine_redeclare_loaded_module_fails() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
     let engine = JsEngine::create(None, None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
     engine
-        .declare_new_modules(vec![
-            JsModule::code(
-                "/pkg/dep.js".to_string(),
-                "export const value = 42;".to_string(),
-            ),
-            JsModule::code(
-                "/pkg/main.js".to_string(),
-                "import { value } from './dep.js'; export default value;".to_string(),
-            ),
-        ])
+        .declare_new_module(JsModule::code(
+            "sticky-module".to_string(),
+            "export function value() { return 1; }".to_string(),
+        ))
         .await
         .unwrap();
 
-    let result = engine
-        .eval(
-            JsCode::Code(
-                "const { default: value } = await import('/pkg/main.js'); value".to_string(),
-            ),
-            None,
-        )
+    let first = engine
+        .call("sticky-module".to_string(), "value".to_string(), None)
         .await
         .unwrap();
+    assert!(matches!(first, JsValue::Integer(1)));
 
-    assert!(matches!(result, JsValue::Integer(42)));
+    let redeclare = engine
+        .declare_new_module(JsModule::code(
+            "sticky-module".to_string(),
+            "export function value() { return 2; }".to_string(),
+        ))
+        .await;
+    assert!(redeclare.is_err());
+    assert!(
+        redeclare
+            .unwrap_err()
+            .to_string()
+            .contains("cannot be redefined")
+    );
+
+    let second = engine
+        .call("sticky-module".to_string(), "value".to_string(), None)
+        .await
+        .unwrap();
+    assert!(matches!(second, JsValue::Integer(1)));
 }
 
 #[tokio::test]
-async fn test_engine_dynamic_module_parent_relative_import() {
+async fn test_engine_evaluate_module_marks_module_as_loaded() {
     let runtime = JsAsyncRuntime::new().unwrap();
     let _context = JsAsyncContext::from(&runtime).await.unwrap();
     let engine = JsEngine::create(None, None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
-    engine
-        .declare_new_modules(vec![
-            JsModule::code(
-                "pkg/dep.js".to_string(),
-                "export const value = 7;".to_string(),
-            ),
+    let result = engine
+        .evaluate_module(
             JsModule::code(
-                "pkg/nested/main.js".to_string(),
-                "import { value } from '../dep.js'; export default value;".to_string(),
+                "evaluated-module".to_string(),
+                "export default 1;".to_string(),
             ),
-        ])
+            None,
+        )
         .await
         .unwrap();
+    assert!(matches!(result, JsValue::None));
+
+    let redeclare = engine
+        .declare_new_module(JsModule::code(
+            "evaluated-module".to_string(),
+            "export default 2;".to_string(),
+        ))
+        .await;
+    assert!(redeclare.is_err());
+    assert!(
+        redeclare
+            .unwrap_err()
+            .to_string()
+            .contains("cannot be redefined")
+    );
+}
+
+#[tokio::test]
+async fn test_engine_call_module_function() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let module = JsModule::code(
+        "math-utils".to_string(),
+        "export function add(a, b) { return a + b; }".to_string(),
+    );
+    engine.declare_new_module(module).await.unwrap();
 
     let result = engine
-        .eval(
-            JsCode::Code(
-                "const { default: value } = await import('pkg/nested/main.js'); value".to_string(),
-            ),
-            None,
+        .call(
+            "math-utils".to_string(),
+            "add".to_string(),
+            Some(vec![JsValue::Integer(3), JsValue::Integer(4)]),
+        )
+        .await;
+
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(matches!(value, JsValue::Integer(7)));
+}
+
+#[tokio::test]
+async fn test_engine_call_async_function() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let module = JsModule::code(
+        "async-utils".to_string(),
+        "export async function asyncAdd(a, b) { return a + b; }".to_string(),
+    );
+    engine.declare_new_module(module).await.unwrap();
+
+    let result = engine
+        .call(
+            "async-utils".to_string(),
+            "asyncAdd".to_string(),
+            Some(vec![JsValue::Integer(10), JsValue::Integer(20)]),
         )
+        .await;
+
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(matches!(value, JsValue::Integer(30)));
+}
+
+#[tokio::test]
+async fn test_engine_call_nonexistent_module() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let result = engine
+        .call("nonexistent".to_string(), "func".to_string(), None)
+        .await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_engine_call_nonexistent_function() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
+
+    let module = JsModule::code(
+        "has-func".to_string(),
+        "export function exists() { return 1; }".to_string(),
+    );
+    engine.declare_new_module(module).await.unwrap();
+
+    let result = engine
+        .call("has-func".to_string(), "notExists".to_string(), None)
+        .await;
+
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// Engine Bridge Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_engine_bridge_call() {
+    let runtime = JsAsyncRuntime::new().unwrap();
+    let _context = JsAsyncContext::from(&runtime).await.unwrap();
+    let engine = JsEngine::create(None, None, None).await.unwrap();
+
+    engine
+        .init(|value| {
+            Box::pin(async move {
+                // Double the integer if passed
+                match value {
+                    JsValue::Integer(n) => JsResult::Ok(JsValue::Integer(n * 2)),
+                    _ => JsResult::Ok(value),
+                }
+            })
+        })
         .await
         .unwrap();
 
-    assert!(matches!(result, JsValue::Integer(7)));
+    let result = engine
+        .eval(JsCode::Code("fjs.bridge_call(21)".to_string()), None)
+        .await;
+
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(matches!(value, JsValue::Integer(42)));
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_is_module_declared() {
+async fn test_engine_bridge_call_with_object() {
     let runtime = JsAsyncRuntime::new().unwrap();
     let _context = JsAsyncContext::from(&runtime).await.unwrap();
     let engine = JsEngine::create(None, None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
-
-    let module = JsModule::code(
-        "check-module".to_string(),
-        "export const x = 1;".to_string(),
-    );
-    engine.declare_new_module(module).await.unwrap();
 
-    let exists = engine
-        .is_module_declared("check-module".to_string())
+    engine
+        .init(|value| {
+            Box::pin(async move {
+                // Return the value as-is
+                JsResult::Ok(value)
+            })
+        })
         .await
         .unwrap();
-    assert!(exists);
 
-    let not_exists = engine
-        .is_module_declared("non-existent".to_string())
-        .await
-        .unwrap();
-    assert!(!not_exists);
+    let result = engine
+        .eval(
+            JsCode::Code("fjs.bridge_call({name: 'test', value: 42})".to_string()),
+            None,
+        )
+        .await;
+
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(value.is_object());
+    engine.close().await.unwrap();
 }
 
+// ============================================================================
+// Engine with Builtins Tests
+// ============================================================================
+
 #[tokio::test]
-async fn test_engine_get_declared_modules() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_with_console_builtin() {
+    let builtin = JsBuiltinOptions {
+        console: Some(true),
+        ..Default::default()
+    };
+    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
-    let modules = vec![
-        JsModule::code("mod-a".to_string(), "export const a = 1;".to_string()),
-        JsModule::code("mod-b".to_string(), "export const b = 2;".to_string()),
-    ];
-    engine.declare_new_modules(modules).await.unwrap();
-
-    let declared = engine.get_declared_modules().await.unwrap();
-    assert_eq!(declared.len(), 2);
-    assert_eq!(declared, vec!["mod-a".to_string(), "mod-b".to_string()]);
-    assert!(declared.contains(&"mod-a".to_string()));
-    assert!(declared.contains(&"mod-b".to_string()));
+    // Console should be available
+    let result = engine
+        .eval(
+            JsCode::Code("typeof console.log === 'function'".to_string()),
+            None,
+        )
+        .await;
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(matches!(value, JsValue::Boolean(true)));
 }
 
 #[tokio::test]
-async fn test_engine_get_available_modules() {
-    let engine = JsEngine::create(
-        Some(JsBuiltinOptions {
-            path: Some(true),
-            https: Some(true),
-            ..Default::default()
-        }),
-        Some(vec![JsModule::code(
-            "static-extra".to_string(),
-            "export const value = 1;".to_string(),
-        )]),
-        None,
-    )
-    .await
-    .unwrap();
+async fn test_engine_with_buffer_builtin() {
+    let builtin = JsBuiltinOptions {
+        buffer: Some(true),
+        ..Default::default()
+    };
+    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
-    engine
-        .declare_new_module(JsModule::code(
-            "dynamic-extra".to_string(),
-            "export const value = 2;".to_string(),
-        ))
-        .await
-        .unwrap();
 
-    let modules = engine.get_available_modules().await.unwrap();
-
-    assert!(modules.contains(&"dynamic-extra".to_string()));
-    assert!(modules.contains(&"https".to_string()));
-    assert!(modules.contains(&"path".to_string()));
-    assert!(modules.contains(&"static-extra".to_string()));
+    // Buffer should be available globally
+    let result = engine
+        .eval(
+            JsCode::Code("typeof Buffer !== 'undefined'".to_string()),
+            None,
+        )
+        .await;
+    assert!(result.is_ok());
 }
 
 #[tokio::test]
-async fn test_engine_is_module_available() {
-    let engine = JsEngine::create(
-        Some(JsBuiltinOptions {
-            dgram: Some(true),
-            ..Default::default()
-        }),
-        None,
-        None,
-    )
-    .await
-    .unwrap();
+async fn test_engine_with_url_builtin() {
+    let builtin = JsBuiltinOptions {
+        url: Some(true),
+        ..Default::default()
+    };
+    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
-    engine
-        .declare_new_module(JsModule::code(
-            "dynamic-extra".to_string(),
-            "export const value = 2;".to_string(),
-        ))
-        .await
-        .unwrap();
 
-    assert!(
-        engine
-            .is_module_available("dgram".to_string())
-            .await
-            .unwrap()
-    );
-    assert!(
-        engine
-            .is_module_available("dynamic-extra".to_string())
-            .await
-            .unwrap()
-    );
-    assert!(
-        !engine
-            .is_module_available("missing".to_string())
-            .await
-            .unwrap()
-    );
+    // URL should be available
+    let result = engine
+        .eval(
+            JsCode::Code("new URL('https://example.com').hostname".to_string()),
+            None,
+        )
+        .await;
+    assert!(result.is_ok());
+    let value = result.unwrap();
+    assert!(matches!(value, JsValue::String(s) if s == "example.com"));
 }
 
 #[tokio::test]
-async fn test_engine_clear_pending_modules() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_with_path_builtin() {
+    let builtin = JsBuiltinOptions {
+        path: Some(true),
+        ..Default::default()
+    };
+    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
-    let module = JsModule::code(
-        "clear-module".to_string(),
-        "export const x = 1;".to_string(),
-    );
-    engine.declare_new_module(module).await.unwrap();
-
-    let before = engine.get_declared_modules().await.unwrap();
-    assert!(!before.is_empty());
-
-    engine.clear_pending_modules().await.unwrap();
-
-    let after = engine.get_declared_modules().await.unwrap();
-    assert!(after.is_empty());
-
+    // Path module should be importable
     let result = engine
         .eval(
-            JsCode::Code("await import('clear-module')".to_string()),
+            JsCode::Code(
+                r#"
+                const { default: path } = await import('path');
+                path.join('a', 'b', 'c')
+            "#
+                .to_string(),
+            ),
             None,
         )
         .await;
-    assert!(result.is_err());
+    assert!(result.is_ok());
 }
 
 #[tokio::test]
-async fn test_engine_clear_pending_modules_keeps_loaded_modules() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_with_crypto_builtin() {
+    let builtin = JsBuiltinOptions {
+        crypto: Some(true),
+        ..Default::default()
+    };
+    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
-    engine
-        .declare_new_modules(vec![
-            JsModule::code(
-                "loaded-module".to_string(),
-                "export function value() { return 1; }".to_string(),
-            ),
-            JsModule::code(
-                "pending-module".to_string(),
-                "export const value = 2;".to_string(),
-            ),
-        ])
-        .await
-        .unwrap();
-
+    // Crypto should be available
     let result = engine
-        .call("loaded-module".to_string(), "value".to_string(), None)
-        .await
-        .unwrap();
-    assert!(matches!(result, JsValue::Integer(1)));
-
-    engine.clear_pending_modules().await.unwrap();
-
-    let declared = engine.get_declared_modules().await.unwrap();
-    assert_eq!(declared, vec!["loaded-module".to_string()]);
-
-    let pending_exists = engine
-        .is_module_declared("pending-module".to_string())
-        .await
-        .unwrap();
-    assert!(!pending_exists);
+        .eval(
+            JsCode::Code("typeof crypto !== 'undefined'".to_string()),
+            None,
+        )
+        .await;
+    assert!(result.is_ok());
+}
 
-    let loaded_available = engine
-        .is_module_available("loaded-module".to_string())
-        .await
-        .unwrap();
-    assert!(loaded_available);
+#[tokio::test]
+async fn test_engine_with_events_builtin() {
+    let builtin = JsBuiltinOptions {
+        events: Some(true),
+        ..Default::default()
+    };
+    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
+    engine.init_without_bridge().await.unwrap();
 
-    let pending_import = engine
+    // EventEmitter should be available
+    let result = engine
         .eval(
-            JsCode::Code("await import('pending-module')".to_string()),
+            JsCode::Code(
+                r#"
+                const { EventEmitter } = await import('events');
+                typeof EventEmitter === 'function'
+            "#
+                .to_string(),
+            ),
             None,
         )
         .await;
-    assert!(pending_import.is_err());
+    assert!(result.is_ok());
 }
 
 #[tokio::test]
-async fn test_engine_redeclare_loaded_module_fails() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_pause_freezes_intervals_until_resume() {
+    let engine = JsEngine::create(Some(JsBuiltinOptions::essential()), None, None)
+        .await
+        .unwrap();
     engine.init_without_bridge().await.unwrap();
-
     engine
-        .declare_new_module(JsModule::code(
-            "sticky-module".to_string(),
-            "export function value() { return 1; }".to_string(),
-        ))
+        .eval(
+            JsCode::Code(
+                "globalThis.ticks = 0; setInterval(() => { globalThis.ticks += 1; }, 50);"
+                    .to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
 
-    let first = engine
-        .call("sticky-module".to_string(), "value".to_string(), None)
+    engine.pause().await.unwrap();
+    engine.pause().await.unwrap();
+    let paused = engine
+        .eval(JsCode::Code("ticks".to_string()), None)
         .await
-        .unwrap();
-    assert!(matches!(first, JsValue::Integer(1)));
-
-    let redeclare = engine
-        .declare_new_module(JsModule::code(
-            "sticky-module".to_string(),
-            "export function value() { return 2; }".to_string(),
-        ))
-        .await;
-    assert!(redeclare.is_err());
+        .unwrap_err();
+    assert!(paused.to_string().contains("Engine is paused"), "{paused}");
+    assert!(engine.create_realm(None).await.is_err());
+    tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+
+    engine.resume().await.unwrap();
+    let before = match engine.eval(JsCode::Code("ticks".to_string()), None).await {
+        Ok(JsValue::Integer(ticks)) => ticks,
+        other => panic!("unexpected result: {other:?}"),
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+    let after = match engine.eval(JsCode::Code("ticks".to_string()), None).await {
+        Ok(JsValue::Integer(ticks)) => ticks,
+        other => panic!("unexpected result: {other:?}"),
+    };
     assert!(
-        redeclare
-            .unwrap_err()
-            .to_string()
-            .contains("cannot be redefined")
+        after - before <= 1,
+        "interval burst on resume: {before} -> {after}"
     );
-
-    let second = engine
-        .call("sticky-module".to_string(), "value".to_string(), None)
-        .await
-        .unwrap();
-    assert!(matches!(second, JsValue::Integer(1)));
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_evaluate_module_marks_module_as_loaded() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_manual_event_loop_reports_pumped_turns() {
+    let engine = JsEngine::create(
+        Some(JsBuiltinOptions::essential()),
+        None,
+        Some(crate::api::engine::JsEngineRuntimeOptions {
+            manual_event_loop: Some(true),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
     engine.init_without_bridge().await.unwrap();
-
-    let result = engine
-        .evaluate_module(JsModule::code(
-            "evaluated-module".to_string(),
-            "export default 1;".to_string(),
-        ))
+    engine
+        .eval(
+            JsCode::Code(
+                "globalThis.fired = false; setTimeout(() => { globalThis.fired = true; }, 20);"
+                    .to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
-    assert!(matches!(result, JsValue::None));
 
-    let redeclare = engine
-        .declare_new_module(JsModule::code(
-            "evaluated-module".to_string(),
-            "export default 2;".to_string(),
-        ))
-        .await;
-    assert!(redeclare.is_err());
+    tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+    let turn = engine.run_pending_jobs(None, Some(100)).await.unwrap();
+    assert!(turn.jobs_run >= 1, "{turn:?}");
+    assert_eq!(turn.next_timer_delay_ms, None);
+    let fired = engine.eval(JsCode::Code("fired".to_string()), None).await;
+    assert!(matches!(fired, Ok(JsValue::Boolean(true))), "{fired:?}");
+
+    engine
+        .eval(
+            JsCode::Code("setTimeout(() => {}, 10000);".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+    let turn = engine.run_pending_jobs(Some(1), None).await.unwrap();
+    assert!(turn.has_pending_work);
     assert!(
-        redeclare
-            .unwrap_err()
-            .to_string()
-            .contains("cannot be redefined")
+        matches!(turn.next_timer_delay_ms, Some(delay) if delay > 5000),
+        "{turn:?}"
     );
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_call_module_function() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
+async fn test_engine_deterministic_mode_uses_virtual_clock_and_seed() {
+    async fn create(seed: u64) -> JsEngine {
+        let engine = JsEngine::create(
+            Some(JsBuiltinOptions::essential()),
+            None,
+            Some(crate::api::engine::JsEngineRuntimeOptions {
+                deterministic: Some(crate::api::engine::JsDeterministicOptions {
+                    seed,
+                    start_time_ms: Some(1_000_000),
+                }),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+        engine.init_without_bridge().await.unwrap();
+        engine
+    }
+    async fn eval(engine: &JsEngine, code: &str) -> JsValue {
+        engine
+            .eval(JsCode::Code(code.to_string()), None)
+            .await
+            .unwrap()
+    }
 
-    let module = JsModule::code(
-        "math-utils".to_string(),
-        "export function add(a, b) { return a + b; }".to_string(),
+    let engine = create(7).await;
+    let draws = "[Math.random(), Math.random(), Math.random()]";
+    assert_eq!(
+        eval(&engine, draws).await,
+        eval(&create(7).await, draws).await
+    );
+    assert_ne!(
+        eval(&engine, draws).await,
+        eval(&create(8).await, draws).await
     );
-    engine.declare_new_module(module).await.unwrap();
 
-    let result = engine
-        .call(
-            "math-utils".to_string(),
-            "add".to_string(),
-            Some(vec![JsValue::Integer(3), JsValue::Integer(4)]),
+    eval(
+        &engine,
+        r#"
+            globalThis.log = [];
+            setTimeout(() => log.push(`timeout@${Date.now()}`), 250);
+            const id = setInterval(() => {
+                log.push(`interval@${performance.now()}`);
+                Promise.resolve().then(() => log.push("microtask"));
+            }, 100);
+            setTimeout(() => clearInterval(id), 350);
+        "#,
+    )
+    .await;
+    assert_eq!(
+        eval(
+            &engine,
+            "Date.now() === 1000000 && new Date().getTime() === 1000000 && log.length === 0",
         )
-        .await;
-
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert!(matches!(value, JsValue::Integer(7)));
-}
+        .await,
+        JsValue::Boolean(true)
+    );
 
-#[tokio::test]
-async fn test_engine_call_async_function() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
+    engine.advance_time(1_000).await.unwrap();
+    let log = eval(&engine, "log.join(',')").await;
+    assert_eq!(
+        log,
+        JsValue::String(
+            "interval@100,microtask,interval@200,microtask,timeout@1000250,interval@300,microtask"
+                .to_string()
+        )
+    );
 
-    let module = JsModule::code(
-        "async-utils".to_string(),
-        "export async function asyncAdd(a, b) { return a + b; }".to_string(),
+    engine.set_time(5_000).await.unwrap();
+    assert_eq!(
+        eval(&engine, "Date.now() === 5000 && performance.now() === 1000").await,
+        JsValue::Boolean(true)
     );
-    engine.declare_new_module(module).await.unwrap();
 
-    let result = engine
-        .call(
-            "async-utils".to_string(),
-            "asyncAdd".to_string(),
-            Some(vec![JsValue::Integer(10), JsValue::Integer(20)]),
+    eval(
+        &engine,
+        r#"
+            globalThis.later = [];
+            const timers = await import('timers');
+            setImmediate(() => later.push(`immediate@${performance.now()}`));
+            timers.setTimeout(() => later.push(`module@${performance.now()}`), 10);
+        "#,
+    )
+    .await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(
+        eval(
+            &engine,
+            "later.length === 0 && typeof globalThis.__fjs_clock__ === 'undefined'"
         )
-        .await;
-
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert!(matches!(value, JsValue::Integer(30)));
+        .await,
+        JsValue::Boolean(true)
+    );
+    engine.advance_time(10).await.unwrap();
+    assert_eq!(
+        eval(&engine, "later.join(',')").await,
+        JsValue::String("immediate@1000,module@1010".to_string())
+    );
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_call_nonexistent_module() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
-
-    let result = engine
-        .call("nonexistent".to_string(), "func".to_string(), None)
-        .await;
-
-    assert!(result.is_err());
-}
+async fn test_engine_records_bridge_traffic_and_replays_without_dart() {
+    use crate::api::engine::{JsBatchCall, JsInvocation};
+    use crate::api::recording::{JsRecordedEvent, JsRecording};
+    use crate::api::source::{JsEvalOptions, JsModuleEvalOptions};
+    use std::collections::HashMap;
 
-#[tokio::test]
-async fn test_engine_call_nonexistent_function() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
+    let module = || {
+        JsModule::code(
+            "plugin".to_string(),
+            "export async function lookup(key) { return (await fjs.bridge_call({ key })) + '!'; }"
+                .to_string(),
+        )
+    };
+    let engine = JsEngine::create(None, Some(vec![module()]), None)
+        .await
+        .unwrap();
+    engine
+        .init(|value| {
+            Box::pin(async move {
+                match value {
+                    JsValue::Object(fields) if fields.get("key") == Some(&JsValue::from("a")) => {
+                        JsResult::Ok(JsValue::from("alpha"))
+                    }
+                    _ => JsResult::Err(crate::api::error::JsError::bridge("unknown key")),
+                }
+            })
+        })
+        .await
+        .unwrap();
 
-    let module = JsModule::code(
-        "has-func".to_string(),
-        "export function exists() { return 1; }".to_string(),
+    engine.start_recording().await.unwrap();
+    assert!(engine.start_recording().await.is_err());
+    let found = engine
+        .call(
+            "plugin".to_string(),
+            "lookup".to_string(),
+            Some(vec![JsValue::from("a")]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(found, JsValue::from("alpha!"));
+    assert!(
+        engine
+            .eval(
+                JsCode::Code("fjs.bridge_call({ key: 'b' })".to_string()),
+                None
+            )
+            .await
+            .is_err()
     );
-    engine.declare_new_module(module).await.unwrap();
+    let bound = engine
+        .eval(
+            JsCode::Code("key + '?'".to_string()),
+            Some(JsEvalOptions {
+                bindings: Some(HashMap::from([("key".to_string(), JsValue::from("k"))])),
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(bound, JsValue::from("k?"));
+    engine
+        .evaluate_module(
+            JsModule::code("extra".to_string(), "export const n = 2;".to_string()),
+            Some(JsModuleEvalOptions {
+                return_exports: Some(true),
+            }),
+        )
+        .await
+        .unwrap();
+    let batch = engine
+        .call_batch(
+            vec![JsBatchCall {
+                module: "plugin".to_string(),
+                method: "lookup".to_string(),
+                params: Some(vec![JsValue::from("a")]),
+            }],
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(batch[0].is_ok());
+    let max = engine
+        .invoke(JsInvocation {
+            path: "Math.max".to_string(),
+            params: Some(vec![JsValue::Integer(1), JsValue::Integer(3)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(max, JsValue::Integer(3));
+    let recording = engine.stop_recording().await.unwrap();
+    engine.close().await.unwrap();
 
-    let result = engine
-        .call("has-func".to_string(), "notExists".to_string(), None)
-        .await;
+    assert_eq!(recording.events.len(), 9);
+    assert!(matches!(recording.events[0], JsRecordedEvent::Call { .. }));
+    assert!(matches!(
+        recording.events[1],
+        JsRecordedEvent::BridgeCall { .. }
+    ));
 
-    assert!(result.is_err());
+    let recording = JsRecording::from_json(recording.to_json()).unwrap();
+    let replay = JsEngine::create(None, Some(vec![module()]), None)
+        .await
+        .unwrap();
+    let steps = replay.replay(recording).await.unwrap();
+    assert_eq!(steps.len(), 6);
+    assert!(steps.iter().all(|step| !step.diverged()), "{steps:?}");
+    replay.close().await.unwrap();
 }
 
-// ============================================================================
-// Engine Bridge Tests
-// ============================================================================
-
 #[tokio::test]
-async fn test_engine_bridge_call() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_bridge_timeouts_and_in_flight_limit() {
+    use crate::api::engine::{JsBridgeOptions, JsBridgeOverflowPolicy, JsEngineRuntimeOptions};
 
+    let engine = JsEngine::create(
+        None,
+        None,
+        Some(JsEngineRuntimeOptions {
+            bridge: Some(JsBridgeOptions {
+                timeout_ms: Some(100),
+                max_in_flight: Some(1),
+                on_overflow: Some(JsBridgeOverflowPolicy::Reject),
+            }),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
     engine
         .init(|value| {
             Box::pin(async move {
-                // Double the integer if passed
-                match value {
-                    JsValue::Integer(n) => JsResult::Ok(JsValue::Integer(n * 2)),
-                    _ => JsResult::Ok(value),
+                if let JsValue::Integer(ms) = value {
+                    tokio::time::sleep(std::time::Duration::from_millis(ms as u64)).await;
                 }
+                JsResult::Ok(value)
             })
         })
         .await
         .unwrap();
 
     let result = engine
-        .eval(JsCode::Code("fjs.bridge_call(21)".to_string()), None)
-        .await;
+        .eval(
+            JsCode::Code(
+                r#"
+                    (async () => {
+                        const settle = (promise) =>
+                            promise.then((value) => `ok:${value}`, (error) => `err:${error.message}`);
+                        const slow = await settle(fjs.bridge_call(1000));
+                        const overridden = await settle(fjs.bridge_call(150, { timeoutMs: 500 }));
+                        const [first, second] = await Promise.all([
+                            settle(fjs.bridge_call(20)),
+                            settle(fjs.bridge_call(20)),
+                        ]);
+                        let huge;
+                        try {
+                            fjs.bridge_call(1, { timeoutMs: 1e300 });
+                            huge = "accepted";
+                        } catch (error) {
+                            huge = `err:${error.message}`;
+                        }
+                        return [slow, overridden, first, second, huge].join("|");
+                    })()
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
 
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert!(matches!(value, JsValue::Integer(42)));
+    let JsValue::String(result) = result else {
+        panic!("unexpected result: {result:?}");
+    };
+    let parts: Vec<&str> = result.split('|').collect();
+    assert!(parts[0].contains("timed out after 100 ms"), "{result}");
+    assert_eq!(parts[1], "ok:150");
+    assert_eq!(parts[2], "ok:20");
+    assert!(
+        parts[3].contains("Too many bridge calls in flight"),
+        "{result}"
+    );
+    assert!(parts[4].contains("timeoutMs must be"), "{result}");
     engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_bridge_call_with_object() {
-    let runtime = JsAsyncRuntime::new().unwrap();
-    let _context = JsAsyncContext::from(&runtime).await.unwrap();
-    let engine = JsEngine::create(None, None, None).await.unwrap();
+async fn test_engine_trace_context_follows_async_chain_into_bridge() {
+    use crate::api::engine::JsEngineRuntimeOptions;
+    use std::collections::HashMap;
 
+    let engine = JsEngine::create(
+        None,
+        None,
+        Some(JsEngineRuntimeOptions {
+            trace_context: Some(true),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
     engine
-        .init(|value| {
+        .init_with_traced_bridge(|value, trace| {
             Box::pin(async move {
-                // Return the value as-is
-                JsResult::Ok(value)
+                let trace_id = match trace {
+                    Some(JsValue::Object(fields)) => fields.get("traceId").cloned(),
+                    _ => None,
+                };
+                JsResult::Ok(JsValue::Array(vec![
+                    value,
+                    trace_id.unwrap_or(JsValue::from("none")),
+                ]))
             })
         })
         .await
         .unwrap();
 
-    let result = engine
-        .eval(
-            JsCode::Code("fjs.bridge_call({name: 'test', value: 42})".to_string()),
+    // The context reaches Dart next to the argument; objects and primitives
+    // arrive unchanged.
+    let source = r#"
+        (async () => {
+            const id = () => traceContext.getStore()?.traceId ?? "none";
+            const seen = [id()];
+            await null;
+            seen.push(id());
+            seen.push(await new Promise((resolve) => setTimeout(() => resolve(id()), 5)));
+            const [payload, fromObject] = await fjs.bridge_call({ op: "load" });
+            seen.push(Object.keys(payload).join(",") === "op" ? fromObject : "mutated");
+            const [text, fromString] = await fjs.bridge_call("load");
+            seen.push(text === "load" ? fromString : "mutated");
+            return seen.join("|");
+        })()
+    "#;
+    let traced = engine
+        .eval_with_trace_context(
+            JsCode::Code(source.to_string()),
             None,
+            HashMap::from([("traceId".to_string(), JsValue::String("abc".to_string()))]),
         )
-        .await;
+        .await
+        .unwrap();
+    assert_eq!(traced, JsValue::String("abc|abc|abc|abc|abc".to_string()));
 
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert!(value.is_object());
+    let untraced = engine
+        .eval(JsCode::Code(source.to_string()), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        untraced,
+        JsValue::String("none|none|none|none|none".to_string())
+    );
     engine.close().await.unwrap();
-}
 
-// ============================================================================
-// Engine with Builtins Tests
-// ============================================================================
+    let plain = JsEngine::create(None, None, None).await.unwrap();
+    plain
+        .init(|value| Box::pin(async move { JsResult::Ok(value) }))
+        .await
+        .unwrap();
+    let error = plain
+        .eval_with_trace_context(JsCode::Code("1".to_string()), None, HashMap::new())
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("Trace context is not enabled"));
+    plain.close().await.unwrap();
+}
 
 #[tokio::test]
-async fn test_engine_with_console_builtin() {
-    let builtin = JsBuiltinOptions {
-        console: Some(true),
-        ..Default::default()
-    };
-    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
+async fn test_engine_async_stack_traces_span_await_boundaries() {
+    use crate::api::engine::JsEngineRuntimeOptions;
 
-    // Console should be available
-    let result = engine
-        .eval(
-            JsCode::Code("typeof console.log === 'function'".to_string()),
-            None,
-        )
-        .await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert!(matches!(value, JsValue::Boolean(true)));
+    let module = JsModule::code(
+        "api".to_string(),
+        r#"
+            async function inner() {
+                await null;
+                throw new Error("boom");
+            }
+            export async function load() {
+                await inner();
+            }
+        "#
+        .to_string(),
+    );
+    let engine = JsEngine::create(
+        None,
+        Some(vec![module]),
+        Some(JsEngineRuntimeOptions {
+            async_stack_traces: Some(true),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    engine
+        .init(|value| Box::pin(async move { JsResult::Ok(value) }))
+        .await
+        .unwrap();
+
+    let error = engine
+        .call("api".to_string(), "load".to_string(), None)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("boom"), "{error}");
+    assert!(error.contains("--- await ---"), "{error}");
+    assert!(error.contains("at load"), "{error}");
+    assert!(error.contains("at <call api.load>"), "{error}");
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_with_buffer_builtin() {
-    let builtin = JsBuiltinOptions {
-        buffer: Some(true),
-        ..Default::default()
+async fn test_engine_call_batch_reports_per_item_results() {
+    use crate::api::engine::JsBatchCall;
+
+    let module = JsModule::code(
+        "store".to_string(),
+        r#"
+            let release;
+            const gate = new Promise((resolve) => { release = resolve; });
+            export const title = () => "cart";
+            export const double = (n) => n * 2;
+            export async function wait() { return `released:${await gate}`; }
+            export function open(value) { release(value); return "opened"; }
+        "#
+        .to_string(),
+    );
+    let engine = JsEngine::create(None, Some(vec![module]), None)
+        .await
+        .unwrap();
+    engine
+        .init(|value| Box::pin(async move { JsResult::Ok(value) }))
+        .await
+        .unwrap();
+    let call = |module: &str, method: &str, params: Option<Vec<JsValue>>| JsBatchCall {
+        module: module.to_string(),
+        method: method.to_string(),
+        params,
     };
-    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
 
-    // Buffer should be available globally
-    let result = engine
-        .eval(
-            JsCode::Code("typeof Buffer !== 'undefined'".to_string()),
+    let results = engine
+        .call_batch(
+            vec![
+                call("store", "title", None),
+                call("store", "double", Some(vec![JsValue::Integer(21)])),
+                call("store", "missing", None),
+                call("nowhere", "title", None),
+            ],
             None,
         )
-        .await;
-    assert!(result.is_ok());
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        results[0].clone().into_result().unwrap(),
+        JsValue::from("cart")
+    );
+    assert_eq!(
+        results[1].clone().into_result().unwrap(),
+        JsValue::Integer(42)
+    );
+    assert!(results[2].is_err());
+    assert!(results[3].is_err());
+
+    // The first call only settles once the second one has started.
+    let results = engine
+        .call_batch(
+            vec![
+                call("store", "wait", None),
+                call("store", "open", Some(vec![JsValue::from("go")])),
+            ],
+            Some(true),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        results[0].clone().into_result().unwrap(),
+        JsValue::from("released:go")
+    );
+    assert_eq!(
+        results[1].clone().into_result().unwrap(),
+        JsValue::from("opened")
+    );
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_with_url_builtin() {
-    let builtin = JsBuiltinOptions {
-        url: Some(true),
+async fn test_engine_invoke_resolves_paths_constructors_and_generators() {
+    use crate::api::engine::{JsInvocation, JsThisBinding};
+
+    let module = JsModule::code(
+        "geo".to_string(),
+        r#"
+            export class Point {
+                constructor(x, y) { this.x = x; this.y = y; }
+            }
+            export const api = { users: { prefix: "user:", get(id) { return this.prefix + id; } } };
+            export const named = { name: "named" };
+            export default { greet: (name) => `hi ${name}` };
+            export async function* ticks(n) {
+                for (let i = 0; i < n; i++) { await null; yield i; }
+            }
+            export function whoami() { return this?.name ?? "none"; }
+        "#
+        .to_string(),
+    );
+    let engine = JsEngine::create(None, Some(vec![module]), None)
+        .await
+        .unwrap();
+    engine
+        .init(|value| Box::pin(async move { JsResult::Ok(value) }))
+        .await
+        .unwrap();
+    let geo = |path: &str| JsInvocation {
+        module: Some("geo".to_string()),
+        path: path.to_string(),
         ..Default::default()
     };
-    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
 
-    // URL should be available
-    let result = engine
+    let user = engine
+        .invoke(JsInvocation {
+            params: Some(vec![JsValue::Integer(7)]),
+            ..geo("api.users.get")
+        })
+        .await
+        .unwrap();
+    assert_eq!(user, JsValue::from("user:7"));
+
+    let greeting = engine
+        .invoke(JsInvocation {
+            params: Some(vec![JsValue::from("bob")]),
+            ..geo("default.greet")
+        })
+        .await
+        .unwrap();
+    assert_eq!(greeting, JsValue::from("hi bob"));
+
+    let JsValue::Object(point) = engine
+        .invoke(JsInvocation {
+            params: Some(vec![JsValue::Integer(1), JsValue::Integer(2)]),
+            construct: Some(true),
+            ..geo("Point")
+        })
+        .await
+        .unwrap()
+    else {
+        panic!("constructor did not return an object");
+    };
+    assert_eq!(point.get("y"), Some(&JsValue::Integer(2)));
+
+    let ticks = engine
+        .invoke(JsInvocation {
+            params: Some(vec![JsValue::Integer(3)]),
+            ..geo("ticks")
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        ticks,
+        JsValue::Array(vec![
+            JsValue::Integer(0),
+            JsValue::Integer(1),
+            JsValue::Integer(2)
+        ])
+    );
+
+    let this_value = engine
+        .invoke(JsInvocation {
+            this_binding: Some(JsThisBinding::Value(JsValue::Object(
+                [("name".to_string(), JsValue::from("dart"))].into(),
+            ))),
+            ..geo("whoami")
+        })
+        .await
+        .unwrap();
+    assert_eq!(this_value, JsValue::from("dart"));
+    let this_path = engine
+        .invoke(JsInvocation {
+            this_binding: Some(JsThisBinding::Path("named".to_string())),
+            ..geo("whoami")
+        })
+        .await
+        .unwrap();
+    assert_eq!(this_path, JsValue::from("named"));
+
+    engine
         .eval(
-            JsCode::Code("new URL('https://example.com').hostname".to_string()),
+            JsCode::Code("globalThis.tools = { twice: (n) => n * 2 }".to_string()),
             None,
         )
-        .await;
-    assert!(result.is_ok());
-    let value = result.unwrap();
-    assert!(matches!(value, JsValue::String(s) if s == "example.com"));
+        .await
+        .unwrap();
+    let twice = engine
+        .invoke(JsInvocation {
+            path: "tools.twice".to_string(),
+            params: Some(vec![JsValue::Integer(4)]),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(twice, JsValue::Integer(8));
+
+    assert!(engine.invoke(geo("api.nope")).await.is_err());
+    assert!(
+        engine
+            .invoke(JsInvocation {
+                construct: Some(true),
+                this_binding: Some(JsThisBinding::Owner),
+                ..geo("Point")
+            })
+            .await
+            .is_err()
+    );
+    engine.close().await.unwrap();
 }
 
 #[tokio::test]
-async fn test_engine_with_path_builtin() {
-    let builtin = JsBuiltinOptions {
-        path: Some(true),
-        ..Default::default()
-    };
-    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
+async fn test_engine_evaluate_module_returns_exports_and_lists_kinds() {
+    use crate::api::module::{JsModuleExport, JsModuleExportKind};
+    use crate::api::source::JsModuleEvalOptions;
+
+    let engine = JsEngine::create(None, None, None).await.unwrap();
     engine.init_without_bridge().await.unwrap();
 
-    // Path module should be importable
-    let result = engine
-        .eval(
-            JsCode::Code(
+    let JsValue::Object(exports) = engine
+        .evaluate_module(
+            JsModule::code(
+                "shapes".to_string(),
                 r#"
-                const { default: path } = await import('path');
-                path.join('a', 'b', 'c')
-            "#
+                    export const sides = 4;
+                    export class Square {}
+                    export function area(side) { return side * side; }
+                    export async function load() { return sides; }
+                    export function* corners() { yield* [1, 2, 3, 4]; }
+                    export default { name: "shapes" };
+                "#
                 .to_string(),
             ),
-            None,
+            Some(JsModuleEvalOptions {
+                return_exports: Some(true),
+            }),
         )
-        .await;
-    assert!(result.is_ok());
-}
-
-#[tokio::test]
-async fn test_engine_with_crypto_builtin() {
-    let builtin = JsBuiltinOptions {
-        crypto: Some(true),
-        ..Default::default()
+        .await
+        .unwrap()
+    else {
+        panic!("exports were not returned as an object");
     };
-    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
+    assert_eq!(exports.get("sides"), Some(&JsValue::Integer(4)));
+    assert_eq!(
+        exports.get("area"),
+        Some(&JsValue::Function("area".to_string()))
+    );
+    assert!(matches!(exports.get("default"), Some(JsValue::Object(_))));
 
-    // Crypto should be available
-    let result = engine
-        .eval(
-            JsCode::Code("typeof crypto !== 'undefined'".to_string()),
-            None,
+    let area = engine
+        .call(
+            "shapes".to_string(),
+            "area".to_string(),
+            Some(vec![JsValue::Integer(3)]),
         )
-        .await;
-    assert!(result.is_ok());
-}
+        .await
+        .unwrap();
+    assert_eq!(area, JsValue::Integer(9));
 
-#[tokio::test]
-async fn test_engine_with_events_builtin() {
-    let builtin = JsBuiltinOptions {
-        events: Some(true),
-        ..Default::default()
+    let kinds = engine.module_exports("shapes".to_string()).await.unwrap();
+    let export = |name: &str, kind| JsModuleExport {
+        name: name.to_string(),
+        kind,
     };
-    let engine = JsEngine::create(Some(builtin), None, None).await.unwrap();
-    engine.init_without_bridge().await.unwrap();
+    assert_eq!(
+        kinds,
+        vec![
+            export("Square", JsModuleExportKind::Class),
+            export("area", JsModuleExportKind::Function),
+            export("corners", JsModuleExportKind::GeneratorFunction),
+            export("default", JsModuleExportKind::Value),
+            export("load", JsModuleExportKind::AsyncFunction),
+            export("sides", JsModuleExportKind::Value),
+        ]
+    );
+    assert!(engine.module_exports("missing".to_string()).await.is_err());
 
-    // EventEmitter should be available
-    let result = engine
-        .eval(
-            JsCode::Code(
-                r#"
-                const { EventEmitter } = await import('events');
-                typeof EventEmitter === 'function'
-            "#
-                .to_string(),
-            ),
-            None,
-        )
-        .await;
-    assert!(result.is_ok());
+    // Listing exports never runs a module for the first time.
+    engine
+        .declare_new_module(JsModule::code(
+            "unloaded".to_string(),
+            "globalThis.unloadedRan = true; export const x = 1;".to_string(),
+        ))
+        .await
+        .unwrap();
+    let unloaded = engine.module_exports("unloaded".to_string()).await;
+    assert!(matches!(
+        unloaded,
+        Err(crate::api::error::JsError::Module { .. })
+    ));
+    assert_eq!(
+        engine
+            .eval(
+                JsCode::Code("globalThis.unloadedRan ?? false".to_string()),
+                None
+            )
+            .await
+            .unwrap(),
+        JsValue::Boolean(false)
+    );
+    engine.close().await.unwrap();
 }
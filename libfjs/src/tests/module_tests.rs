@@ -3,7 +3,7 @@
 //! Tests for the JavaScript module system including dynamic loading,
 //! resolution, and module builder functionality.
 
-use crate::api::source::{JsCode, JsModule};
+use crate::api::source::{JsCode, JsModule, JsModuleKind};
 
 // ============================================================================
 // JsCode Tests
@@ -119,6 +119,32 @@ fn test_jsmodule_bytes() {
     assert!(module.source.is_bytes());
 }
 
+#[test]
+fn test_jsmodule_typed_constructors() {
+    assert_eq!(
+        JsModule::code("a".to_string(), "x".to_string()).kind,
+        JsModuleKind::JavaScript
+    );
+    assert_eq!(
+        JsModule::json("a.json".to_string(), "{}".to_string()).kind,
+        JsModuleKind::Json
+    );
+    assert_eq!(
+        JsModule::text("a.txt".to_string(), "x".to_string()).kind,
+        JsModuleKind::Text
+    );
+    assert_eq!(
+        JsModule::raw_bytes("a.bin".to_string(), vec![1]).kind,
+        JsModuleKind::Bytes
+    );
+    assert_eq!(JsModuleKind::Json.import_type().as_deref(), Some("json"));
+    assert_eq!(
+        JsModuleKind::from_import_type("bytes"),
+        Some(JsModuleKind::Bytes)
+    );
+    assert_eq!(JsModuleKind::from_import_type("css"), None);
+}
+
 #[test]
 fn test_jsmodule_clone() {
     let module = JsModule::code("test".to_string(), "code".to_string());
@@ -300,6 +326,24 @@ fn test_dynamic_module_resolver_found() {
     });
 }
 
+#[test]
+fn test_dynamic_module_resolver_requires_matching_type() {
+    test_with(|ctx| {
+        let modules = Arc::new(RwLock::new(HashMap::<String, DynamicModuleEntry>::new()));
+        modules.write().unwrap().insert(
+            "config.json".to_string(),
+            DynamicModuleEntry::Json(b"{}".to_vec()),
+        );
+        let _ = ctx.store_userdata(modules);
+
+        let mut resolver = DynamicModuleResolver::default();
+        let error = resolver
+            .resolve(&ctx, ".", "config.json", None)
+            .unwrap_err();
+        assert!(error.to_string().contains("type 'json'"));
+    });
+}
+
 #[test]
 fn test_dynamic_module_loader_not_found() {
     test_with(|ctx| {
@@ -326,6 +370,84 @@ fn test_dynamic_module_loader_found() {
     });
 }
 
+#[test]
+fn test_dynamic_module_public_name_strips_reload_generation() {
+    use crate::api::module::dynamic_module_public_name;
+
+    assert_eq!(dynamic_module_public_name("app"), "app");
+    assert_eq!(dynamic_module_public_name("app?fjs-reload=3"), "app");
+    assert_eq!(
+        dynamic_module_public_name("app?fjs-reload=x"),
+        "app?fjs-reload=x"
+    );
+}
+
+#[test]
+fn test_dynamic_module_loader_json_entry() {
+    test_with(|ctx| {
+        let modules = Arc::new(RwLock::new(HashMap::<String, DynamicModuleEntry>::new()));
+        modules.write().unwrap().insert(
+            "config.json".to_string(),
+            DynamicModuleEntry::Json(br#"{"answer": 42, "quote": "a\"b"}"#.to_vec()),
+        );
+        let _ = ctx.store_userdata(modules);
+
+        let mut loader = DynamicModuleLoader::default();
+        let module = loader.load(&ctx, "config.json", None).unwrap();
+        let (module, promise) = module.eval().unwrap();
+        promise.finish::<()>().unwrap();
+        let config: rquickjs::Object = module.get("default").unwrap();
+        assert_eq!(config.get::<_, i32>("answer").unwrap(), 42);
+        assert_eq!(config.get::<_, String>("quote").unwrap(), "a\"b");
+    });
+}
+
+#[test]
+fn test_module_source_code_wraps_text_and_bytes() {
+    use crate::api::module::module_source_code;
+
+    let text = module_source_code("a.txt", JsModuleKind::Text, "line\n\"q\"".as_bytes()).unwrap();
+    assert_eq!(
+        String::from_utf8(text).unwrap(),
+        "export default \"line\\n\\\"q\\\"\";\n"
+    );
+    let js = module_source_code("a.js", JsModuleKind::JavaScript, b"export {}").unwrap();
+    assert_eq!(js, b"export {}");
+    assert!(module_source_code("a.json", JsModuleKind::Json, &[0xff]).is_err());
+
+    test_with(|ctx| {
+        for len in 0..=7 {
+            let bytes: Vec<u8> = (0..len).map(|i| 255 - i * 37).collect();
+            let name = format!("bytes-{len}");
+            let source = module_source_code(&name, JsModuleKind::Bytes, &bytes).unwrap();
+            let module = rquickjs::Module::declare(ctx.clone(), name, source).unwrap();
+            let (module, promise) = module.eval().unwrap();
+            promise.finish::<()>().unwrap();
+            let decoded: rquickjs::TypedArray<u8> = module.get("default").unwrap();
+            assert_eq!(decoded.as_bytes().unwrap(), &bytes[..]);
+        }
+    });
+}
+
+#[test]
+fn test_dynamic_module_loader_bytes_entry() {
+    test_with(|ctx| {
+        let modules = Arc::new(RwLock::new(HashMap::<String, DynamicModuleEntry>::new()));
+        modules.write().unwrap().insert(
+            "blob.bin".to_string(),
+            DynamicModuleEntry::Bytes(vec![0, 1, 254, 255]),
+        );
+        let _ = ctx.store_userdata(modules);
+
+        let mut loader = DynamicModuleLoader::default();
+        let module = loader.load(&ctx, "blob.bin", None).unwrap();
+        let (module, promise) = module.eval().unwrap();
+        promise.finish::<()>().unwrap();
+        let bytes: rquickjs::TypedArray<u8> = module.get("default").unwrap();
+        assert_eq!(bytes.as_bytes().unwrap(), &[0, 1, 254, 255]);
+    });
+}
+
 // ============================================================================
 // Module Names Tests
 // ============================================================================
@@ -551,6 +551,83 @@ async fn engine_surfaces_unhandled_background_error_on_next_operation() {
     engine.close().await.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn report_policy_streams_background_errors_without_poisoning() {
+    use crate::api::engine::JsEngineRuntimeOptions;
+    use crate::api::error::{JsUnhandledErrorOrigin, JsUnhandledErrorPolicy};
+
+    let engine = JsEngine::create(
+        Some(JsBuiltinOptions::essential()),
+        None,
+        Some(JsEngineRuntimeOptions {
+            unhandled_error_policy: Some(JsUnhandledErrorPolicy::Report),
+            ..Default::default()
+        }),
+    )
+    .await
+    .unwrap();
+    engine.init_without_bridge().await.unwrap();
+    let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let listener_reports = reports.clone();
+    engine
+        .runtime_for_test()
+        .driver
+        .add_listener(Arc::new(move |report| {
+            listener_reports.lock().unwrap().push(report);
+            true
+        }));
+
+    engine
+        .eval(
+            JsCode::Code(
+                r#"
+                setTimeout(() => {
+                  throw new TypeError('fjs reported timer error');
+                }, 10);
+                Promise.reject(new Error('fjs reported rejection'));
+                'scheduled'
+                "#
+                .to_string(),
+            ),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while reports.lock().unwrap().len() < 2 {
+        assert!(
+            Instant::now() < deadline,
+            "background errors were not streamed"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let reports = reports.lock().unwrap().clone();
+    assert!(reports.iter().all(|report| !report.poisons_engine));
+    assert!(reports.iter().any(|report| {
+        matches!(
+            report.origin,
+            JsUnhandledErrorOrigin::PromiseRejection { .. }
+        ) && report.error.to_string().contains("fjs reported rejection")
+    }));
+    assert!(reports.iter().any(|report| {
+        matches!(
+            &report.origin,
+            JsUnhandledErrorOrigin::Timer { function, delay_ms: Some(delay), .. }
+                if function == "setTimeout" && *delay == 10.0
+        ) && matches!(report.error, JsError::Type(_))
+    }));
+    assert!(matches!(
+        engine
+            .eval(JsCode::Code("1 + 1".to_string()), None)
+            .await
+            .unwrap(),
+        JsValue::Integer(2)
+    ));
+    engine.close().await.unwrap();
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn detached_promise_error_caught_in_javascript_is_not_surfaced_to_dart() {
     let engine = JsEngine::create(None, None, None).await.unwrap();
@@ -707,11 +784,14 @@ async fn engine_call_rejected_promise_error_is_not_replayed_as_background_error(
     engine.init_without_bridge().await.unwrap();
 
     engine
-        .evaluate_module(JsModule::code(
-            "/foreground-call-error".to_string(),
-            "export async function run() { throw new Error('fjs call foreground failure'); }"
-                .to_string(),
-        ))
+        .evaluate_module(
+            JsModule::code(
+                "/foreground-call-error".to_string(),
+                "export async function run() { throw new Error('fjs call foreground failure'); }"
+                    .to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
 
@@ -1204,16 +1284,19 @@ async fn engine_close_cancels_pending_module_call() {
     );
     engine.init_without_bridge().await.unwrap();
     engine
-        .evaluate_module(JsModule::code(
-            "/slow-call".to_string(),
-            r#"
+        .evaluate_module(
+            JsModule::code(
+                "/slow-call".to_string(),
+                r#"
             export async function run() {
               await new Promise((resolve) => setTimeout(resolve, 700));
               return "done";
             }
             "#
-            .to_string(),
-        ))
+                .to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
 
@@ -1408,16 +1491,19 @@ async fn engine_close_cancels_mixed_in_flight_eval_call_and_bridge_operations()
         .unwrap();
 
     engine
-        .evaluate_module(JsModule::code(
-            "/mixed-close".to_string(),
-            r#"
+        .evaluate_module(
+            JsModule::code(
+                "/mixed-close".to_string(),
+                r#"
             export async function slow() {
               await new Promise((resolve) => setTimeout(resolve, 700));
               return "call done";
             }
             "#
-            .to_string(),
-        ))
+                .to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
 
@@ -1492,16 +1578,19 @@ async fn engine_close_gracefully_allows_pending_module_call_to_complete() {
     );
     engine.init_without_bridge().await.unwrap();
     engine
-        .evaluate_module(JsModule::code(
-            "/graceful-call".to_string(),
-            r#"
+        .evaluate_module(
+            JsModule::code(
+                "/graceful-call".to_string(),
+                r#"
             export async function run() {
               await new Promise((resolve) => setTimeout(resolve, 60));
               return "done";
             }
             "#
-            .to_string(),
-        ))
+                .to_string(),
+            ),
+            None,
+        )
         .await
         .unwrap();
 
@@ -1603,7 +1692,7 @@ async fn engine_rejects_all_public_work_apis_after_close() {
     assert_engine_closed(engine.declare_new_bytecode_bundle(bundle.clone()).await);
     assert_engine_closed(engine.declare_new_module(module.clone()).await);
     assert_engine_closed(engine.declare_new_modules(vec![module.clone()]).await);
-    assert_engine_closed(engine.evaluate_module(module.clone()).await);
+    assert_engine_closed(engine.evaluate_module(module.clone(), None).await);
     assert_engine_closed(engine.evaluate_bytecode_module(bytecode.clone()).await);
     assert_engine_closed(engine.evaluate_bytecode_bundle(bundle).await);
     assert_engine_closed(engine.evaluate_script_bytecode(script).await);
@@ -1693,7 +1782,7 @@ async fn dropping_essential_bridge_engine_with_loaded_module_without_close_does_
         .evaluate_module(crate::api::source::JsModule::code(
             "/drop-test".to_string(),
             "import { value } from 'drop-fixture'; export async function run() { return await fjs.bridge_call(value); }".to_string(),
-        ))
+        ), None)
         .await
         .unwrap();
 